@@ -1,4 +1,6 @@
-use rand::Rng;
+use std::rc::Rc;
+
+use serde::{Serialize, de::DeserializeOwned};
 use spin_sdk::{
     http::{Json, Params, Request, Response, conversions::TryIntoBody},
     http_router,
@@ -6,12 +8,160 @@ use spin_sdk::{
 use utoipa::OpenApi;
 use utoipa_scalar::Scalar;
 
-use crate::{characters::Character, cultures::HeroicCulture, rand::rng_from_os_rng};
+use crate::{
+    calendar::{Date, Reckoning},
+    callings::{Calling, ShadowPath},
+    characters::{
+        Character, StandardOfLiving,
+        shadow::{ShadowSource, Virtue},
+    },
+    combat::{
+        Adversary, Encounter, Hero, Stance, Weapon,
+        adversaries::{AdversaryKind, AdversaryType},
+        fell_abilities::{Condition, Effect, FellAbility},
+    },
+    companies::{
+        Company,
+        fellowship::{ChosenUndertaking, Undertaking, UndertakingKind},
+    },
+    cultures::HeroicCulture,
+    dice::FeatDie,
+    eye::EyeAwareness,
+    journeys::{Journey, Role, Season},
+    map::{Location, Region, Terrain},
+    npcs::Attitude,
+    patrons::Patron,
+    rand::rng_from_os_rng,
+    regions::RegionType,
+    sanctuaries::Sanctuary,
+    settlements::{Folk, OtherFolk, SettlementSize},
+    solo::{Answer, Likelihood, lore::LoreColumn},
+    storage::{Collection, KeyValueStore, SpinStore, Stored},
+    tables::{Entry, Table, journey_events::JourneyEvent, rumours::RumourKind},
+    treasure::{MagicalItem, MagicalItemKind, Origin, Quality, Skill, hoards::HoardFind},
+};
+
+mod adversaries;
+mod calendar;
+mod characters;
+mod combats;
+mod companies;
+mod dice;
+mod eye;
+mod journeys;
+mod map;
+mod npcs;
+mod patrons;
+mod sanctuaries;
+mod settlements;
+mod solo;
+mod tables;
+mod treasure;
 
 #[derive(OpenApi)]
 #[openapi(
-    paths(openapi, characters, names),
-    components(schemas(Character, HeroicCulture))
+    paths(
+        openapi,
+        adversaries::list_adversaries,
+        adversaries::generate_adversary_group,
+        adversaries::list_fell_abilities,
+        calendar::get_calendar,
+        characters::create_character,
+        characters::list_characters,
+        characters::get_character,
+        characters::update_character,
+        characters::delete_character,
+        characters::gain_shadow,
+        characters::suffer_madness,
+        combats::simulate_combat,
+        companies::create_company,
+        companies::list_companies,
+        companies::get_company,
+        companies::update_company,
+        companies::delete_company,
+        companies::resolve_fellowship_phase,
+        dice::roll_dice,
+        eye::create_eye_awareness,
+        eye::get_eye_awareness,
+        eye::update_eye_awareness,
+        eye::delete_eye_awareness,
+        eye::record_eye_rolls,
+        journeys::resolve_journey,
+        map::list_locations,
+        map::find_route,
+        names,
+        npcs::create_npc,
+        patrons::list_patrons,
+        sanctuaries::list_sanctuaries,
+        settlements::create_settlement,
+        solo::ask_telling_table,
+        solo::roll_lore,
+        solo::resolve_solo_journey,
+        solo::resolve_solo_fellowship_phase,
+        tables::list_tables,
+        tables::get_table,
+        tables::upload_table,
+        tables::delete_table,
+        tables::roll_table,
+        tables::roll_journey_event,
+        tables::roll_revelation_episode,
+        tables::roll_rumour,
+        treasure::create_hoard,
+        treasure::create_magical_item
+    ),
+    components(schemas(
+        Adversary,
+        AdversaryKind,
+        AdversaryType,
+        Answer,
+        Attitude,
+        Calling,
+        Character,
+        ChosenUndertaking,
+        Company,
+        Condition,
+        Date,
+        Effect,
+        Encounter,
+        Entry,
+        EyeAwareness,
+        FeatDie,
+        FellAbility,
+        Folk,
+        HoardFind,
+        Hero,
+        HeroicCulture,
+        Journey,
+        JourneyEvent,
+        Likelihood,
+        Location,
+        LoreColumn,
+        MagicalItem,
+        MagicalItemKind,
+        Origin,
+        OtherFolk,
+        Patron,
+        Quality,
+        Reckoning,
+        Region,
+        RegionType,
+        Role,
+        RumourKind,
+        Sanctuary,
+        Season,
+        SettlementSize,
+        ShadowPath,
+        ShadowSource,
+        Skill,
+        StandardOfLiving,
+        Stance,
+        Table,
+        Terrain,
+        Undertaking,
+        UndertakingKind,
+        Virtue,
+        Weapon
+    ))
 )]
 struct ApiDoc;
 
@@ -41,6 +191,13 @@ fn scalar(_: Request, _: Params) -> Response {
 }
 
 pub fn router(req: Request) -> Response {
+    router_with_store(req, SpinStore)
+}
+
+/// Routes requests, persisting anything that needs saving in `store`
+fn router_with_store(req: Request, store: impl KeyValueStore + 'static) -> Response {
+    let store: Rc<dyn KeyValueStore> = Rc::new(store);
+
     if let Some(header) = req.header("spin-full-url") {
         println!(
             "Handling request to {}",
@@ -49,8 +206,52 @@ pub fn router(req: Request) -> Response {
     }
 
     let router = http_router! {
-        POST "/characters" => characters,
+        GET  "/adversaries" => adversaries::list_adversaries,
+        POST "/adversaries/groups" => adversaries::generate_adversary_group,
+        GET  "/calendar" => calendar::get_calendar,
+        GET  "/characters" => with_store(&store, characters::list_characters),
+        POST "/characters" => with_store(&store, characters::create_character),
+        GET  "/characters/:id" => with_store(&store, characters::get_character),
+        PUT  "/characters/:id" => with_store(&store, characters::update_character),
+        DELETE "/characters/:id" => with_store(&store, characters::delete_character),
+        POST "/characters/:id/madness" => with_store(&store, characters::suffer_madness),
+        POST "/characters/:id/shadow" => with_store(&store, characters::gain_shadow),
+        POST "/combats/simulate" => combats::simulate_combat,
+        GET  "/companies" => with_store(&store, companies::list_companies),
+        POST "/companies" => with_store(&store, companies::create_company),
+        GET  "/companies/:id" => with_store(&store, companies::get_company),
+        PUT  "/companies/:id" => with_store(&store, companies::update_company),
+        DELETE "/companies/:id" => with_store(&store, companies::delete_company),
         POST "/cultures/:culture/names" => names,
+        POST "/dice" => dice::roll_dice,
+        POST "/eye-awareness" => with_store(&store, eye::create_eye_awareness),
+        GET  "/eye-awareness/:id" => with_store(&store, eye::get_eye_awareness),
+        PUT  "/eye-awareness/:id" => with_store(&store, eye::update_eye_awareness),
+        DELETE "/eye-awareness/:id" => with_store(&store, eye::delete_eye_awareness),
+        POST "/eye-awareness/:id/rolls" => with_store(&store, eye::record_eye_rolls),
+        GET  "/fell-abilities" => adversaries::list_fell_abilities,
+        POST "/fellowship-phases" => companies::resolve_fellowship_phase,
+        POST "/journeys" => journeys::resolve_journey,
+        GET  "/locations" => map::list_locations,
+        POST "/npcs" => npcs::create_npc,
+        GET  "/patrons" => patrons::list_patrons,
+        GET  "/routes" => map::find_route,
+        GET  "/sanctuaries" => sanctuaries::list_sanctuaries,
+        POST "/settlements" => settlements::create_settlement,
+        POST "/solo/fellowship-phases" => solo::resolve_solo_fellowship_phase,
+        POST "/solo/journeys" => solo::resolve_solo_journey,
+        POST "/solo/lore" => solo::roll_lore,
+        POST "/solo/telling-table" => solo::ask_telling_table,
+        GET  "/tables" => with_store(&store, tables::list_tables),
+        GET  "/tables/:id" => with_store(&store, tables::get_table),
+        PUT  "/tables/:id" => with_store(&store, tables::upload_table),
+        DELETE "/tables/:id" => with_store(&store, tables::delete_table),
+        POST "/tables/:id/roll" => with_store(&store, tables::roll_table),
+        POST "/tables/journey-events/roll" => tables::roll_journey_event,
+        POST "/tables/revelation-episodes/roll" => tables::roll_revelation_episode,
+        POST "/tables/rumours/roll" => tables::roll_rumour,
+        POST "/treasure/hoards" => treasure::create_hoard,
+        POST "/treasure/magical" => treasure::create_magical_item,
         GET  "/api-docs" => scalar,
         GET  "/api-docs/openapi.json" => openapi,
         _   "/*"             => |_req: Request, _| {
@@ -60,24 +261,125 @@ pub fn router(req: Request) -> Response {
     router.handle(req)
 }
 
-// POST /characters
-#[utoipa::path(
-    post,
-    path = "/characters",
-    responses(
-        (status = 200, description = "Character", body = Character)
-    )
-)]
-fn characters(_req: Request, _params: Params) -> anyhow::Result<Response> {
-    let character = rng_from_os_rng().random::<Character>();
+/// Handler signature for routes that need access to the key-value store
+type StoreHandler = fn(Request, Params, &dyn KeyValueStore) -> anyhow::Result<Response>;
+
+/// Adapts a handler that needs the store into one the router can call
+fn with_store(
+    store: &Rc<dyn KeyValueStore>,
+    handler: StoreHandler,
+) -> impl Fn(Request, Params) -> anyhow::Result<Response> + 'static {
+    let store = Rc::clone(store);
+    move |req, params| handler(req, params, store.as_ref())
+}
+
+/// Empty 404 response, matching the router's fallback
+fn not_found() -> Response {
+    Response::new(404, "")
+}
 
+/// 400 response explaining what was wrong with the request
+fn bad_request(message: impl Into<String>) -> Response {
+    Response::builder()
+        .status(400)
+        .header("content-type", "text/plain")
+        .body(message.into())
+        .build()
+}
+
+/// Serialize `body` as a JSON response
+fn json_response<T: Serialize>(status: u16, body: T) -> anyhow::Result<Response> {
     Ok(Response::builder()
-        .status(200)
+        .status(status)
         .header("content-type", "application/json")
-        .body(Json(character).try_into_body()?)
+        .body(Json(body).try_into_body()?)
         .build())
 }
 
+/// Everything saved under `prefix`
+fn list_stored<T: DeserializeOwned + Serialize>(
+    store: &dyn KeyValueStore,
+    prefix: &'static str,
+) -> anyhow::Result<Response> {
+    json_response(200, Collection::new(store, prefix).list::<T>()?)
+}
+
+/// The value saved under `prefix` with the id from the path
+fn get_stored<T: DeserializeOwned + Serialize>(
+    store: &dyn KeyValueStore,
+    prefix: &'static str,
+    params: &Params,
+) -> anyhow::Result<Response> {
+    let id = id_param(params);
+    match Collection::new(store, prefix).get::<T>(id)? {
+        Some(value) => json_response(
+            200,
+            Stored {
+                id: id.to_owned(),
+                value,
+            },
+        ),
+        None => Ok(not_found()),
+    }
+}
+
+/// Replace the value saved under `prefix` with the request body
+fn replace_stored<T: DeserializeOwned + Serialize>(
+    req: &Request,
+    store: &dyn KeyValueStore,
+    prefix: &'static str,
+    params: &Params,
+) -> anyhow::Result<Response> {
+    let value = match json_body::<T>(req) {
+        Ok(value) => value,
+        Err(response) => return Ok(response),
+    };
+    match Collection::new(store, prefix).replace(id_param(params), value)? {
+        Some(stored) => json_response(200, stored),
+        None => Ok(not_found()),
+    }
+}
+
+/// Delete the value saved under `prefix` with the id from the path
+fn delete_stored(
+    store: &dyn KeyValueStore,
+    prefix: &'static str,
+    params: &Params,
+) -> anyhow::Result<Response> {
+    if Collection::new(store, prefix).remove(id_param(params))? {
+        Ok(Response::new(204, ()))
+    } else {
+        Ok(not_found())
+    }
+}
+
+/// Value of the `:id` path segment
+fn id_param(params: &Params) -> &str {
+    params.get("id").expect("ID param missing")
+}
+
+/// Deserialize the request's query string
+fn query<T: DeserializeOwned>(req: &Request) -> anyhow::Result<T> {
+    Ok(serde_urlencoded::from_str(req.query())?)
+}
+
+/// Name of the first query parameter that cannot be deserialized into `T` on its own
+fn invalid_parameter<T: DeserializeOwned>(req: &Request) -> Option<String> {
+    serde_urlencoded::from_str::<Vec<(String, String)>>(req.query())
+        .ok()?
+        .into_iter()
+        .find(|pair| {
+            serde_urlencoded::to_string([pair])
+                .is_ok_and(|single| serde_urlencoded::from_str::<T>(&single).is_err())
+        })
+        .map(|(name, _)| name)
+}
+
+/// Deserialize the request's JSON body, or explain in a 400 response what is wrong with it
+fn json_body<T: DeserializeOwned>(req: &Request) -> Result<T, Response> {
+    serde_json::from_slice(req.body()).map_err(|err| bad_request(err.to_string()))
+}
+
 // POST /cultures/:culture/names
 #[utoipa::path(
     post,
@@ -111,6 +413,8 @@ mod test {
     use spin_sdk::http::Method;
     use strum::IntoEnumIterator;
 
+    use crate::storage::MemoryStore;
+
     use super::*;
 
     #[test]
@@ -132,6 +436,20 @@ mod test {
         assert!(!body["name"].as_str().unwrap().is_empty());
     }
 
+    #[test]
+    fn saves_and_lists_characters() {
+        let store = Rc::new(MemoryStore::default());
+        let response = router_with_store(
+            Request::new(Method::Post, "/characters?save=true"),
+            Rc::clone(&store),
+        );
+        assert_eq!(response.status(), &201);
+
+        let response = router_with_store(Request::new(Method::Get, "/characters"), store);
+        let body = serde_json::from_slice::<Vec<Value>>(response.body()).unwrap();
+        assert_eq!(body.len(), 1);
+    }
+
     #[test]
     fn returns_a_name() {
         for culture in HeroicCulture::iter() {
@@ -143,6 +461,24 @@ mod test {
         }
     }
 
+    #[test]
+    fn named_tables_take_precedence_over_data_tables() {
+        let store = Rc::new(MemoryStore::default());
+        let response = router_with_store(
+            Request::new(Method::Post, "/tables/journey-events/roll?seed=2965"),
+            Rc::clone(&store),
+        );
+        let body = serde_json::from_slice::<Value>(response.body()).unwrap();
+        assert!(body["event"].is_string());
+
+        let response = router_with_store(
+            Request::new(Method::Post, "/tables/elves-of-lindon-names/roll"),
+            store,
+        );
+        let body = serde_json::from_slice::<Value>(response.body()).unwrap();
+        assert_eq!(body["table"], "elves-of-lindon-names");
+    }
+
     #[test]
     fn unknown_route() {
         let request = Request::get("/unknown")
@@ -153,16 +489,6 @@ mod test {
         assert_eq!(response.status(), &404);
     }
 
-    #[test]
-    fn character_route_returns_a_character() {
-        let response =
-            characters(Request::new(Method::Post, "/characters"), Params::new()).unwrap();
-        let body = serde_json::from_slice::<Value>(response.body()).unwrap();
-
-        assert!(!body["heroic_culture"].as_str().unwrap().is_empty());
-        assert!(!body["name"].as_str().unwrap().is_empty());
-    }
-
     #[test]
     fn name_route_returns_a_name() {
         for culture in HeroicCulture::iter() {
@@ -0,0 +1,243 @@
+use rand::seq::IteratorRandom;
+use serde::Deserialize;
+use spin_sdk::http::{Params, Request, Response};
+use strum::IntoEnumIterator;
+use utoipa::IntoParams;
+
+use crate::{
+    combat::{
+        adversaries::{
+            AdversaryDescription, AdversaryGroup, AdversaryType, VALOUR_RATINGS, catalogue,
+        },
+        fell_abilities::{FellAbilityDescription, registry},
+    },
+    companies::COMPANY_SIZES,
+};
+
+use super::{
+    bad_request, invalid_parameter, json_response, query,
+    tables::{INVALID_SEED, SeededRoll},
+};
+
+/// Options for listing adversaries
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AdversaryQuery {
+    /// Only list adversaries of this type
+    #[serde(rename = "type")]
+    #[param(rename = "type")]
+    adversary_type: Option<AdversaryType>,
+}
+
+/// Options for generating a group of adversaries
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AdversaryGroupQuery {
+    /// Type of adversaries to gather. A random type is picked when left out.
+    #[serde(rename = "type")]
+    #[param(rename = "type")]
+    adversary_type: Option<AdversaryType>,
+    /// Number of heroes in the company, between 1 and 8
+    #[serde(default = "default_size")]
+    #[param(default = 4)]
+    size: usize,
+    /// Average Valour of the heroes, between 0 and 6
+    #[serde(default = "default_valour")]
+    #[param(default = 1)]
+    valour: u32,
+    /// Seed for the random number generator, to repeat an earlier group
+    seed: Option<u64>,
+}
+
+fn default_size() -> usize {
+    4
+}
+
+fn default_valour() -> u32 {
+    1
+}
+
+// GET /adversaries
+#[utoipa::path(
+    get,
+    path = "/adversaries",
+    params(AdversaryQuery),
+    responses(
+        (status = 200, description = "Adversary catalogue", body = [AdversaryDescription]),
+        (status = 400, description = "Unknown adversary type")
+    )
+)]
+pub fn list_adversaries(req: Request, _params: Params) -> anyhow::Result<Response> {
+    let Ok(AdversaryQuery { adversary_type }) = query(&req) else {
+        return Ok(bad_request("Unknown adversary type"));
+    };
+
+    json_response(200, catalogue(adversary_type))
+}
+
+// POST /adversaries/groups
+#[utoipa::path(
+    post,
+    path = "/adversaries/groups",
+    params(AdversaryGroupQuery),
+    responses(
+        (status = 200, description = "Group of adversaries", body = SeededRoll<AdversaryGroup>),
+        (status = 400, description = "Unknown adversary type, unsupported company size or Valour")
+    )
+)]
+pub fn generate_adversary_group(req: Request, _params: Params) -> anyhow::Result<Response> {
+    let company_size = || {
+        format!(
+            "Company size must be between {} and {}",
+            COMPANY_SIZES.start(),
+            COMPANY_SIZES.end()
+        )
+    };
+    let valour_rating = || {
+        format!(
+            "Valour must be between {} and {}",
+            VALOUR_RATINGS.start(),
+            VALOUR_RATINGS.end()
+        )
+    };
+
+    let Ok(AdversaryGroupQuery {
+        adversary_type,
+        size,
+        valour,
+        seed,
+    }) = query(&req)
+    else {
+        return Ok(bad_request(
+            match invalid_parameter::<AdversaryGroupQuery>(&req).as_deref() {
+                Some("size") => company_size(),
+                Some("valour") => valour_rating(),
+                Some("seed") => INVALID_SEED.to_string(),
+                _ => "Unknown adversary type".to_string(),
+            },
+        ));
+    };
+    if !COMPANY_SIZES.contains(&size) {
+        return Ok(bad_request(company_size()));
+    }
+    if !VALOUR_RATINGS.contains(&valour) {
+        return Ok(bad_request(valour_rating()));
+    }
+    let heroes = u32::try_from(size)?;
+
+    json_response(
+        200,
+        SeededRoll::roll(seed, |rng| {
+            let adversary_type = adversary_type
+                .or_else(|| AdversaryType::iter().choose(rng))
+                .unwrap_or(AdversaryType::Orcs);
+            AdversaryGroup::generate(rng, adversary_type, heroes, valour)
+        }),
+    )
+}
+
+// GET /fell-abilities
+#[utoipa::path(
+    get,
+    path = "/fell-abilities",
+    responses(
+        (status = 200, description = "Fell Abilities adversaries can reference by id", body = [FellAbilityDescription])
+    )
+)]
+pub fn list_fell_abilities(_req: Request, _params: Params) -> anyhow::Result<Response> {
+    json_response(200, registry())
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::Value;
+    use spin_sdk::http::Method;
+
+    use super::*;
+
+    fn list(uri: &str) -> Response {
+        list_adversaries(Request::new(Method::Get, uri), Params::new()).unwrap()
+    }
+
+    fn generate(uri: &str) -> Response {
+        generate_adversary_group(Request::new(Method::Post, uri), Params::new()).unwrap()
+    }
+
+    #[test]
+    fn filters_by_type() {
+        let response = list("/adversaries?type=trolls");
+        let body = serde_json::from_slice::<Vec<Value>>(response.body()).unwrap();
+
+        assert!(!body.is_empty());
+        assert!(body.iter().all(|adversary| adversary["type"] == "trolls"));
+        assert!(body.iter().any(|adversary| adversary["id"] == "hill-troll"));
+    }
+
+    #[test]
+    fn lists_every_adversary_without_filter() {
+        let all = serde_json::from_slice::<Vec<Value>>(list("/adversaries").body()).unwrap();
+        let orcs =
+            serde_json::from_slice::<Vec<Value>>(list("/adversaries?type=orcs").body()).unwrap();
+
+        assert!(all.len() > orcs.len());
+    }
+
+    #[test]
+    fn lists_fell_abilities_with_effects() {
+        let response =
+            list_fell_abilities(Request::new(Method::Get, "/fell-abilities"), Params::new())
+                .unwrap();
+        let body = serde_json::from_slice::<Vec<Value>>(response.body()).unwrap();
+
+        let toughness = body
+            .iter()
+            .find(|ability| ability["id"] == "hideous-toughness")
+            .unwrap();
+        assert_eq!(toughness["effect"]["kind"], "ignore-wound");
+        assert_eq!(toughness["effect"]["hate_cost"], 1);
+    }
+
+    #[test]
+    fn unknown_type_is_bad_request() {
+        assert_eq!(list("/adversaries?type=dragons").status(), &400);
+        assert_eq!(generate("/adversaries/groups?type=dragons").status(), &400);
+    }
+
+    #[test]
+    fn generates_seeded_group() {
+        let first = generate("/adversaries/groups?type=wolves&size=5&seed=2965");
+        let second = generate("/adversaries/groups?type=wolves&size=5&seed=2965");
+        let body = serde_json::from_slice::<Value>(first.body()).unwrap();
+
+        assert_eq!(first.body(), second.body());
+        assert_eq!(body["type"], "wolves");
+        assert!(!body["adversaries"].as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_unsupported_company_size() {
+        assert_eq!(generate("/adversaries/groups?size=12").status(), &400);
+    }
+
+    #[test]
+    fn rejects_unsupported_valour() {
+        let response = generate("/adversaries/groups?valour=4000000000");
+        assert_eq!(response.status(), &400);
+        assert!(String::from_utf8_lossy(response.body()).starts_with("Valour"));
+        assert_eq!(generate("/adversaries/groups?valour=7").status(), &400);
+        assert_eq!(generate("/adversaries/groups?valour=6").status(), &200);
+    }
+
+    #[test]
+    fn reports_the_invalid_parameter() {
+        let message = |uri| String::from_utf8_lossy(generate(uri).body()).into_owned();
+
+        assert!(message("/adversaries/groups?type=orcs&size=many").starts_with("Company size"));
+        assert!(message("/adversaries/groups?valour=-1").starts_with("Valour"));
+        assert!(message("/adversaries/groups?seed=x").starts_with("Seed"));
+        assert_eq!(
+            message("/adversaries/groups?size=4&type=dragons"),
+            "Unknown adversary type"
+        );
+    }
+}
@@ -0,0 +1,84 @@
+use serde::Deserialize;
+use spin_sdk::http::{Params, Request, Response};
+use utoipa::IntoParams;
+
+use crate::calendar::{CalendarDay, Date, Reckoning};
+
+use super::{bad_request, json_response, query};
+
+/// Options for looking up a date
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CalendarQuery {
+    /// Date to look up, such as "22 Halimath 2965", or the start of the default campaign
+    date: Option<String>,
+    /// Reckoning the date is written in, or any reckoning if not given
+    reckoning: Option<Reckoning>,
+}
+
+// GET /calendar
+#[utoipa::path(
+    get,
+    path = "/calendar",
+    params(CalendarQuery),
+    responses(
+        (status = 200, description = "The date in every reckoning, with its season", body = CalendarDay),
+        (status = 400, description = "Unknown date or reckoning")
+    )
+)]
+pub fn get_calendar(req: Request, _params: Params) -> anyhow::Result<Response> {
+    let Ok(CalendarQuery { date, reckoning }) = query(&req) else {
+        return Ok(bad_request("Unknown reckoning"));
+    };
+
+    let date = match (date, reckoning) {
+        (None, _) => Ok(Date::campaign_start()),
+        (Some(date), None) => date.parse(),
+        (Some(date), Some(reckoning)) => Date::parse(&date, reckoning),
+    };
+    match date {
+        Ok(date) => json_response(200, CalendarDay::from(date)),
+        Err(err) => Ok(bad_request(err)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::Value;
+    use spin_sdk::http::Method;
+
+    use super::*;
+
+    fn get(uri: &str) -> Response {
+        get_calendar(Request::new(Method::Get, uri), Params::new()).unwrap()
+    }
+
+    #[test]
+    fn defaults_to_campaign_start() {
+        let response = get("/calendar");
+        let body = serde_json::from_slice::<Value>(response.body()).unwrap();
+
+        assert_eq!(response.status(), &200);
+        assert_eq!(body["date"], "1 Astron 2965");
+        assert_eq!(body["shire"]["text"], "1 Astron 1365");
+        assert_eq!(body["season"], "spring");
+    }
+
+    #[test]
+    fn converts_date_in_given_reckoning() {
+        let response = get("/calendar?date=1%20Narvinye%202965&reckoning=kings");
+        let body = serde_json::from_slice::<Value>(response.body()).unwrap();
+
+        assert_eq!(response.status(), &200);
+        assert_eq!(body["date"], "1 Afteryule 2965");
+        assert_eq!(body["stewards"]["text"], "1 Narvinyë 2965");
+        assert_eq!(body["season"], "winter");
+        assert_eq!(body["hexes_per_day"], 1);
+    }
+
+    #[test]
+    fn unknown_date_is_bad_request() {
+        assert_eq!(get("/calendar?date=32%20Rethe%202965").status(), &400);
+        assert_eq!(get("/calendar?reckoning=elvish").status(), &400);
+    }
+}
@@ -0,0 +1,502 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use spin_sdk::http::{Params, Request, Response};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    characters::{
+        Character,
+        shadow::{BoutOfMadness, ShadowSource, ShadowTest},
+    },
+    rand::rng_from_os_rng,
+    storage::{Collection, KeyValueStore, Stored},
+};
+
+use super::{
+    bad_request, delete_stored, get_stored, id_param, json_body, json_response, list_stored,
+    not_found, query, replace_stored,
+    tables::{RollQuery, SeededRoll},
+};
+
+/// Key prefix saved characters are stored under
+const PREFIX: &str = "characters";
+
+/// Options for generating a character
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CreateCharacterQuery {
+    /// Save the generated character and return it with its id
+    #[serde(default)]
+    save: bool,
+}
+
+// POST /characters
+#[utoipa::path(
+    post,
+    path = "/characters",
+    params(CreateCharacterQuery),
+    responses(
+        (status = 200, description = "Character", body = Character),
+        (status = 201, description = "Saved character", body = Stored<Character>)
+    )
+)]
+pub fn create_character(
+    req: Request,
+    _params: Params,
+    store: &dyn KeyValueStore,
+) -> anyhow::Result<Response> {
+    let CreateCharacterQuery { save } = query(&req)?;
+    let character = rng_from_os_rng().random::<Character>();
+
+    if save {
+        json_response(201, Collection::new(store, PREFIX).insert(character)?)
+    } else {
+        json_response(200, character)
+    }
+}
+
+// GET /characters
+#[utoipa::path(
+    get,
+    path = "/characters",
+    responses(
+        (status = 200, description = "Saved characters", body = [Stored<Character>])
+    )
+)]
+pub fn list_characters(
+    _req: Request,
+    _params: Params,
+    store: &dyn KeyValueStore,
+) -> anyhow::Result<Response> {
+    list_stored::<Character>(store, PREFIX)
+}
+
+// GET /characters/:id
+#[utoipa::path(
+    get,
+    path = "/characters/{id}",
+    responses(
+        (status = 200, description = "Saved character", body = Stored<Character>),
+        (status = 404, description = "No character saved with this id")
+    ),
+    params(
+        ("id" = String, Path, description = "Id of the saved character"),
+    )
+)]
+pub fn get_character(
+    _req: Request,
+    params: Params,
+    store: &dyn KeyValueStore,
+) -> anyhow::Result<Response> {
+    get_stored::<Character>(store, PREFIX, &params)
+}
+
+// PUT /characters/:id
+#[utoipa::path(
+    put,
+    path = "/characters/{id}",
+    request_body = Character,
+    responses(
+        (status = 200, description = "Updated character", body = Stored<Character>),
+        (status = 400, description = "Malformed request body"),
+        (status = 404, description = "No character saved with this id")
+    ),
+    params(
+        ("id" = String, Path, description = "Id of the saved character"),
+    )
+)]
+pub fn update_character(
+    req: Request,
+    params: Params,
+    store: &dyn KeyValueStore,
+) -> anyhow::Result<Response> {
+    replace_stored::<Character>(&req, store, PREFIX, &params)
+}
+
+// DELETE /characters/:id
+#[utoipa::path(
+    delete,
+    path = "/characters/{id}",
+    responses(
+        (status = 204, description = "Character deleted"),
+        (status = 404, description = "No character saved with this id")
+    ),
+    params(
+        ("id" = String, Path, description = "Id of the saved character"),
+    )
+)]
+pub fn delete_character(
+    _req: Request,
+    params: Params,
+    store: &dyn KeyValueStore,
+) -> anyhow::Result<Response> {
+    delete_stored(store, PREFIX, &params)
+}
+
+/// Shadow a saved hero is exposed to
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ShadowExposure {
+    source: ShadowSource,
+    /// Shadow points gained unless the hero resists
+    points: u8,
+}
+
+/// A saved hero after a Shadow test
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ShadowUpdate {
+    character: Stored<Character>,
+    test: ShadowTest,
+}
+
+/// A saved hero after a bout of madness
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MadnessUpdate {
+    character: Stored<Character>,
+    madness: BoutOfMadness,
+}
+
+// POST /characters/:id/shadow
+#[utoipa::path(
+    post,
+    path = "/characters/{id}/shadow",
+    request_body = ShadowExposure,
+    responses(
+        (status = 200, description = "Shadow test and the updated character", body = SeededRoll<ShadowUpdate>),
+        (status = 400, description = "Seed is not a number or malformed request body"),
+        (status = 404, description = "No character saved with this id")
+    ),
+    params(
+        ("id" = String, Path, description = "Id of the saved character"),
+        RollQuery,
+    )
+)]
+pub fn gain_shadow(
+    req: Request,
+    params: Params,
+    store: &dyn KeyValueStore,
+) -> anyhow::Result<Response> {
+    let RollQuery { seed } = match RollQuery::from_request(&req) {
+        Ok(query) => query,
+        Err(response) => return Ok(response),
+    };
+    let ShadowExposure { source, points } = match json_body(&req) {
+        Ok(exposure) => exposure,
+        Err(response) => return Ok(response),
+    };
+    let id = id_param(&params);
+    let characters = Collection::new(store, PREFIX);
+    let Some(mut character) = characters.get::<Character>(id)? else {
+        return Ok(not_found());
+    };
+
+    let roll = SeededRoll::roll(seed, |rng| character.shadow_test(rng, source, points));
+    let Some(character) = characters.replace(id, character)? else {
+        return Ok(not_found());
+    };
+
+    json_response(200, roll.map(|test| ShadowUpdate { character, test }))
+}
+
+// POST /characters/:id/madness
+#[utoipa::path(
+    post,
+    path = "/characters/{id}/madness",
+    responses(
+        (status = 200, description = "Bout of madness and the updated character", body = MadnessUpdate),
+        (status = 400, description = "The character is not Miserable"),
+        (status = 404, description = "No character saved with this id")
+    ),
+    params(
+        ("id" = String, Path, description = "Id of the saved character"),
+    )
+)]
+pub fn suffer_madness(
+    _req: Request,
+    params: Params,
+    store: &dyn KeyValueStore,
+) -> anyhow::Result<Response> {
+    let id = id_param(&params);
+    let characters = Collection::new(store, PREFIX);
+    let Some(mut character) = characters.get::<Character>(id)? else {
+        return Ok(not_found());
+    };
+    if !character.is_miserable() {
+        return Ok(bad_request(format!(
+            "{} is not Miserable, and cannot suffer a bout of madness",
+            character.name()
+        )));
+    }
+
+    let madness = character.bout_of_madness();
+    let Some(character) = characters.replace(id, character)? else {
+        return Ok(not_found());
+    };
+
+    json_response(200, MadnessUpdate { character, madness })
+}
+
+#[cfg(test)]
+mod test {
+    use routefinder::Capture;
+    use serde_json::Value;
+    use spin_sdk::http::Method;
+
+    use crate::storage::MemoryStore;
+
+    use super::*;
+
+    fn id(id: &str) -> Params {
+        Params::from_iter([Capture::new("id", id.to_owned())])
+    }
+
+    fn save_character(store: &MemoryStore) -> Value {
+        let response = create_character(
+            Request::new(Method::Post, "/characters?save=true"),
+            Params::new(),
+            store,
+        )
+        .unwrap();
+        assert_eq!(response.status(), &201);
+        serde_json::from_slice::<Value>(response.body()).unwrap()
+    }
+
+    #[test]
+    fn character_route_returns_a_character() {
+        let response = create_character(
+            Request::new(Method::Post, "/characters"),
+            Params::new(),
+            &MemoryStore::default(),
+        )
+        .unwrap();
+        let body = serde_json::from_slice::<Value>(response.body()).unwrap();
+
+        assert!(body.get("id").is_none());
+        assert!(!body["heroic_culture"].as_str().unwrap().is_empty());
+        assert!(!body["name"].as_str().unwrap().is_empty());
+    }
+
+    #[test]
+    fn saved_character_can_be_retrieved() {
+        let store = MemoryStore::default();
+        let saved = save_character(&store);
+
+        let response = get_character(
+            Request::new(Method::Get, "/characters"),
+            id(saved["id"].as_str().unwrap()),
+            &store,
+        )
+        .unwrap();
+        let body = serde_json::from_slice::<Value>(response.body()).unwrap();
+
+        assert_eq!(response.status(), &200);
+        assert_eq!(saved, body);
+    }
+
+    #[test]
+    fn saved_characters_are_listed() {
+        let store = MemoryStore::default();
+        let saved = [save_character(&store), save_character(&store)];
+
+        let response = list_characters(
+            Request::new(Method::Get, "/characters"),
+            Params::new(),
+            &store,
+        )
+        .unwrap();
+        let body = serde_json::from_slice::<Vec<Value>>(response.body()).unwrap();
+
+        assert_eq!(body.len(), 2);
+        for character in saved {
+            assert!(body.contains(&character));
+        }
+    }
+
+    #[test]
+    fn saved_character_can_be_updated() {
+        let store = MemoryStore::default();
+        let saved = save_character(&store);
+        let character_id = saved["id"].as_str().unwrap();
+        let update =
+            r#"{"heroic_culture":"men-of-bree","calling":"warden","name":"Barliman Butterbur"}"#;
+
+        let response = update_character(
+            Request::put("/characters", update).build(),
+            id(character_id),
+            &store,
+        )
+        .unwrap();
+        assert_eq!(response.status(), &200);
+
+        let response = get_character(
+            Request::new(Method::Get, "/characters"),
+            id(character_id),
+            &store,
+        )
+        .unwrap();
+        let body = serde_json::from_slice::<Value>(response.body()).unwrap();
+        assert_eq!(body["name"], "Barliman Butterbur");
+        assert_eq!(body["heroic_culture"], "men-of-bree");
+    }
+
+    #[test]
+    fn malformed_update_is_bad_request() {
+        let store = MemoryStore::default();
+        let saved = save_character(&store);
+        let update = |body: &str| {
+            update_character(
+                Request::put("/characters", body.to_owned()).build(),
+                id(saved["id"].as_str().unwrap()),
+                &store,
+            )
+            .unwrap()
+            .status()
+            .to_owned()
+        };
+
+        assert_eq!(update(r#"{"heroic_culture": "men-of-bree""#), 400);
+        assert_eq!(
+            update(r#"{"heroic_culture": "ents", "name": "Quickbeam"}"#),
+            400
+        );
+    }
+
+    #[test]
+    fn saved_character_can_be_deleted() {
+        let store = MemoryStore::default();
+        let saved = save_character(&store);
+        let character_id = saved["id"].as_str().unwrap();
+
+        let response = delete_character(
+            Request::new(Method::Delete, "/characters"),
+            id(character_id),
+            &store,
+        )
+        .unwrap();
+        assert_eq!(response.status(), &204);
+
+        let response = get_character(
+            Request::new(Method::Get, "/characters"),
+            id(character_id),
+            &store,
+        )
+        .unwrap();
+        assert_eq!(response.status(), &404);
+    }
+
+    #[test]
+    fn shadow_is_saved() {
+        let store = MemoryStore::default();
+        let saved = save_character(&store);
+        let character_id = saved["id"].as_str().unwrap();
+
+        let response = gain_shadow(
+            Request::post(
+                "/characters/shadow?seed=3",
+                r#"{"source":"misdeeds","points":2}"#,
+            )
+            .build(),
+            id(character_id),
+            &store,
+        )
+        .unwrap();
+        let body = serde_json::from_slice::<Value>(response.body()).unwrap();
+        assert_eq!(response.status(), &200);
+        assert_eq!(body["seed"], 3);
+        assert_eq!(body["test"]["gained"], 2);
+        assert_eq!(body["character"]["shadow"], 2);
+
+        let response = get_character(
+            Request::new(Method::Get, "/characters"),
+            id(character_id),
+            &store,
+        )
+        .unwrap();
+        let body = serde_json::from_slice::<Value>(response.body()).unwrap();
+        assert_eq!(body["shadow"], 2);
+    }
+
+    #[test]
+    fn invalid_shadow_seed_is_bad_request() {
+        let store = MemoryStore::default();
+        let saved = save_character(&store);
+
+        let response = gain_shadow(
+            Request::post(
+                "/characters/shadow?seed=abc",
+                r#"{"source":"misdeeds","points":2}"#,
+            )
+            .build(),
+            id(saved["id"].as_str().unwrap()),
+            &store,
+        )
+        .unwrap();
+
+        assert_eq!(response.status(), &400);
+    }
+
+    #[test]
+    fn only_miserable_heroes_suffer_madness() {
+        let store = MemoryStore::default();
+        let saved = save_character(&store);
+        let character_id = saved["id"].as_str().unwrap();
+        let madness = || {
+            suffer_madness(
+                Request::new(Method::Post, "/characters/madness"),
+                id(character_id),
+                &store,
+            )
+            .unwrap()
+        };
+
+        assert_eq!(madness().status(), &400);
+
+        let mut miserable = saved.clone();
+        miserable["shadow"] = 12.into();
+        update_character(
+            Request::put("/characters", miserable.to_string()).build(),
+            id(character_id),
+            &store,
+        )
+        .unwrap();
+        let response = madness();
+        let body = serde_json::from_slice::<Value>(response.body()).unwrap();
+
+        assert_eq!(response.status(), &200);
+        assert_eq!(body["madness"]["shadow_scars"], 1);
+        assert_eq!(body["character"]["shadow"], 0);
+        assert_eq!(body["character"]["flaws"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn missing_characters_are_not_found() {
+        let store = MemoryStore::default();
+        let request = || Request::new(Method::Get, "/characters");
+
+        assert_eq!(
+            get_character(request(), id("missing"), &store)
+                .unwrap()
+                .status(),
+            &404
+        );
+        assert_eq!(
+            update_character(
+                Request::put(
+                    "/characters",
+                    r#"{"heroic_culture":"bardings","calling":"captain","name":"Bard"}"#
+                )
+                .build(),
+                id("missing"),
+                &store
+            )
+            .unwrap()
+            .status(),
+            &404
+        );
+        assert_eq!(
+            delete_character(request(), id("missing"), &store)
+                .unwrap()
+                .status(),
+            &404
+        );
+    }
+}
@@ -0,0 +1,174 @@
+use std::ops::RangeInclusive;
+
+use serde::Deserialize;
+use spin_sdk::http::{Params, Request, Response};
+use utoipa::IntoParams;
+
+use crate::combat::{Encounter, Simulation};
+
+use super::{
+    bad_request, invalid_parameter, json_body, json_response, query,
+    tables::{INVALID_SEED, SeededRoll},
+};
+
+/// Number of fights a single simulation can run
+const RUNS: RangeInclusive<u32> = 1..=1000;
+
+/// Options for simulating an encounter
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SimulationQuery {
+    /// Number of fights to run, from 1 to 1000
+    #[serde(default = "default_runs")]
+    #[param(default = 100)]
+    runs: u32,
+    /// Seed for the random number generator, to repeat an earlier simulation
+    seed: Option<u64>,
+}
+
+fn default_runs() -> u32 {
+    100
+}
+
+// POST /combats/simulate
+#[utoipa::path(
+    post,
+    path = "/combats/simulate",
+    params(SimulationQuery),
+    request_body = Encounter,
+    responses(
+        (status = 200, description = "Outcome of the simulated fights", body = SeededRoll<Simulation>),
+        (status = 400, description = "Invalid number of runs or seed, malformed request body or heroes sharing a name")
+    )
+)]
+pub fn simulate_combat(req: Request, _params: Params) -> anyhow::Result<Response> {
+    let fights_allowed = || {
+        format!(
+            "Simulations can run between {} and {} fights",
+            RUNS.start(),
+            RUNS.end()
+        )
+    };
+
+    let Ok(SimulationQuery { runs, seed }) = query(&req) else {
+        return Ok(bad_request(
+            match invalid_parameter::<SimulationQuery>(&req).as_deref() {
+                Some("seed") => INVALID_SEED.to_string(),
+                _ => fights_allowed(),
+            },
+        ));
+    };
+    if !RUNS.contains(&runs) {
+        return Ok(bad_request(fights_allowed()));
+    }
+    let encounter = match json_body::<Encounter>(&req) {
+        Ok(encounter) => encounter,
+        Err(response) => return Ok(response),
+    };
+    if let Some(name) = encounter.repeated_hero_name() {
+        return Ok(bad_request(format!(
+            "Heroes must have different names, but {name} appears more than once"
+        )));
+    }
+
+    json_response(
+        200,
+        SeededRoll::roll(seed, |rng| encounter.simulate(rng, runs)),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::Value;
+
+    use super::*;
+
+    const ENCOUNTER: &str = r#"{
+        "heroes": [
+            {
+                "name": "Aragorn",
+                "stance": "forward",
+                "endurance": 28,
+                "strength": 6,
+                "parry": 15,
+                "armour": 3,
+                "weapon": {"name": "Long Sword", "rating": 4, "damage": 5, "injury": 16}
+            },
+            {
+                "name": "Odo",
+                "stance": "rearward",
+                "endurance": 20,
+                "strength": 3,
+                "parry": 13,
+                "weapon": {"name": "Bow", "rating": 2, "damage": 3, "injury": 14}
+            }
+        ],
+        "adversaries": [
+            {
+                "name": "Orc Soldier",
+                "attribute_level": 3,
+                "endurance": 12,
+                "might": 1,
+                "hate": 3,
+                "parry": 1,
+                "armour": 2,
+                "weapons": [{"name": "Jagged Knife", "rating": 2, "damage": 3, "injury": 14}]
+            }
+        ]
+    }"#;
+
+    fn simulate(uri: &str) -> Response {
+        simulate_combat(Request::post(uri, ENCOUNTER).build(), Params::new()).unwrap()
+    }
+
+    #[test]
+    fn returns_win_rates() {
+        let response = simulate("/combats/simulate?runs=50&seed=2965");
+        let body = serde_json::from_slice::<Value>(response.body()).unwrap();
+
+        assert_eq!(response.status(), &200);
+        assert_eq!(body["seed"], 2965);
+        assert_eq!(body["runs"], 50);
+        assert!(body["hero_win_rate"].as_f64().unwrap() > 0.5);
+        assert!(body["average_endurance_lost"]["Aragorn"].is_f64());
+    }
+
+    #[test]
+    fn seed_repeats_simulation() {
+        let first = simulate("/combats/simulate?seed=1");
+        let second = simulate("/combats/simulate?seed=1");
+
+        assert_eq!(first.body(), second.body());
+    }
+
+    #[test]
+    fn rejects_too_many_runs() {
+        assert_eq!(simulate("/combats/simulate?runs=5000").status(), &400);
+        assert_eq!(simulate("/combats/simulate?runs=0").status(), &400);
+    }
+
+    #[test]
+    fn rejects_invalid_query_parameters() {
+        for runs in ["abc", "-1"] {
+            let response = simulate(&format!("/combats/simulate?runs={runs}"));
+            assert_eq!(response.status(), &400);
+            assert!(String::from_utf8_lossy(response.body()).starts_with("Simulations can run"));
+        }
+
+        let response = simulate("/combats/simulate?seed=abc");
+        assert_eq!(response.status(), &400);
+        assert_eq!(String::from_utf8_lossy(response.body()), INVALID_SEED);
+    }
+
+    #[test]
+    fn rejects_heroes_sharing_a_name() {
+        let encounter = ENCOUNTER.replace("Odo", "Aragorn");
+        let response = simulate_combat(
+            Request::post("/combats/simulate", encounter).build(),
+            Params::new(),
+        )
+        .unwrap();
+
+        assert_eq!(response.status(), &400);
+    }
+}
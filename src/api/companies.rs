@@ -0,0 +1,374 @@
+use serde::Deserialize;
+use serde_json::Value;
+use spin_sdk::http::{Params, Request, Response};
+use utoipa::IntoParams;
+
+use crate::{
+    companies::{
+        COMPANY_SIZES, Company,
+        fellowship::{FellowshipOutcome, FellowshipPhase},
+    },
+    rand::rng_from_os_rng,
+    sanctuaries::Sanctuary,
+    storage::{Collection, KeyValueStore, Stored},
+};
+
+use super::{
+    bad_request, delete_stored, get_stored, invalid_parameter, json_body, json_response,
+    list_stored, query, replace_stored,
+};
+
+/// Key prefix saved companies are stored under
+const PREFIX: &str = "companies";
+
+/// Options for generating a company
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CreateCompanyQuery {
+    /// Number of heroes in the company, between 1 and 8
+    #[serde(default = "default_size")]
+    #[param(default = 4)]
+    size: usize,
+    /// Find a suitable patron for the generated heroes
+    #[serde(default)]
+    patron: bool,
+    /// Choose the sanctuary most of the heroes call home as the Safe Haven
+    #[serde(default)]
+    safe_haven: bool,
+    /// Save the generated company and return it with its id
+    #[serde(default)]
+    save: bool,
+}
+
+fn default_size() -> usize {
+    4
+}
+
+// POST /companies
+#[utoipa::path(
+    post,
+    path = "/companies",
+    params(CreateCompanyQuery),
+    responses(
+        (status = 200, description = "Company", body = Company),
+        (status = 201, description = "Saved company", body = Stored<Company>),
+        (status = 400, description = "Unsupported company size, or an option that is not true or false")
+    )
+)]
+pub fn create_company(
+    req: Request,
+    _params: Params,
+    store: &dyn KeyValueStore,
+) -> anyhow::Result<Response> {
+    let company_size = || {
+        format!(
+            "Company size must be between {} and {}",
+            COMPANY_SIZES.start(),
+            COMPANY_SIZES.end()
+        )
+    };
+
+    let Ok(CreateCompanyQuery {
+        size,
+        patron,
+        safe_haven,
+        save,
+    }) = query(&req)
+    else {
+        return Ok(bad_request(
+            match invalid_parameter::<CreateCompanyQuery>(&req).as_deref() {
+                Some("size") | None => company_size(),
+                Some(option) => format!("{option} must be true or false"),
+            },
+        ));
+    };
+    if !COMPANY_SIZES.contains(&size) {
+        return Ok(bad_request(company_size()));
+    }
+
+    let mut rng = rng_from_os_rng();
+    let mut company = Company::generate(&mut rng, size);
+    if patron {
+        company.choose_patron(&mut rng);
+    }
+    if safe_haven {
+        company.choose_safe_haven();
+    }
+
+    if save {
+        json_response(201, Collection::new(store, PREFIX).insert(company)?)
+    } else {
+        json_response(200, company)
+    }
+}
+
+// GET /companies
+#[utoipa::path(
+    get,
+    path = "/companies",
+    responses(
+        (status = 200, description = "Saved companies", body = [Stored<Company>])
+    )
+)]
+pub fn list_companies(
+    _req: Request,
+    _params: Params,
+    store: &dyn KeyValueStore,
+) -> anyhow::Result<Response> {
+    list_stored::<Company>(store, PREFIX)
+}
+
+// GET /companies/:id
+#[utoipa::path(
+    get,
+    path = "/companies/{id}",
+    responses(
+        (status = 200, description = "Saved company", body = Stored<Company>),
+        (status = 404, description = "No company saved with this id")
+    ),
+    params(
+        ("id" = String, Path, description = "Id of the saved company"),
+    )
+)]
+pub fn get_company(
+    _req: Request,
+    params: Params,
+    store: &dyn KeyValueStore,
+) -> anyhow::Result<Response> {
+    get_stored::<Company>(store, PREFIX, &params)
+}
+
+/// Check the Safe Haven of a company sent in a request is a known sanctuary. Saved companies may
+/// name one that is not, but requests must not add new ones.
+fn check_safe_haven(company: &Value) -> Result<(), Response> {
+    Option::<Sanctuary>::deserialize(&company["safe_haven"])
+        .map(drop)
+        .map_err(|err| bad_request(format!("Invalid Safe Haven: {err}")))
+}
+
+// PUT /companies/:id
+#[utoipa::path(
+    put,
+    path = "/companies/{id}",
+    request_body = Company,
+    responses(
+        (status = 200, description = "Updated company", body = Stored<Company>),
+        (status = 400, description = "Malformed request body or unknown Safe Haven"),
+        (status = 404, description = "No company saved with this id")
+    ),
+    params(
+        ("id" = String, Path, description = "Id of the saved company"),
+    )
+)]
+pub fn update_company(
+    req: Request,
+    params: Params,
+    store: &dyn KeyValueStore,
+) -> anyhow::Result<Response> {
+    if let Err(response) = json_body(&req).and_then(|company| check_safe_haven(&company)) {
+        return Ok(response);
+    }
+    replace_stored::<Company>(&req, store, PREFIX, &params)
+}
+
+// DELETE /companies/:id
+#[utoipa::path(
+    delete,
+    path = "/companies/{id}",
+    responses(
+        (status = 204, description = "Company deleted"),
+        (status = 404, description = "No company saved with this id")
+    ),
+    params(
+        ("id" = String, Path, description = "Id of the saved company"),
+    )
+)]
+pub fn delete_company(
+    _req: Request,
+    params: Params,
+    store: &dyn KeyValueStore,
+) -> anyhow::Result<Response> {
+    delete_stored(store, PREFIX, &params)
+}
+
+// POST /fellowship-phases
+#[utoipa::path(
+    post,
+    path = "/fellowship-phases",
+    request_body = FellowshipPhase,
+    responses(
+        (status = 200, description = "Company after the Fellowship phase, with a log of the rules applied", body = FellowshipOutcome),
+        (status = 400, description = "Malformed request body, unknown Safe Haven or an undertaking could not be carried out")
+    )
+)]
+pub fn resolve_fellowship_phase(req: Request, _params: Params) -> anyhow::Result<Response> {
+    if let Err(response) =
+        json_body::<Value>(&req).and_then(|phase| check_safe_haven(&phase["company"]))
+    {
+        return Ok(response);
+    }
+    let phase = match json_body::<FellowshipPhase>(&req) {
+        Ok(phase) => phase,
+        Err(response) => return Ok(response),
+    };
+
+    match phase.resolve() {
+        Ok(outcome) => json_response(200, outcome),
+        Err(err) => Ok(bad_request(err.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use routefinder::Capture;
+    use spin_sdk::http::Method;
+
+    use crate::storage::MemoryStore;
+
+    use super::*;
+
+    fn create(uri: &str, store: &MemoryStore) -> Response {
+        create_company(Request::new(Method::Post, uri), Params::new(), store).unwrap()
+    }
+
+    #[test]
+    fn generates_company_of_requested_size() {
+        let response = create("/companies?size=3", &MemoryStore::default());
+        let body = serde_json::from_slice::<Value>(response.body()).unwrap();
+
+        assert_eq!(response.status(), &200);
+        assert_eq!(body["heroes"].as_array().unwrap().len(), 3);
+        assert_eq!(body["fellowship_rating"], 3);
+    }
+
+    #[test]
+    fn defaults_to_four_heroes() {
+        let response = create("/companies", &MemoryStore::default());
+        let body = serde_json::from_slice::<Value>(response.body()).unwrap();
+
+        assert_eq!(body["heroes"].as_array().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn can_choose_a_patron() {
+        let response = create("/companies?size=2&patron=true", &MemoryStore::default());
+        let body = serde_json::from_slice::<Value>(response.body()).unwrap();
+
+        assert!(body["patron"].is_string());
+        assert!(body["fellowship_rating"].as_u64().unwrap() >= 2);
+    }
+
+    #[test]
+    fn can_choose_a_safe_haven() {
+        let response = create("/companies?safe_haven=true", &MemoryStore::default());
+        let body = serde_json::from_slice::<Value>(response.body()).unwrap();
+
+        assert!(body["safe_haven"].is_string());
+    }
+
+    #[test]
+    fn rejects_unsupported_sizes() {
+        for size in ["0", "9", "-1", "four"] {
+            let response = create(&format!("/companies?size={size}"), &MemoryStore::default());
+            assert_eq!(response.status(), &400);
+            assert!(String::from_utf8_lossy(response.body()).starts_with("Company size"));
+        }
+
+        let response = create("/companies?patron=maybe", &MemoryStore::default());
+        assert_eq!(response.status(), &400);
+        assert_eq!(response.body(), b"patron must be true or false");
+    }
+
+    #[test]
+    fn saved_company_can_be_retrieved_and_deleted() {
+        let store = MemoryStore::default();
+        let response = create("/companies?save=true", &store);
+        assert_eq!(response.status(), &201);
+        let saved = serde_json::from_slice::<Value>(response.body()).unwrap();
+        let saved_id = saved["id"].as_str().unwrap().to_owned();
+        let id = || Params::from_iter([Capture::new("id", saved_id.clone())]);
+
+        let response = get_company(Request::new(Method::Get, "/companies"), id(), &store).unwrap();
+        assert_eq!(
+            serde_json::from_slice::<Value>(response.body()).unwrap(),
+            saved
+        );
+
+        let response =
+            delete_company(Request::new(Method::Delete, "/companies"), id(), &store).unwrap();
+        assert_eq!(response.status(), &204);
+
+        let response = get_company(Request::new(Method::Get, "/companies"), id(), &store).unwrap();
+        assert_eq!(response.status(), &404);
+    }
+
+    #[test]
+    fn update_with_unknown_safe_haven_is_bad_request() {
+        let store = MemoryStore::default();
+        let response = create("/companies?save=true", &store);
+        let mut saved = serde_json::from_slice::<Value>(response.body()).unwrap();
+        let saved_id = saved["id"].as_str().unwrap().to_owned();
+        let id = || Params::from_iter([Capture::new("id", saved_id.clone())]);
+        let update = |company: &Value| {
+            let request = Request::put("/companies", company.to_string()).build();
+            update_company(request, id(), &store).unwrap()
+        };
+
+        saved["safe_haven"] = "rivendel".into();
+        assert_eq!(update(&saved).status(), &400);
+
+        saved["safe_haven"] = "rivendell".into();
+        let response = update(&saved);
+        let body = serde_json::from_slice::<Value>(response.body()).unwrap();
+        assert_eq!(response.status(), &200);
+        assert_eq!(body["safe_haven"], "rivendell");
+    }
+
+    fn fellowship_phase(undertakings: &str) -> Response {
+        fellowship_phase_at("rivendell", undertakings)
+    }
+
+    fn fellowship_phase_at(safe_haven: &str, undertakings: &str) -> Response {
+        let company = create("/companies?size=2", &MemoryStore::default());
+        let mut company = serde_json::from_slice::<Value>(company.body()).unwrap();
+        company["safe_haven"] = safe_haven.into();
+        let hero = company["heroes"][0]["name"].clone();
+        let body = serde_json::json!({
+            "company": company,
+            "yule": true,
+            "undertakings": [{"hero": hero, "undertaking": undertakings}],
+        });
+
+        resolve_fellowship_phase(
+            Request::post("/fellowship-phases", body.to_string()).build(),
+            Params::new(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn resolves_fellowship_phase() {
+        let response = fellowship_phase("strengthen-fellowship");
+        let body = serde_json::from_slice::<Value>(response.body()).unwrap();
+
+        assert_eq!(response.status(), &200);
+        assert_eq!(body["company"]["fellowship_points"], 3);
+        assert!(body["log"][0].as_str().unwrap().starts_with("Yule"));
+    }
+
+    #[test]
+    fn impossible_undertaking_is_bad_request() {
+        let response = fellowship_phase("heal-scars");
+
+        assert_eq!(response.status(), &400);
+        assert!(String::from_utf8_lossy(response.body()).ends_with("has no Shadow Scars to heal"));
+    }
+
+    #[test]
+    fn unknown_safe_haven_is_bad_request() {
+        let response = fellowship_phase_at("rivendel", "strengthen-fellowship");
+
+        assert_eq!(response.status(), &400);
+        assert!(String::from_utf8_lossy(response.body()).contains("rivendel"));
+    }
+}
@@ -0,0 +1,107 @@
+use serde::Deserialize;
+use spin_sdk::http::{Params, Request, Response};
+use utoipa::IntoParams;
+
+use crate::dice::notation::{Expression, ExpressionRoll};
+
+use super::{
+    bad_request, invalid_parameter, json_response, query,
+    tables::{INVALID_SEED, SeededRoll},
+};
+
+/// Options for rolling dice
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DiceQuery {
+    /// Dice to roll, like `2d6+3`, `4dF`, `4d6kh3` or `3d6!`. A `+` must be escaped as `%2B`.
+    #[param(example = "2d6%2B3", required = true)]
+    expr: Option<String>,
+    /// Seed for the random number generator, to repeat an earlier roll
+    seed: Option<u64>,
+}
+
+// POST /dice
+#[utoipa::path(
+    post,
+    path = "/dice",
+    params(DiceQuery),
+    responses(
+        (status = 200, description = "Every die rolled, with the total of each term and of the expression", body = SeededRoll<ExpressionRoll>),
+        (status = 400, description = "Expression could not be read or seed is not a number")
+    )
+)]
+pub fn roll_dice(req: Request, _params: Params) -> anyhow::Result<Response> {
+    let Ok(DiceQuery { expr, seed }) = query(&req) else {
+        return Ok(bad_request(
+            match invalid_parameter::<DiceQuery>(&req).as_deref() {
+                Some("seed") => INVALID_SEED,
+                _ => "Dice options could not be read",
+            },
+        ));
+    };
+    let Some(expr) = expr else {
+        return Ok(bad_request("Missing dice expression"));
+    };
+    let expression = match expr.parse::<Expression>() {
+        Ok(expression) => expression,
+        Err(err) => return Ok(bad_request(err)),
+    };
+
+    json_response(200, SeededRoll::roll(seed, |rng| expression.roll(rng)))
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::Value;
+    use spin_sdk::http::Method;
+
+    use super::*;
+
+    fn roll(uri: &str) -> Response {
+        roll_dice(Request::new(Method::Post, uri), Params::new()).unwrap()
+    }
+
+    #[test]
+    fn returns_dice_and_totals() {
+        let response = roll("/dice?expr=4d6kh3%2B2&seed=2965");
+        let body = serde_json::from_slice::<Value>(response.body()).unwrap();
+
+        assert_eq!(response.status(), &200);
+        assert_eq!(body["seed"], 2965);
+        assert_eq!(body["expression"], "4d6kh3+2");
+        assert_eq!(body["terms"][0]["dice"].as_array().unwrap().len(), 4);
+        assert_eq!(body["terms"][1]["total"], 2);
+        assert_eq!(
+            body["total"],
+            body["terms"][0]["total"].as_i64().unwrap() + 2
+        );
+    }
+
+    #[test]
+    fn seed_repeats_roll() {
+        assert_eq!(
+            roll("/dice?expr=10d10!&seed=7").body(),
+            roll("/dice?expr=10d10!&seed=7").body()
+        );
+    }
+
+    #[test]
+    fn malformed_expression_is_bad_request() {
+        let response = roll("/dice?expr=2x6");
+
+        assert_eq!(response.status(), &400);
+        assert_eq!(response.body(), b"Unexpected 'x' at position 2");
+        assert_eq!(roll("/dice").status(), &400);
+    }
+
+    #[test]
+    fn invalid_query_parameters_are_named() {
+        let response = roll("/dice?seed=7");
+        assert_eq!(response.status(), &400);
+        assert_eq!(response.body(), b"Missing dice expression");
+
+        let response = roll("/dice?expr=2d6&seed=abc");
+        assert_eq!(response.status(), &400);
+        assert_eq!(response.body(), INVALID_SEED.as_bytes());
+    }
+}
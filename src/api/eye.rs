@@ -0,0 +1,304 @@
+use serde::{Deserialize, Serialize};
+use spin_sdk::http::{Params, Request, Response};
+use utoipa::ToSchema;
+
+use crate::{
+    companies::Company,
+    dice::FeatDie,
+    eye::EyeAwareness,
+    rand::rng_from_os_rng,
+    regions::RegionType,
+    storage::{Collection, KeyValueStore, Stored},
+    tables::revelation_episodes::RevelationEpisodeRoll,
+};
+
+use super::{
+    bad_request, delete_stored, get_stored, id_param, json_body, json_response, not_found,
+    replace_stored,
+};
+
+/// Key prefix Eye Awareness trackers are stored under
+const PREFIX: &str = "eye-awareness";
+
+/// Key prefix companies are stored under
+const COMPANIES: &str = "companies";
+
+/// Start tracking the Eye Awareness of a saved company
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct NewEyeAwareness {
+    /// Id of the saved company
+    company_id: String,
+    /// Type of region the company is in
+    #[serde(default)]
+    region: RegionType,
+}
+
+/// Feat dice rolled by the company since the last update
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct FeatDiceRolled {
+    feat_dice: Vec<FeatDie>,
+}
+
+/// Tracker after recording rolls
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EyeAwarenessUpdate {
+    tracker: Stored<EyeAwareness>,
+    /// Eye Awareness gained from the rolls
+    increase: u32,
+    /// Revelation Episodes drawn because the Hunt threshold was reached
+    revelation_episodes: Vec<RevelationEpisodeRoll>,
+}
+
+// POST /eye-awareness
+#[utoipa::path(
+    post,
+    path = "/eye-awareness",
+    request_body = NewEyeAwareness,
+    responses(
+        (status = 201, description = "New Eye Awareness tracker", body = Stored<EyeAwareness>),
+        (status = 400, description = "No company saved with this id")
+    )
+)]
+pub fn create_eye_awareness(
+    req: Request,
+    _params: Params,
+    store: &dyn KeyValueStore,
+) -> anyhow::Result<Response> {
+    let NewEyeAwareness { company_id, region } = match json_body(&req) {
+        Ok(eye) => eye,
+        Err(response) => return Ok(response),
+    };
+    let Some(company) = Collection::new(store, COMPANIES).get::<Company>(&company_id)? else {
+        return Ok(bad_request(format!(
+            "No company saved with id {company_id}"
+        )));
+    };
+
+    let tracker = EyeAwareness::new(company.heroes(), region);
+    json_response(201, Collection::new(store, PREFIX).insert(tracker)?)
+}
+
+// GET /eye-awareness/:id
+#[utoipa::path(
+    get,
+    path = "/eye-awareness/{id}",
+    responses(
+        (status = 200, description = "Eye Awareness tracker", body = Stored<EyeAwareness>),
+        (status = 404, description = "No tracker saved with this id")
+    ),
+    params(
+        ("id" = String, Path, description = "Id of the tracker"),
+    )
+)]
+pub fn get_eye_awareness(
+    _req: Request,
+    params: Params,
+    store: &dyn KeyValueStore,
+) -> anyhow::Result<Response> {
+    get_stored::<EyeAwareness>(store, PREFIX, &params)
+}
+
+// PUT /eye-awareness/:id
+#[utoipa::path(
+    put,
+    path = "/eye-awareness/{id}",
+    request_body = EyeAwareness,
+    responses(
+        (status = 200, description = "Updated tracker", body = Stored<EyeAwareness>),
+        (status = 400, description = "Malformed request body"),
+        (status = 404, description = "No tracker saved with this id")
+    ),
+    params(
+        ("id" = String, Path, description = "Id of the tracker"),
+    )
+)]
+pub fn update_eye_awareness(
+    req: Request,
+    params: Params,
+    store: &dyn KeyValueStore,
+) -> anyhow::Result<Response> {
+    replace_stored::<EyeAwareness>(&req, store, PREFIX, &params)
+}
+
+// DELETE /eye-awareness/:id
+#[utoipa::path(
+    delete,
+    path = "/eye-awareness/{id}",
+    responses(
+        (status = 204, description = "Tracker deleted"),
+        (status = 404, description = "No tracker saved with this id")
+    ),
+    params(
+        ("id" = String, Path, description = "Id of the tracker"),
+    )
+)]
+pub fn delete_eye_awareness(
+    _req: Request,
+    params: Params,
+    store: &dyn KeyValueStore,
+) -> anyhow::Result<Response> {
+    delete_stored(store, PREFIX, &params)
+}
+
+// POST /eye-awareness/:id/rolls
+#[utoipa::path(
+    post,
+    path = "/eye-awareness/{id}/rolls",
+    request_body = FeatDiceRolled,
+    responses(
+        (status = 200, description = "Updated tracker", body = EyeAwarenessUpdate),
+        (status = 400, description = "Malformed request body"),
+        (status = 404, description = "No tracker saved with this id")
+    ),
+    params(
+        ("id" = String, Path, description = "Id of the tracker"),
+    )
+)]
+pub fn record_eye_rolls(
+    req: Request,
+    params: Params,
+    store: &dyn KeyValueStore,
+) -> anyhow::Result<Response> {
+    let FeatDiceRolled { feat_dice } = match json_body(&req) {
+        Ok(rolled) => rolled,
+        Err(response) => return Ok(response),
+    };
+    let id = id_param(&params);
+    let trackers = Collection::new(store, PREFIX);
+    let Some(mut tracker) = trackers.get::<EyeAwareness>(id)? else {
+        return Ok(not_found());
+    };
+
+    let update = tracker.record_rolls(feat_dice);
+    let mut rng = rng_from_os_rng();
+    let revelation_episodes = (0..update.revelation_episodes)
+        .map(|_| RevelationEpisodeRoll::roll(&mut rng))
+        .collect();
+    let Some(tracker) = trackers.replace(id, tracker)? else {
+        return Ok(not_found());
+    };
+
+    json_response(
+        200,
+        EyeAwarenessUpdate {
+            tracker,
+            increase: update.increase,
+            revelation_episodes,
+        },
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use routefinder::Capture;
+    use serde_json::Value;
+    use spin_sdk::http::Method;
+
+    use crate::storage::MemoryStore;
+
+    use super::*;
+
+    fn id(id: &str) -> Params {
+        Params::from_iter([Capture::new("id", id.to_owned())])
+    }
+
+    fn create_tracker(store: &MemoryStore, region: &str) -> Value {
+        let company = Collection::new(store, COMPANIES)
+            .insert(Company::generate(&mut rng_from_os_rng(), 3))
+            .unwrap();
+        let response = create_eye_awareness(
+            Request::post(
+                "/eye-awareness",
+                format!(r#"{{"company_id":"{}","region":"{region}"}}"#, company.id),
+            )
+            .build(),
+            Params::new(),
+            store,
+        )
+        .unwrap();
+        assert_eq!(response.status(), &201);
+        serde_json::from_slice(response.body()).unwrap()
+    }
+
+    #[test]
+    fn tracker_starts_at_initial_awareness() {
+        let tracker = create_tracker(&MemoryStore::default(), "border-land");
+
+        assert_eq!(tracker["awareness"], 1);
+        assert_eq!(tracker["initial"], 1);
+        assert_eq!(tracker["region"], "border-land");
+    }
+
+    #[test]
+    fn tracker_requires_saved_company() {
+        let response = create_eye_awareness(
+            Request::post("/eye-awareness", r#"{"company_id":"missing"}"#).build(),
+            Params::new(),
+            &MemoryStore::default(),
+        )
+        .unwrap();
+
+        assert_eq!(response.status(), &400);
+    }
+
+    #[test]
+    fn eye_rolls_are_recorded() {
+        let store = MemoryStore::default();
+        let tracker = create_tracker(&store, "dark-land");
+        let tracker_id = tracker["id"].as_str().unwrap();
+
+        let response = record_eye_rolls(
+            Request::post("/eye-awareness/rolls", r#"{"feat_dice":["eye",3,"eye"]}"#).build(),
+            id(tracker_id),
+            &store,
+        )
+        .unwrap();
+        let body = serde_json::from_slice::<Value>(response.body()).unwrap();
+        assert_eq!(body["increase"], 2);
+        assert!(body["revelation_episodes"].as_array().unwrap().is_empty());
+        assert_eq!(body["tracker"]["awareness"], 3);
+
+        let response = get_eye_awareness(
+            Request::new(Method::Get, "/eye-awareness"),
+            id(tracker_id),
+            &store,
+        )
+        .unwrap();
+        let body = serde_json::from_slice::<Value>(response.body()).unwrap();
+        assert_eq!(body["awareness"], 3);
+    }
+
+    #[test]
+    fn reaching_hunt_threshold_draws_revelation_episode() {
+        let store = MemoryStore::default();
+        let tracker = create_tracker(&store, "dark-land");
+
+        let response = record_eye_rolls(
+            Request::post(
+                "/eye-awareness/rolls",
+                serde_json::json!({ "feat_dice": vec!["eye"; 11] }).to_string(),
+            )
+            .build(),
+            id(tracker["id"].as_str().unwrap()),
+            &store,
+        )
+        .unwrap();
+        let body = serde_json::from_slice::<Value>(response.body()).unwrap();
+
+        assert_eq!(body["revelation_episodes"].as_array().unwrap().len(), 1);
+        assert_eq!(body["tracker"]["awareness"], 1);
+        assert_eq!(body["tracker"]["revelation_episodes"], 1);
+    }
+
+    #[test]
+    fn rolls_against_missing_tracker_are_not_found() {
+        let response = record_eye_rolls(
+            Request::post("/eye-awareness/rolls", r#"{"feat_dice":["eye"]}"#).build(),
+            id("missing"),
+            &MemoryStore::default(),
+        )
+        .unwrap();
+
+        assert_eq!(response.status(), &404);
+    }
+}
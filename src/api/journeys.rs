@@ -0,0 +1,109 @@
+use spin_sdk::http::{Params, Request, Response};
+
+use crate::journeys::{Journey, JourneyLog, MAX_ROUTE_HEXES};
+
+use super::{
+    bad_request, json_body, json_response,
+    tables::{RollQuery, SeededRoll},
+};
+
+// POST /journeys
+#[utoipa::path(
+    post,
+    path = "/journeys",
+    params(RollQuery),
+    request_body = Journey,
+    responses(
+        (status = 200, description = "Day-by-day journey log", body = SeededRoll<JourneyLog>),
+        (status = 400, description = "Seed is not a number, malformed request body or route too long")
+    )
+)]
+pub fn resolve_journey(req: Request, _params: Params) -> anyhow::Result<Response> {
+    let RollQuery { seed } = match RollQuery::from_request(&req) {
+        Ok(query) => query,
+        Err(response) => return Ok(response),
+    };
+    let journey = match json_body::<Journey>(&req) {
+        Ok(journey) => journey,
+        Err(response) => return Ok(response),
+    };
+
+    travel(seed, &journey)
+}
+
+/// Resolve a journey, unless its route is too long to travel
+pub fn travel(seed: Option<u64>, journey: &Journey) -> anyhow::Result<Response> {
+    if journey.hexes() > u64::from(MAX_ROUTE_HEXES) {
+        return Ok(bad_request(format!(
+            "Routes can cover at most {MAX_ROUTE_HEXES} hexes"
+        )));
+    }
+
+    json_response(200, SeededRoll::roll(seed, |rng| journey.resolve(rng)))
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::Value;
+
+    use super::*;
+
+    const JOURNEY: &str = r#"{
+        "route": [
+            {"region": "border-land", "hexes": 3},
+            {"region": "wild-land", "hexes": 6}
+        ],
+        "season": "autumn",
+        "roles": {
+            "guide": {"hero": "Aragorn", "rating": 3},
+            "hunter": {"hero": "Halbarad", "rating": 2},
+            "look_out": {"hero": "Lifstan", "rating": 1},
+            "scout": {"hero": "Frár", "rating": 2, "target_number": 12}
+        }
+    }"#;
+
+    fn resolve(uri: &str) -> Value {
+        let response = resolve_journey(Request::post(uri, JOURNEY).build(), Params::new()).unwrap();
+        assert_eq!(response.status(), &200);
+        serde_json::from_slice(response.body()).unwrap()
+    }
+
+    #[test]
+    fn returns_day_by_day_log() {
+        let body = resolve("/journeys");
+
+        assert!(body["days"].as_u64().unwrap() >= 1);
+        assert!(!body["log"].as_array().unwrap().is_empty());
+        assert_eq!(body["fatigue"].as_object().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn seed_repeats_journey() {
+        assert_eq!(resolve("/journeys?seed=3"), resolve("/journeys?seed=3"));
+    }
+
+    #[test]
+    fn invalid_seed_is_bad_request() {
+        let response = resolve_journey(
+            Request::post("/journeys?seed=abc", JOURNEY).build(),
+            Params::new(),
+        )
+        .unwrap();
+
+        assert_eq!(response.status(), &400);
+    }
+
+    #[test]
+    fn oversized_route_is_bad_request() {
+        let journey = r#"{
+            "route": [
+                {"region": "wild-land", "hexes": 4294967295},
+                {"region": "wild-land", "hexes": 4294967295}
+            ]
+        }"#;
+        let response =
+            resolve_journey(Request::post("/journeys", journey).build(), Params::new()).unwrap();
+
+        assert_eq!(response.status(), &400);
+    }
+}
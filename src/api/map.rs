@@ -0,0 +1,93 @@
+use serde::Deserialize;
+use spin_sdk::http::{Params, Request, Response};
+use utoipa::IntoParams;
+
+use crate::map::{Location, LocationDescription, Route, locations};
+
+use super::{bad_request, json_response, not_found, query};
+
+/// Where the route starts and ends
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RouteQuery {
+    from: Location,
+    to: Location,
+}
+
+// GET /locations
+#[utoipa::path(
+    get,
+    path = "/locations",
+    responses(
+        (status = 200, description = "Locations on the map of Eriador", body = [LocationDescription])
+    )
+)]
+pub fn list_locations(_req: Request, _params: Params) -> anyhow::Result<Response> {
+    json_response(200, locations())
+}
+
+// GET /routes
+#[utoipa::path(
+    get,
+    path = "/routes",
+    params(RouteQuery),
+    responses(
+        (status = 200, description = "Shortest route between the locations", body = Route),
+        (status = 400, description = "Unknown location"),
+        (status = 404, description = "No route between the locations")
+    )
+)]
+pub fn find_route(req: Request, _params: Params) -> anyhow::Result<Response> {
+    let Ok(RouteQuery { from, to }) = query(&req) else {
+        return Ok(bad_request(
+            "Both `from` and `to` must name a location listed at /locations",
+        ));
+    };
+
+    match Route::find(from, to) {
+        Some(route) => json_response(200, route),
+        None => Ok(not_found()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::Value;
+    use spin_sdk::http::Method;
+
+    use super::*;
+
+    fn route(uri: &str) -> Response {
+        find_route(Request::new(Method::Get, uri), Params::new()).unwrap()
+    }
+
+    #[test]
+    fn lists_locations() {
+        let response =
+            list_locations(Request::new(Method::Get, "/locations"), Params::new()).unwrap();
+        let body = serde_json::from_slice::<Vec<Value>>(response.body()).unwrap();
+
+        assert!(
+            body.iter()
+                .any(|location| location["id"] == "bree" && location["region"] == "bree-land")
+        );
+    }
+
+    #[test]
+    fn finds_route_with_segments() {
+        let response = route("/routes?from=bree&to=rivendell");
+        let body = serde_json::from_slice::<Value>(response.body()).unwrap();
+
+        assert_eq!(response.status(), &200);
+        assert_eq!(body["stops"][0], "bree");
+        assert_eq!(body["stops"][3], "rivendell");
+        assert!(body["hexes"].as_u64().unwrap() > 0);
+        assert!(body["segments"][0]["region"].is_string());
+    }
+
+    #[test]
+    fn unknown_location_is_bad_request() {
+        assert_eq!(route("/routes?from=bree&to=mordor").status(), &400);
+        assert_eq!(route("/routes?from=bree").status(), &400);
+    }
+}
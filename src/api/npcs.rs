@@ -0,0 +1,79 @@
+use rand::Rng;
+use serde::Deserialize;
+use spin_sdk::http::{Params, Request, Response};
+use utoipa::IntoParams;
+
+use crate::{cultures::HeroicCulture, npcs::Npc};
+
+use super::{bad_request, json_response, query, tables::SeededRoll};
+
+/// Options for generating an NPC
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct NpcQuery {
+    /// Culture of the NPC. A random culture is picked when left out.
+    culture: Option<HeroicCulture>,
+    /// Seed for the random number generator, to repeat an earlier NPC
+    seed: Option<u64>,
+}
+
+// POST /npcs
+#[utoipa::path(
+    post,
+    path = "/npcs",
+    params(NpcQuery),
+    responses(
+        (status = 200, description = "Generated NPC", body = SeededRoll<Npc>),
+        (status = 400, description = "Unknown culture")
+    )
+)]
+pub fn create_npc(req: Request, _params: Params) -> anyhow::Result<Response> {
+    let Ok(NpcQuery { culture, seed }) = query(&req) else {
+        return Ok(bad_request("Unknown culture"));
+    };
+
+    json_response(
+        200,
+        SeededRoll::roll(seed, |rng| {
+            let culture = culture.unwrap_or_else(|| rng.random());
+            Npc::random(rng, culture)
+        }),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::Value;
+    use spin_sdk::http::Method;
+
+    use super::*;
+
+    fn create(uri: &str) -> Response {
+        create_npc(Request::new(Method::Post, uri), Params::new()).unwrap()
+    }
+
+    #[test]
+    fn generates_npc_of_culture() {
+        let response = create("/npcs?culture=men-of-bree");
+        let body = serde_json::from_slice::<Value>(response.body()).unwrap();
+
+        assert_eq!(response.status(), &200);
+        assert_eq!(body["culture"], "men-of-bree");
+        assert!(!body["name"].as_str().unwrap().is_empty());
+        assert!(body["occupation"].is_string());
+        assert!(body["attitude"].is_string());
+    }
+
+    #[test]
+    fn seed_repeats_npc() {
+        assert_eq!(
+            create("/npcs?seed=2965").body(),
+            create("/npcs?seed=2965").body()
+        );
+    }
+
+    #[test]
+    fn unknown_culture_is_bad_request() {
+        assert_eq!(create("/npcs?culture=orcs").status(), &400);
+    }
+}
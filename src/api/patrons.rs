@@ -0,0 +1,34 @@
+use spin_sdk::http::{Params, Request, Response};
+
+use crate::patrons::{PatronDescription, catalogue};
+
+use super::json_response;
+
+// GET /patrons
+#[utoipa::path(
+    get,
+    path = "/patrons",
+    responses(
+        (status = 200, description = "Patron catalogue", body = [PatronDescription])
+    )
+)]
+pub fn list_patrons(_req: Request, _params: Params) -> anyhow::Result<Response> {
+    json_response(200, catalogue())
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::Value;
+    use spin_sdk::http::Method;
+
+    use super::*;
+
+    #[test]
+    fn lists_all_patrons() {
+        let response = list_patrons(Request::new(Method::Get, "/patrons"), Params::new()).unwrap();
+        let body = serde_json::from_slice::<Vec<Value>>(response.body()).unwrap();
+
+        assert_eq!(body.len(), 6);
+        assert!(body.iter().any(|patron| patron["id"] == "tom-bombadil"));
+    }
+}
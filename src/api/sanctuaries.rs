@@ -0,0 +1,42 @@
+use spin_sdk::http::{Params, Request, Response};
+
+use crate::sanctuaries::{SanctuaryDescription, catalogue};
+
+use super::json_response;
+
+// GET /sanctuaries
+#[utoipa::path(
+    get,
+    path = "/sanctuaries",
+    responses(
+        (status = 200, description = "Sanctuary catalogue", body = [SanctuaryDescription])
+    )
+)]
+pub fn list_sanctuaries(_req: Request, _params: Params) -> anyhow::Result<Response> {
+    json_response(200, catalogue())
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::Value;
+    use spin_sdk::http::Method;
+
+    use super::*;
+
+    #[test]
+    fn lists_all_sanctuaries() {
+        let response =
+            list_sanctuaries(Request::new(Method::Get, "/sanctuaries"), Params::new()).unwrap();
+        let body = serde_json::from_slice::<Vec<Value>>(response.body()).unwrap();
+
+        assert_eq!(body.len(), 6);
+        let rivendell = body.iter().find(|s| s["id"] == "rivendell").unwrap();
+        assert_eq!(rivendell["location"], "rivendell");
+        assert!(
+            rivendell["undertakings"]
+                .as_array()
+                .unwrap()
+                .contains(&"heal-scars".into())
+        );
+    }
+}
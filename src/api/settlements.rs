@@ -0,0 +1,103 @@
+use serde::Deserialize;
+use spin_sdk::http::{Params, Request, Response};
+use utoipa::IntoParams;
+
+use crate::{
+    map::Region,
+    settlements::{Settlement, SettlementSize},
+    tables::rumours::CAMPAIGN_START,
+};
+
+use super::{bad_request, json_response, query, tables::SeededRoll};
+
+/// Options for generating a settlement
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SettlementQuery {
+    /// How big the settlement is
+    #[serde(default)]
+    size: SettlementSize,
+    /// Region of Eriador the settlement lies in, which decides the folk living there
+    #[serde(default = "default_region")]
+    region: Region,
+    /// Year of the Third Age, which decides the rumours going around
+    #[serde(default = "default_year")]
+    #[param(default = 2965)]
+    year: u16,
+    /// Seed for the random number generator, to repeat an earlier settlement
+    seed: Option<u64>,
+}
+
+fn default_region() -> Region {
+    Region::BreeLand
+}
+
+fn default_year() -> u16 {
+    CAMPAIGN_START
+}
+
+// POST /settlements
+#[utoipa::path(
+    post,
+    path = "/settlements",
+    params(SettlementQuery),
+    responses(
+        (status = 200, description = "Generated settlement", body = SeededRoll<Settlement>),
+        (status = 400, description = "Unknown size or region, or invalid year")
+    )
+)]
+pub fn create_settlement(req: Request, _params: Params) -> anyhow::Result<Response> {
+    let Ok(SettlementQuery {
+        size,
+        region,
+        year,
+        seed,
+    }) = query(&req)
+    else {
+        return Ok(bad_request("Unknown size or region, or invalid year"));
+    };
+
+    json_response(
+        200,
+        SeededRoll::roll(seed, |rng| Settlement::random(rng, size, region, year)),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::Value;
+    use spin_sdk::http::Method;
+
+    use super::*;
+
+    fn create(uri: &str) -> Response {
+        create_settlement(Request::new(Method::Post, uri), Params::new()).unwrap()
+    }
+
+    #[test]
+    fn generates_village_in_bree_land_by_default() {
+        let response = create("/settlements");
+        let body = serde_json::from_slice::<Value>(response.body()).unwrap();
+
+        assert_eq!(response.status(), &200);
+        assert_eq!(body["size"], "village");
+        assert_eq!(body["region"], "bree-land");
+        assert_eq!(body["folk"][0]["folk"], "men-of-bree");
+        assert!(body["inn"]["name"].as_str().unwrap().starts_with("The "));
+        assert_eq!(body["inn"]["keeper"]["occupation"], "innkeeper");
+    }
+
+    #[test]
+    fn seed_repeats_settlement() {
+        assert_eq!(
+            create("/settlements?size=town&region=the-shire&seed=2965").body(),
+            create("/settlements?size=town&region=the-shire&seed=2965").body()
+        );
+    }
+
+    #[test]
+    fn unknown_size_is_bad_request() {
+        assert_eq!(create("/settlements?size=city").status(), &400);
+        assert_eq!(create("/settlements?region=mordor").status(), &400);
+    }
+}
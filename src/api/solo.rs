@@ -0,0 +1,248 @@
+use serde::Deserialize;
+use spin_sdk::http::{Params, Request, Response};
+use utoipa::IntoParams;
+
+use crate::{
+    companies::fellowship::{FellowshipOutcome, FellowshipPhase},
+    journeys::{Journey, JourneyLog},
+    solo::{
+        Likelihood, SoloFellowshipPhase, SoloJourney, TellingRoll,
+        lore::{LoreColumn, LoreRoll},
+    },
+};
+
+use super::{
+    bad_request,
+    journeys::travel,
+    json_body, json_response, query,
+    tables::{RollQuery, SeededRoll},
+};
+
+/// A question for the Telling Table
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TellingQuery {
+    /// The question asked, echoed back with the answer
+    question: Option<String>,
+    /// How likely the answer is to be yes
+    #[serde(default)]
+    likelihood: Likelihood,
+    /// Seed for the random number generator, to repeat an earlier roll
+    seed: Option<u64>,
+}
+
+// POST /solo/telling-table
+#[utoipa::path(
+    post,
+    path = "/solo/telling-table",
+    params(TellingQuery),
+    responses(
+        (status = 200, description = "Feat die rolled and the answer it gives", body = SeededRoll<TellingRoll>),
+        (status = 400, description = "Unknown likelihood")
+    )
+)]
+pub fn ask_telling_table(req: Request, _params: Params) -> anyhow::Result<Response> {
+    let Ok(TellingQuery {
+        question,
+        likelihood,
+        seed,
+    }) = query(&req)
+    else {
+        return Ok(bad_request("Unknown likelihood"));
+    };
+
+    json_response(
+        200,
+        SeededRoll::roll(seed, |rng| TellingRoll::roll(rng, question, likelihood)),
+    )
+}
+
+/// Options for rolling on the Lore tables
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct LoreQuery {
+    /// Roll on this table only, instead of all three
+    column: Option<LoreColumn>,
+    /// Seed for the random number generator, to repeat an earlier roll
+    seed: Option<u64>,
+}
+
+// POST /solo/lore
+#[utoipa::path(
+    post,
+    path = "/solo/lore",
+    params(LoreQuery),
+    responses(
+        (status = 200, description = "An action, an aspect and a focus to interpret, with the dice that picked them", body = SeededRoll<LoreRoll>),
+        (status = 400, description = "Unknown Lore table")
+    )
+)]
+pub fn roll_lore(req: Request, _params: Params) -> anyhow::Result<Response> {
+    let Ok(LoreQuery { column, seed }) = query(&req) else {
+        return Ok(bad_request("Unknown Lore table"));
+    };
+
+    json_response(
+        200,
+        SeededRoll::roll(seed, |rng| LoreRoll::roll(rng, column)),
+    )
+}
+
+// POST /solo/journeys
+#[utoipa::path(
+    post,
+    path = "/solo/journeys",
+    params(RollQuery),
+    request_body = SoloJourney,
+    responses(
+        (status = 200, description = "Day-by-day log of the lone hero's journey", body = SeededRoll<JourneyLog>),
+        (status = 400, description = "Seed is not a number, malformed request body or route too long")
+    )
+)]
+pub fn resolve_solo_journey(req: Request, _params: Params) -> anyhow::Result<Response> {
+    let RollQuery { seed } = match RollQuery::from_request(&req) {
+        Ok(query) => query,
+        Err(response) => return Ok(response),
+    };
+    let journey = match json_body::<SoloJourney>(&req) {
+        Ok(journey) => Journey::from(journey),
+        Err(response) => return Ok(response),
+    };
+
+    travel(seed, &journey)
+}
+
+// POST /solo/fellowship-phases
+#[utoipa::path(
+    post,
+    path = "/solo/fellowship-phases",
+    request_body = SoloFellowshipPhase,
+    responses(
+        (status = 200, description = "The hero at the end of the phase, and a log of the rules applied", body = FellowshipOutcome),
+        (status = 400, description = "Undertaking not possible for a lone hero or at the Safe Haven")
+    )
+)]
+pub fn resolve_solo_fellowship_phase(req: Request, _params: Params) -> anyhow::Result<Response> {
+    let phase = match json_body::<SoloFellowshipPhase>(&req) {
+        Ok(phase) => phase,
+        Err(response) => return Ok(response),
+    };
+
+    match FellowshipPhase::try_from(phase).and_then(FellowshipPhase::resolve) {
+        Ok(outcome) => json_response(200, outcome),
+        Err(err) => Ok(bad_request(err.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::Value;
+    use spin_sdk::http::Method;
+
+    use super::*;
+
+    fn body(response: &Response) -> Value {
+        serde_json::from_slice(response.body()).unwrap()
+    }
+
+    #[test]
+    fn answers_with_the_feat_die() {
+        let response = ask_telling_table(
+            Request::new(
+                Method::Post,
+                "/solo/telling-table?question=Is%20the%20bridge%20guarded&likelihood=likely&seed=7",
+            ),
+            Params::new(),
+        )
+        .unwrap();
+        let body = body(&response);
+
+        assert_eq!(response.status(), &200);
+        assert_eq!(body["question"], "Is the bridge guarded");
+        assert_eq!(body["likelihood"], "likely");
+        assert_eq!(body["target_number"], 4);
+        assert!(body["feat_die"].is_number() || body["feat_die"].is_string());
+        assert!(body["answer"].is_string());
+    }
+
+    #[test]
+    fn unknown_likelihood_is_bad_request() {
+        let response = ask_telling_table(
+            Request::new(Method::Post, "/solo/telling-table?likelihood=perhaps"),
+            Params::new(),
+        )
+        .unwrap();
+
+        assert_eq!(response.status(), &400);
+    }
+
+    #[test]
+    fn rolls_lore_words() {
+        let roll = |uri| roll_lore(Request::new(Method::Post, uri), Params::new()).unwrap();
+        let response = roll("/solo/lore?seed=12");
+
+        let body = body(&response);
+
+        assert_eq!(response.status(), &200);
+        for column in ["action", "aspect", "focus"] {
+            assert!(body[column]["word"].is_string());
+            assert!(body[column]["success_die"].is_number());
+        }
+        assert_eq!(response.body(), roll("/solo/lore?seed=12").body());
+    }
+
+    #[test]
+    fn rolls_single_lore_column() {
+        let roll = |uri| roll_lore(Request::new(Method::Post, uri), Params::new()).unwrap();
+        let body = body(&roll("/solo/lore?column=focus"));
+
+        assert!(body["focus"]["word"].is_string());
+        assert!(body.get("action").is_none());
+        assert_eq!(roll("/solo/lore?column=verb").status(), &400);
+    }
+
+    #[test]
+    fn resolves_solo_journey() {
+        let response = resolve_solo_journey(
+            Request::post(
+                "/solo/journeys?seed=3",
+                r#"{"route": [{"region": "border-land", "hexes": 6}], "hero": "Aragorn"}"#,
+            )
+            .build(),
+            Params::new(),
+        )
+        .unwrap();
+
+        assert_eq!(response.status(), &200);
+        assert!(body(&response)["fatigue"]["Aragorn"].is_number());
+    }
+
+    #[test]
+    fn resolves_solo_fellowship_phase() {
+        let phase = |undertaking: &str| {
+            let body = format!(
+                r#"{{
+                    "hero": {{"heroic_culture": "hobbits-of-the-shire", "calling": "scholar", "name": "Marigold"}},
+                    "safe_haven": "the-shire",
+                    "undertaking": {undertaking}
+                }}"#
+            );
+            resolve_solo_fellowship_phase(
+                Request::post("/solo/fellowship-phases", body).build(),
+                Params::new(),
+            )
+            .unwrap()
+        };
+
+        let response =
+            phase(r#"{"undertaking": "write-a-song", "title": "The Road Goes Ever On"}"#);
+        assert_eq!(response.status(), &200);
+        assert_eq!(
+            body(&response)["company"]["songs"][0],
+            "The Road Goes Ever On"
+        );
+
+        let response = phase(r#"{"undertaking": "strengthen-fellowship"}"#);
+        assert_eq!(response.status(), &400);
+    }
+}
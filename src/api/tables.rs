@@ -0,0 +1,722 @@
+use serde::{Deserialize, Serialize};
+use spin_sdk::http::{Params, Request, Response};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    map::Region,
+    rand::{rng_from_seed, seed_or_random},
+    regions::RegionType,
+    storage::{Collection, KeyValueStore},
+    tables::{
+        Table, TableRoll, TableSummary, Tables,
+        custom::{self, TableError},
+        journey_events::JourneyEventRoll,
+        revelation_episodes::RevelationEpisodeRoll,
+        rumours::{CAMPAIGN_START, RumourKind, RumourRoll},
+    },
+};
+
+use super::{
+    bad_request, delete_stored, id_param, invalid_parameter, json_response, not_found, query,
+};
+
+/// Options for rolling on a table
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RollQuery {
+    /// Seed for the random number generator, to repeat an earlier roll
+    pub seed: Option<u64>,
+}
+
+/// Explanation given when the seed of a roll is not a number
+pub const INVALID_SEED: &str = "Seed must be a whole number";
+
+impl RollQuery {
+    /// Read the options from the request's query string, or a 400 response explaining what is
+    /// wrong with them
+    pub fn from_request(req: &Request) -> Result<Self, Response> {
+        query(req).map_err(|_| bad_request(INVALID_SEED))
+    }
+}
+
+/// Result of a roll, along with the seed to reproduce it
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SeededRoll<T> {
+    seed: u64,
+    #[serde(flatten)]
+    result: T,
+}
+
+impl<T> SeededRoll<T> {
+    /// Roll with the requested seed, or a new random one
+    pub fn roll(seed: Option<u64>, roll: impl FnOnce(&mut dyn rand::RngCore) -> T) -> Self {
+        let seed = seed_or_random(seed);
+        Self {
+            seed,
+            result: roll(&mut rng_from_seed(seed)),
+        }
+    }
+
+    /// Transform the result, keeping the seed it was rolled with
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> SeededRoll<U> {
+        SeededRoll {
+            seed: self.seed,
+            result: f(self.result),
+        }
+    }
+
+    /// Roll with the requested seed, or a new random one, for rolls that can fail
+    pub fn try_roll(
+        seed: Option<u64>,
+        roll: impl FnOnce(&mut dyn rand::RngCore) -> anyhow::Result<T>,
+    ) -> anyhow::Result<Self> {
+        let seed = seed_or_random(seed);
+        Ok(Self {
+            seed,
+            result: roll(&mut rng_from_seed(seed))?,
+        })
+    }
+}
+
+/// Key prefix tables uploaded by Loremasters are stored under
+const PREFIX: &str = "tables";
+
+/// Ids of built-in tables with their own roll routes, which uploads would hide
+const RESERVED_IDS: &[&str] = &["journey-events", "revelation-episodes", "rumours"];
+
+/// Built-in tables along with those uploaded by Loremasters
+fn all_tables(store: &dyn KeyValueStore) -> anyhow::Result<Tables> {
+    let mut tables = Tables::builtin();
+    tables.extend(
+        Collection::new(store, PREFIX)
+            .list::<Table>()?
+            .into_iter()
+            .map(|stored| stored.value),
+    );
+    Ok(tables)
+}
+
+// GET /tables
+#[utoipa::path(
+    get,
+    path = "/tables",
+    responses(
+        (status = 200, description = "Built-in and uploaded tables that can be rolled on", body = [TableSummary])
+    )
+)]
+pub fn list_tables(
+    _req: Request,
+    _params: Params,
+    store: &dyn KeyValueStore,
+) -> anyhow::Result<Response> {
+    json_response(200, all_tables(store)?.summaries().collect::<Vec<_>>())
+}
+
+// GET /tables/:id
+#[utoipa::path(
+    get,
+    path = "/tables/{id}",
+    params(
+        ("id" = String, Path, description = "Id of the table")
+    ),
+    responses(
+        (status = 200, description = "Table with its entries", body = Table),
+        (status = 404, description = "Unknown table")
+    )
+)]
+pub fn get_table(
+    _req: Request,
+    params: Params,
+    store: &dyn KeyValueStore,
+) -> anyhow::Result<Response> {
+    match all_tables(store)?.get(id_param(&params)) {
+        Some(table) => json_response(200, table),
+        None => Ok(not_found()),
+    }
+}
+
+// PUT /tables/:id
+#[utoipa::path(
+    put,
+    path = "/tables/{id}",
+    params(
+        ("id" = String, Path, description = "Id other tables can reference the table by")
+    ),
+    request_body(
+        description = "Table as JSON, or as plain text with one entry per line",
+        content(
+            (Table = "application/json"),
+            (String = "text/plain", example = "@name Weather\n@dice 1d6\n1-4: Rain\n5-6: Fog")
+        )
+    ),
+    responses(
+        (status = 200, description = "Uploaded table replaced", body = Table),
+        (status = 201, description = "Table uploaded", body = Table),
+        (status = 400, description = "Problems found with the table, by line or entry", body = [TableError])
+    )
+)]
+pub fn upload_table(
+    req: Request,
+    params: Params,
+    store: &dyn KeyValueStore,
+) -> anyhow::Result<Response> {
+    let id = id_param(&params);
+    if Tables::builtin().get(id).is_some() || RESERVED_IDS.contains(&id) {
+        return Ok(bad_request(format!(
+            "Built-in table {id} cannot be replaced"
+        )));
+    }
+
+    let is_text = req
+        .header("content-type")
+        .and_then(|header| header.as_str())
+        .is_some_and(|content_type| content_type.starts_with("text/plain"));
+    let tables = all_tables(store)?;
+    let table = if is_text {
+        custom::parse_lines(id, &String::from_utf8_lossy(req.body()), &tables)
+    } else {
+        custom::parse_json(id, req.body(), &tables)
+    };
+
+    match table {
+        Ok(table) => {
+            let collection = Collection::new(store, PREFIX);
+            let status = if collection.get::<Table>(id)?.is_some() {
+                200
+            } else {
+                201
+            };
+            json_response(status, collection.set(id, table)?.value)
+        }
+        Err(errors) => json_response(400, errors),
+    }
+}
+
+// DELETE /tables/:id
+#[utoipa::path(
+    delete,
+    path = "/tables/{id}",
+    params(
+        ("id" = String, Path, description = "Id of the uploaded table")
+    ),
+    responses(
+        (status = 204, description = "Table deleted"),
+        (status = 404, description = "No table uploaded with this id")
+    )
+)]
+pub fn delete_table(
+    _req: Request,
+    params: Params,
+    store: &dyn KeyValueStore,
+) -> anyhow::Result<Response> {
+    delete_stored(store, PREFIX, &params)
+}
+
+// POST /tables/:id/roll
+#[utoipa::path(
+    post,
+    path = "/tables/{id}/roll",
+    params(
+        ("id" = String, Path, description = "Id of the table"),
+        RollQuery
+    ),
+    responses(
+        (status = 200, description = "Entry rolled, with every table it references rolled too", body = SeededRoll<TableRoll>),
+        (status = 400, description = "Seed is not a number, or the table references a table that does not exist, nests too deep or expands too far"),
+        (status = 404, description = "Unknown table")
+    )
+)]
+pub fn roll_table(
+    req: Request,
+    params: Params,
+    store: &dyn KeyValueStore,
+) -> anyhow::Result<Response> {
+    let RollQuery { seed } = match RollQuery::from_request(&req) {
+        Ok(query) => query,
+        Err(response) => return Ok(response),
+    };
+    let id = id_param(&params);
+    let tables = all_tables(store)?;
+    if tables.get(id).is_none() {
+        return Ok(not_found());
+    }
+
+    match SeededRoll::try_roll(seed, |rng| tables.roll(rng, id)) {
+        Ok(roll) => json_response(200, roll),
+        Err(err) => Ok(bad_request(err.to_string())),
+    }
+}
+
+/// Options for rolling a Journey Event
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct JourneyEventQuery {
+    /// Seed for the random number generator, to repeat an earlier roll
+    seed: Option<u64>,
+    /// Type of region the company is travelling through, making the roll favoured or
+    /// ill-favoured
+    #[serde(default)]
+    region: RegionType,
+}
+
+// POST /tables/journey-events/roll
+#[utoipa::path(
+    post,
+    path = "/tables/journey-events/roll",
+    params(JourneyEventQuery),
+    responses(
+        (status = 200, description = "Journey Event and its detail", body = SeededRoll<JourneyEventRoll>),
+        (status = 400, description = "Unknown region type or seed is not a number")
+    )
+)]
+pub fn roll_journey_event(req: Request, _params: Params) -> anyhow::Result<Response> {
+    let Ok(JourneyEventQuery { seed, region }) = query(&req) else {
+        return Ok(bad_request(
+            match invalid_parameter::<JourneyEventQuery>(&req).as_deref() {
+                Some("seed") => INVALID_SEED,
+                _ => "Unknown region type",
+            },
+        ));
+    };
+
+    json_response(
+        200,
+        SeededRoll::roll(seed, |rng| {
+            JourneyEventRoll::roll(rng, region.event_favour())
+        }),
+    )
+}
+
+// POST /tables/revelation-episodes/roll
+#[utoipa::path(
+    post,
+    path = "/tables/revelation-episodes/roll",
+    params(RollQuery),
+    responses(
+        (status = 200, description = "Revelation Episode", body = SeededRoll<RevelationEpisodeRoll>),
+        (status = 400, description = "Seed is not a number")
+    )
+)]
+pub fn roll_revelation_episode(req: Request, _params: Params) -> anyhow::Result<Response> {
+    let RollQuery { seed } = match RollQuery::from_request(&req) {
+        Ok(query) => query,
+        Err(response) => return Ok(response),
+    };
+
+    json_response(
+        200,
+        SeededRoll::roll(seed, |rng| RevelationEpisodeRoll::roll(rng)),
+    )
+}
+
+/// Options for rolling a rumour or adventure hook
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RumourQuery {
+    /// Region of Eriador the rumour is heard in
+    #[serde(default = "default_region")]
+    region: Region,
+    /// Year of the Third Age the rumour is heard in
+    #[serde(default = "default_year")]
+    #[param(default = 2965)]
+    year: u16,
+    /// Only roll rumours or only hooks. Either can come up when left out.
+    kind: Option<RumourKind>,
+    /// Seed for the random number generator, to repeat an earlier roll
+    seed: Option<u64>,
+}
+
+fn default_region() -> Region {
+    Region::BreeLand
+}
+
+fn default_year() -> u16 {
+    CAMPAIGN_START
+}
+
+// POST /tables/rumours/roll
+#[utoipa::path(
+    post,
+    path = "/tables/rumours/roll",
+    params(RumourQuery),
+    responses(
+        (status = 200, description = "Rumour or adventure hook, with the NPCs it names", body = SeededRoll<RumourRoll>),
+        (status = 400, description = "Unknown region or kind, or invalid year")
+    )
+)]
+pub fn roll_rumour(req: Request, _params: Params) -> anyhow::Result<Response> {
+    let Ok(RumourQuery {
+        region,
+        year,
+        kind,
+        seed,
+    }) = query(&req)
+    else {
+        return Ok(bad_request("Unknown region or kind, or invalid year"));
+    };
+
+    json_response(
+        200,
+        SeededRoll::roll(seed, |rng| RumourRoll::roll(rng, region, year, kind)),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use routefinder::Capture;
+    use serde_json::Value;
+    use spin_sdk::http::Method;
+
+    use crate::storage::MemoryStore;
+
+    use super::*;
+
+    fn roll(uri: &str) -> Value {
+        let response =
+            roll_revelation_episode(Request::new(Method::Post, uri), Params::new()).unwrap();
+        assert_eq!(response.status(), &200);
+        serde_json::from_slice(response.body()).unwrap()
+    }
+
+    fn with_id(id: &str) -> Params {
+        Params::from_iter([Capture::new("id", id.to_owned())])
+    }
+
+    #[test]
+    fn lists_builtin_tables() {
+        let response = list_tables(
+            Request::new(Method::Get, "/tables"),
+            Params::new(),
+            &MemoryStore::default(),
+        )
+        .unwrap();
+        let body = serde_json::from_slice::<Vec<Value>>(response.body()).unwrap();
+
+        let names = body
+            .iter()
+            .find(|table| table["id"] == "hobbits-of-the-shire-names")
+            .unwrap();
+        assert_eq!(names["name"], "Names of the Hobbits of the Shire");
+        assert_eq!(names["entries"], 2);
+    }
+
+    #[test]
+    fn returns_table_entries() {
+        let response = get_table(
+            Request::new(Method::Get, "/tables/men-of-bree-names"),
+            with_id("men-of-bree-names"),
+            &MemoryStore::default(),
+        )
+        .unwrap();
+        let body = serde_json::from_slice::<Value>(response.body()).unwrap();
+
+        assert_eq!(response.status(), &200);
+        assert_eq!(
+            body["entries"][0]["text"],
+            "{table:men-of-bree-male-names} {table:men-of-bree-family-names}"
+        );
+    }
+
+    #[test]
+    fn rolls_nested_tables() {
+        let roll = |seed| {
+            roll_table(
+                Request::new(
+                    Method::Post,
+                    format!("/tables/rangers-of-the-north-names/roll?seed={seed}"),
+                ),
+                with_id("rangers-of-the-north-names"),
+                &MemoryStore::default(),
+            )
+            .unwrap()
+        };
+        let body = serde_json::from_slice::<Value>(roll(2965).body()).unwrap();
+
+        assert_eq!(body["table"], "rangers-of-the-north-names");
+        assert!(!body["text"].as_str().unwrap().contains('{'));
+        assert_eq!(roll(2965).body(), roll(2965).body());
+    }
+
+    #[test]
+    fn invalid_table_seed_is_bad_request() {
+        let response = roll_table(
+            Request::new(Method::Post, "/tables/men-of-bree-names/roll?seed=-1"),
+            with_id("men-of-bree-names"),
+            &MemoryStore::default(),
+        )
+        .unwrap();
+
+        assert_eq!(response.status(), &400);
+    }
+
+    #[test]
+    fn unknown_table_is_not_found() {
+        let store = MemoryStore::default();
+        let get = get_table(
+            Request::new(Method::Get, "/tables/orcs"),
+            with_id("orcs"),
+            &store,
+        )
+        .unwrap();
+        let roll = roll_table(
+            Request::new(Method::Post, "/tables/orcs/roll"),
+            with_id("orcs"),
+            &store,
+        )
+        .unwrap();
+        let delete = delete_table(
+            Request::new(Method::Delete, "/tables/orcs"),
+            with_id("orcs"),
+            &store,
+        )
+        .unwrap();
+
+        assert_eq!(get.status(), &404);
+        assert_eq!(roll.status(), &404);
+        assert_eq!(delete.status(), &404);
+    }
+
+    fn upload(store: &MemoryStore, id: &str, content_type: &str, body: &str) -> Response {
+        upload_table(
+            Request::put(format!("/tables/{id}"), body.to_owned())
+                .header("content-type", content_type)
+                .build(),
+            with_id(id),
+            store,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn uploaded_tables_roll_alongside_builtin_ones() {
+        let store = MemoryStore::default();
+        let response = upload(
+            &store,
+            "travellers",
+            "text/plain",
+            "@name Travellers\n3: Dwarves going east\n{table:elves-of-lindon-names}",
+        );
+        assert_eq!(response.status(), &201);
+        let response = upload(
+            &store,
+            "road-encounters",
+            "application/json",
+            r#"{
+                "name": "Encounters on the road",
+                "dice": "1d6",
+                "entries": [
+                    { "range": "1-3", "text": "{table:travellers}" },
+                    { "range": "4-6", "text": "Nothing but rain" }
+                ]
+            }"#,
+        );
+        assert_eq!(response.status(), &201);
+
+        let response = roll_table(
+            Request::new(Method::Post, "/tables/road-encounters/roll?seed=2965"),
+            with_id("road-encounters"),
+            &store,
+        )
+        .unwrap();
+        let body = serde_json::from_slice::<Value>(response.body()).unwrap();
+        assert_eq!(response.status(), &200);
+        assert!(!body["text"].as_str().unwrap().contains('{'));
+
+        let response =
+            list_tables(Request::new(Method::Get, "/tables"), Params::new(), &store).unwrap();
+        let body = serde_json::from_slice::<Vec<Value>>(response.body()).unwrap();
+        assert!(body.iter().any(|table| table["id"] == "road-encounters"));
+    }
+
+    #[test]
+    fn reupload_replaces_table() {
+        let store = MemoryStore::default();
+        upload(&store, "weather", "text/plain", "Rain");
+        let response = upload(&store, "weather", "text/plain", "Fog");
+        assert_eq!(response.status(), &200);
+
+        let response = get_table(
+            Request::new(Method::Get, "/tables/weather"),
+            with_id("weather"),
+            &store,
+        )
+        .unwrap();
+        let body = serde_json::from_slice::<Value>(response.body()).unwrap();
+        assert_eq!(body["entries"][0]["text"], "Fog");
+
+        let response = delete_table(
+            Request::new(Method::Delete, "/tables/weather"),
+            with_id("weather"),
+            &store,
+        )
+        .unwrap();
+        assert_eq!(response.status(), &204);
+    }
+
+    #[test]
+    fn reports_upload_errors_by_line() {
+        let response = upload(
+            &MemoryStore::default(),
+            "weather",
+            "text/plain",
+            "@dice 1d6\n1-4: Rain\nFog",
+        );
+        let body = serde_json::from_slice::<Vec<Value>>(response.body()).unwrap();
+
+        assert_eq!(response.status(), &400);
+        assert_eq!(body[0]["message"], "No entry for a roll of 5, 6");
+        assert_eq!(body[1]["line"], 3);
+    }
+
+    #[test]
+    fn rolls_expanding_too_far_are_bad_requests() {
+        let store = MemoryStore::default();
+        let collection = Collection::new(&store, PREFIX);
+        collection
+            .set("leaf", Table::list("leaf", "Leaf", &["Rain"]))
+            .unwrap();
+        for (id, below) in [("fan-1", "leaf"), ("fan-2", "fan-1"), ("fan-3", "fan-2")] {
+            let entry = format!("{{table:{below}}}").repeat(10);
+            collection
+                .set(id, Table::list(id, "Fan", &[&entry]))
+                .unwrap();
+        }
+
+        let response = roll_table(
+            Request::new(Method::Post, "/tables/fan-3/roll"),
+            with_id("fan-3"),
+            &store,
+        )
+        .unwrap();
+        assert_eq!(response.status(), &400);
+
+        let response = upload(&store, "fan-4", "text/plain", "{table:fan-3}");
+        assert_eq!(response.status(), &400);
+        let response = upload(&store, "weather", "text/plain", "Rain");
+        assert_eq!(response.status(), &201);
+    }
+
+    #[test]
+    fn builtin_tables_cannot_be_replaced() {
+        let response = upload(
+            &MemoryStore::default(),
+            "men-of-bree-names",
+            "text/plain",
+            "Bob",
+        );
+        assert_eq!(response.status(), &400);
+
+        for id in RESERVED_IDS {
+            let response = upload(&MemoryStore::default(), id, "text/plain", "Bob");
+            assert_eq!(response.status(), &400);
+        }
+    }
+
+    #[test]
+    fn returns_journey_event_with_detail() {
+        let response = roll_journey_event(
+            Request::new(
+                Method::Post,
+                "/tables/journey-events/roll?region=dark-land&seed=7",
+            ),
+            Params::new(),
+        )
+        .unwrap();
+        let body = serde_json::from_slice::<Value>(response.body()).unwrap();
+
+        assert_eq!(body["seed"], 7);
+        assert!(body["event"].is_string());
+        assert!(!body["detail"]["title"].as_str().unwrap().is_empty());
+        assert!(body["detail"]["target"].is_string());
+        assert!(body["skill"].is_string());
+    }
+
+    #[test]
+    fn rejects_unknown_region() {
+        let response = roll_journey_event(
+            Request::new(Method::Post, "/tables/journey-events/roll?region=mordor"),
+            Params::new(),
+        )
+        .unwrap();
+
+        assert_eq!(response.status(), &400);
+        assert_eq!(
+            String::from_utf8_lossy(response.body()),
+            "Unknown region type"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_journey_event_seed() {
+        let response = roll_journey_event(
+            Request::new(
+                Method::Post,
+                "/tables/journey-events/roll?region=wild-land&seed=abc",
+            ),
+            Params::new(),
+        )
+        .unwrap();
+
+        assert_eq!(response.status(), &400);
+        assert_eq!(String::from_utf8_lossy(response.body()), INVALID_SEED);
+    }
+
+    #[test]
+    fn rolls_hook_for_region_and_year() {
+        let response = roll_rumour(
+            Request::new(
+                Method::Post,
+                "/tables/rumours/roll?region=the-shire&year=3018&kind=hook&seed=7",
+            ),
+            Params::new(),
+        )
+        .unwrap();
+        let body = serde_json::from_slice::<Value>(response.body()).unwrap();
+
+        assert_eq!(response.status(), &200);
+        assert_eq!(body["kind"], "hook");
+        assert!(!body["text"].as_str().unwrap().contains('{'));
+        assert!(body["npcs"].is_array());
+    }
+
+    #[test]
+    fn rejects_unknown_rumour_kind() {
+        let response = roll_rumour(
+            Request::new(Method::Post, "/tables/rumours/roll?kind=gossip"),
+            Params::new(),
+        )
+        .unwrap();
+
+        assert_eq!(response.status(), &400);
+    }
+
+    #[test]
+    fn returns_episode_and_seed() {
+        let body = roll("/tables/revelation-episodes/roll");
+
+        assert!(body["seed"].is_u64());
+        assert!(!body["episode"]["title"].as_str().unwrap().is_empty());
+        assert!(!body["episode"]["effects"].as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn seed_repeats_roll() {
+        let first = roll("/tables/revelation-episodes/roll?seed=2965");
+        let second = roll("/tables/revelation-episodes/roll?seed=2965");
+
+        assert_eq!(first["seed"], 2965);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn invalid_seed_is_bad_request() {
+        let response = roll_revelation_episode(
+            Request::new(Method::Post, "/tables/revelation-episodes/roll?seed=abc"),
+            Params::new(),
+        )
+        .unwrap();
+
+        assert_eq!(response.status(), &400);
+        assert_eq!(response.body(), INVALID_SEED.as_bytes());
+    }
+}
@@ -0,0 +1,157 @@
+use rand::Rng;
+use serde::Deserialize;
+use spin_sdk::http::{Params, Request, Response};
+use utoipa::IntoParams;
+
+use crate::treasure::{
+    MagicalItem, MagicalItemKind, Origin,
+    hoards::{HOARD_RATINGS, Hoard},
+};
+
+use super::{bad_request, json_response, query, tables::SeededRoll};
+
+/// Options for generating magical treasure
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct MagicalTreasureQuery {
+    /// Kind of item to generate, or any kind weighted by rarity if not given
+    kind: Option<MagicalItemKind>,
+    /// Folk who made the item, or any folk if not given
+    origin: Option<Origin>,
+    /// Seed for the random number generator, to repeat an earlier roll
+    seed: Option<u64>,
+}
+
+// POST /treasure/magical
+#[utoipa::path(
+    post,
+    path = "/treasure/magical",
+    params(MagicalTreasureQuery),
+    responses(
+        (status = 200, description = "Magical item with its name, history and enchantments", body = SeededRoll<MagicalItem>),
+        (status = 400, description = "Unknown kind or origin")
+    )
+)]
+pub fn create_magical_item(req: Request, _params: Params) -> anyhow::Result<Response> {
+    let Ok(MagicalTreasureQuery { kind, origin, seed }) = query(&req) else {
+        return Ok(bad_request("Unknown kind or origin of magical treasure"));
+    };
+
+    json_response(
+        200,
+        SeededRoll::roll(seed, |rng| {
+            let kind = kind.unwrap_or_else(|| rng.random());
+            let origin = origin.unwrap_or_else(|| rng.random());
+            MagicalItem::random(rng, kind, origin)
+        }),
+    )
+}
+
+/// Options for generating a hoard
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct HoardQuery {
+    /// Rating of the hoard, from 1 to 6: the Hoard table is rolled on once per point
+    rating: u8,
+    /// Seed for the random number generator, to reveal the same hoard again
+    seed: Option<u64>,
+}
+
+// POST /treasure/hoards
+#[utoipa::path(
+    post,
+    path = "/treasure/hoards",
+    params(HoardQuery),
+    responses(
+        (status = 200, description = "Hoard with its Treasure points, magical items and a Markdown description", body = SeededRoll<Hoard>),
+        (status = 400, description = "Missing or unsupported hoard rating")
+    )
+)]
+pub fn create_hoard(req: Request, _params: Params) -> anyhow::Result<Response> {
+    let Ok(HoardQuery { rating, seed }) = query(&req) else {
+        return Ok(bad_request("Missing or invalid hoard rating"));
+    };
+    if !HOARD_RATINGS.contains(&rating) {
+        return Ok(bad_request(format!(
+            "Hoard rating must be between {} and {}",
+            HOARD_RATINGS.start(),
+            HOARD_RATINGS.end()
+        )));
+    }
+
+    json_response(
+        200,
+        SeededRoll::roll(seed, |rng| Hoard::random(rng, rating)),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::Value;
+    use spin_sdk::http::Method;
+
+    use super::*;
+
+    fn create(uri: &str) -> Response {
+        create_magical_item(Request::new(Method::Post, uri), Params::new()).unwrap()
+    }
+
+    #[test]
+    fn generates_requested_item() {
+        let response = create("/treasure/magical?kind=famous-weapon&origin=elven&seed=2965");
+        let body = serde_json::from_slice::<Value>(response.body()).unwrap();
+
+        assert_eq!(response.status(), &200);
+        assert_eq!(body["seed"], 2965);
+        assert_eq!(body["kind"], "famous-weapon");
+        assert_eq!(body["origin"], "elven");
+        assert_eq!(body["banes"].as_array().unwrap().len(), 1);
+        assert!(!body["history"].as_str().unwrap().is_empty());
+    }
+
+    fn hoard(uri: &str) -> Response {
+        create_hoard(Request::new(Method::Post, uri), Params::new()).unwrap()
+    }
+
+    #[test]
+    fn seed_repeats_item() {
+        assert_eq!(
+            create("/treasure/magical?seed=12").body(),
+            create("/treasure/magical?seed=12").body()
+        );
+    }
+
+    #[test]
+    fn unknown_kind_is_bad_request() {
+        assert_eq!(
+            create("/treasure/magical?kind=ring-of-power").status(),
+            &400
+        );
+    }
+
+    #[test]
+    fn generates_hoard_of_requested_rating() {
+        let response = hoard("/treasure/hoards?rating=4&seed=2965");
+        let body = serde_json::from_slice::<Value>(response.body()).unwrap();
+
+        assert_eq!(response.status(), &200);
+        assert_eq!(body["rating"], 4);
+        assert_eq!(body["rolls"].as_array().unwrap().len(), 4);
+        assert!(
+            body["description"]
+                .as_str()
+                .unwrap()
+                .starts_with("# Hoard (rating 4)")
+        );
+        assert_eq!(
+            response.body(),
+            hoard("/treasure/hoards?rating=4&seed=2965").body()
+        );
+    }
+
+    #[test]
+    fn unsupported_rating_is_bad_request() {
+        assert_eq!(hoard("/treasure/hoards?rating=7").status(), &400);
+        assert_eq!(hoard("/treasure/hoards").status(), &400);
+    }
+}
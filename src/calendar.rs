@@ -0,0 +1,536 @@
+/*!
+# Calendar
+
+Keeps track of the campaign's date. Hobbits count the days by the Shire Reckoning: twelve months
+of thirty days, with the Yule days at the turn of the year and the Lithe days at midsummer. The
+Dúnedain use the Steward's Reckoning, also of twelve thirty-day months but with five holidays
+outside of them, while the older King's Reckoning gives the first and last months 31 days.
+
+All three begin the year on the same day and keep Midyear's Day together, so a date is stored
+as the day of the year and converted to any reckoning when it is shown. Years are counted in the
+Third Age, which is 1600 years ahead of the Shire's own count.
+*/
+
+use std::{borrow::Cow, fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use strum::{EnumIter, IntoEnumIterator};
+use utoipa::{
+    PartialSchema, ToSchema,
+    openapi::{ObjectBuilder, RefOr, Schema, Type},
+};
+
+use crate::{journeys::Season, tables::rumours::CAMPAIGN_START};
+
+/// Difference between years of the Third Age and years of the Shire Reckoning
+const SHIRE_OFFSET: u16 = 1600;
+
+/// Day of the year the default campaign begins on: 1 Astron, as the roads open in spring
+const CAMPAIGN_START_DAY: u16 = 92;
+
+/// A way of counting the days of the year
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Deserialize,
+    strum::Display,
+    EnumIter,
+    Eq,
+    PartialEq,
+    Serialize,
+    ToSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "title_case")]
+pub enum Reckoning {
+    /// The calendar of the Hobbits, with years counted from the crossing of the Brandywine
+    #[default]
+    Shire,
+    /// The calendar of Mardil the Steward, used by the Dúnedain of the North and South
+    Stewards,
+    /// The calendar of the Númenórean kings, still kept by some of the Elves and the old
+    /// houses of Men
+    Kings,
+}
+
+/// Whether a part of the year is found in every year, or only in some
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum Occurs {
+    Always,
+    CommonYears,
+    LeapYears,
+}
+
+/// A month, or a holiday outside of the months
+#[derive(Clone, Copy)]
+struct Period {
+    name: &'static str,
+    days: u16,
+    /// Whether the days of the period are numbered, as in a month
+    month: bool,
+    occurs: Occurs,
+}
+
+const fn month(name: &'static str, days: u16) -> Period {
+    Period {
+        name,
+        days,
+        month: true,
+        occurs: Occurs::Always,
+    }
+}
+
+const fn holiday(name: &'static str, occurs: Occurs) -> Period {
+    Period {
+        name,
+        days: 1,
+        month: false,
+        occurs,
+    }
+}
+
+const SHIRE: &[Period] = &[
+    holiday("2 Yule", Occurs::Always),
+    month("Afteryule", 30),
+    month("Solmath", 30),
+    month("Rethe", 30),
+    month("Astron", 30),
+    month("Thrimidge", 30),
+    month("Forelithe", 30),
+    holiday("1 Lithe", Occurs::Always),
+    holiday("Midyear's Day", Occurs::Always),
+    holiday("Overlithe", Occurs::LeapYears),
+    holiday("2 Lithe", Occurs::Always),
+    month("Afterlithe", 30),
+    month("Wedmath", 30),
+    month("Halimath", 30),
+    month("Winterfilth", 30),
+    month("Blotmath", 30),
+    month("Foreyule", 30),
+    holiday("1 Yule", Occurs::Always),
+];
+
+const STEWARDS: &[Period] = &[
+    holiday("Yestarë", Occurs::Always),
+    month("Narvinyë", 30),
+    month("Nénimë", 30),
+    month("Súlimë", 30),
+    holiday("Tuilérë", Occurs::Always),
+    month("Víressë", 30),
+    month("Lótessë", 30),
+    month("Nárië", 30),
+    holiday("Loëndë", Occurs::CommonYears),
+    holiday("Enderi", Occurs::LeapYears),
+    holiday("Enderi", Occurs::LeapYears),
+    month("Cermië", 30),
+    month("Urimë", 30),
+    month("Yavannië", 30),
+    holiday("Yáviérë", Occurs::Always),
+    month("Narquelië", 30),
+    month("Hísimë", 30),
+    month("Ringarë", 30),
+    holiday("Mettarë", Occurs::Always),
+];
+
+const KINGS: &[Period] = &[
+    holiday("Yestarë", Occurs::Always),
+    month("Narvinyë", 31),
+    month("Nénimë", 30),
+    month("Súlimë", 30),
+    month("Víressë", 30),
+    month("Lótessë", 30),
+    month("Nárië", 30),
+    holiday("Loëndë", Occurs::CommonYears),
+    holiday("Enderi", Occurs::LeapYears),
+    holiday("Enderi", Occurs::LeapYears),
+    month("Cermië", 30),
+    month("Urimë", 30),
+    month("Yavannië", 30),
+    month("Narquelië", 30),
+    month("Hísimë", 30),
+    month("Ringarë", 31),
+    holiday("Mettarë", Occurs::Always),
+];
+
+impl Reckoning {
+    /// Months and holidays of the year, in order
+    fn periods(self, leap: bool) -> impl Iterator<Item = Period> {
+        let periods = match self {
+            Reckoning::Shire => SHIRE,
+            Reckoning::Stewards => STEWARDS,
+            Reckoning::Kings => KINGS,
+        };
+        periods.iter().copied().filter(move |period| {
+            period.occurs == Occurs::Always || (period.occurs == Occurs::LeapYears) == leap
+        })
+    }
+
+    /// Year of the reckoning for a year of the Third Age
+    #[must_use]
+    pub fn year(self, year: u16) -> u16 {
+        match self {
+            Reckoning::Shire => year.saturating_sub(SHIRE_OFFSET),
+            Reckoning::Stewards | Reckoning::Kings => year,
+        }
+    }
+}
+
+/// Whether a year of the Third Age has an extra day at midsummer: every fourth year, except
+/// the last year of a century
+#[must_use]
+pub fn is_leap_year(year: u16) -> bool {
+    year.is_multiple_of(4) && !year.is_multiple_of(100)
+}
+
+/// Number of days in a year of the Third Age
+#[must_use]
+pub fn days_in_year(year: u16) -> u16 {
+    if is_leap_year(year) { 366 } else { 365 }
+}
+
+/// A day of the campaign
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Date {
+    /// Year of the Third Age
+    year: u16,
+    /// Day of the year, starting from 1
+    day: u16,
+}
+
+impl Date {
+    /// The day the default campaign begins on, in the spring of 2965
+    #[must_use]
+    pub fn campaign_start() -> Self {
+        Self {
+            year: CAMPAIGN_START,
+            day: CAMPAIGN_START_DAY,
+        }
+    }
+
+    /// The first day of a year of the Third Age
+    #[must_use]
+    pub fn new_year(year: u16) -> Self {
+        Self { year, day: 1 }
+    }
+
+    #[must_use]
+    pub fn year(self) -> u16 {
+        self.year
+    }
+
+    /// The date `days` days later
+    #[must_use]
+    pub fn add_days(self, days: u32) -> Self {
+        let mut year = self.year;
+        let mut day = u32::from(self.day) + days;
+        while day > u32::from(days_in_year(year)) {
+            day -= u32::from(days_in_year(year));
+            year = year.saturating_add(1);
+        }
+        Self {
+            year,
+            day: u16::try_from(day).unwrap_or(1),
+        }
+    }
+
+    /// Season the day falls in. Spring begins with Rethe, summer with Forelithe, autumn with
+    /// Halimath and winter with Foreyule.
+    #[must_use]
+    pub fn season(self) -> Season {
+        let overlithe = u16::from(is_leap_year(self.year) && self.day > 183);
+        match self.day - overlithe {
+            62..=151 => Season::Spring,
+            152..=244 => Season::Summer,
+            245..=334 => Season::Autumn,
+            _ => Season::Winter,
+        }
+    }
+
+    /// The date as counted in the given reckoning
+    #[must_use]
+    pub fn reckon(self, reckoning: Reckoning) -> ReckonedDate {
+        let mut day = self.day;
+        let mut name = "";
+        let mut day_of_month = None;
+        for period in reckoning.periods(is_leap_year(self.year)) {
+            name = period.name;
+            if day <= period.days {
+                day_of_month = period.month.then_some(day);
+                break;
+            }
+            day -= period.days;
+        }
+
+        let year = reckoning.year(self.year);
+        ReckonedDate {
+            reckoning,
+            day: day_of_month,
+            name,
+            year,
+            text: match day_of_month {
+                Some(day) => format!("{day} {name} {year}"),
+                None => format!("{name} {year}"),
+            },
+        }
+    }
+
+    /// Read a date written as in the given reckoning, such as "22 Halimath 2965" or "Midyear's
+    /// Day 2965". Years are in the Third Age unless marked "S.R." for the Shire Reckoning.
+    ///
+    /// # Errors
+    ///
+    /// If the date has no year, or names a day the reckoning does not have.
+    pub fn parse(text: &str, reckoning: Reckoning) -> Result<Self, String> {
+        let mut words = text.split_whitespace().collect::<Vec<_>>();
+        let year = words
+            .pop()
+            .and_then(|year| year.parse::<u16>().ok())
+            .ok_or_else(|| format!("{text:?} does not end with a year"))?;
+        let year = match words.last().map(|era| fold(era)) {
+            Some(era) if era == "sr" => {
+                words.pop();
+                year.saturating_add(SHIRE_OFFSET)
+            }
+            Some(era) if era == "ta" => {
+                words.pop();
+                year
+            }
+            _ => year,
+        };
+        let leap = is_leap_year(year);
+
+        let name = fold(&words.join(" "));
+        let (number, month) = match words.split_first() {
+            Some((number, month)) => (number.parse::<u16>().ok(), fold(&month.join(" "))),
+            None => (None, String::new()),
+        };
+
+        let mut start = 1;
+        for period in reckoning.periods(leap) {
+            let day = if period.month {
+                number.filter(|day| (1..=period.days).contains(day) && fold(period.name) == month)
+            } else {
+                (fold(period.name) == name).then_some(1)
+            };
+            if let Some(day) = day {
+                return Ok(Self {
+                    year,
+                    day: start + day - 1,
+                });
+            }
+            start += period.days;
+        }
+
+        Err(format!(
+            "{text:?} is not a day of the {reckoning} Reckoning in {year}"
+        ))
+    }
+}
+
+impl FromStr for Date {
+    type Err = String;
+
+    /// Read a date in any reckoning, trying the Shire Reckoning first
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Reckoning::iter()
+            .find_map(|reckoning| Date::parse(text, reckoning).ok())
+            .ok_or_else(|| format!("{text:?} is not a known date"))
+    }
+}
+
+impl fmt::Display for Date {
+    /// The date in the Shire Reckoning, with the year of the Third Age
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let shire = self.reckon(Reckoning::Shire);
+        match shire.day {
+            Some(day) => write!(f, "{day} {} {}", shire.name, self.year),
+            None => write!(f, "{} {}", shire.name, self.year),
+        }
+    }
+}
+
+impl TryFrom<String> for Date {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        text.parse()
+    }
+}
+
+impl From<Date> for String {
+    fn from(date: Date) -> Self {
+        date.to_string()
+    }
+}
+
+impl ToSchema for Date {
+    fn name() -> Cow<'static, str> {
+        Cow::Borrowed("Date")
+    }
+}
+
+impl PartialSchema for Date {
+    fn schema() -> RefOr<Schema> {
+        ObjectBuilder::new()
+            .schema_type(Type::String)
+            .description(Some(
+                "A day of the Third Age, such as \"22 Halimath 2965\", \"Midyear's Day 2965\" \
+                 or \"1 Narquelië 2965\". Years marked \"S.R.\" are in the Shire Reckoning.",
+            ))
+            .examples(["1 Astron 2965"])
+            .into()
+    }
+}
+
+/// A date as counted in one of the reckonings
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReckonedDate {
+    reckoning: Reckoning,
+    /// Day of the month, unless the date is a holiday outside of the months
+    #[serde(skip_serializing_if = "Option::is_none")]
+    day: Option<u16>,
+    /// Name of the month or holiday
+    name: &'static str,
+    year: u16,
+    /// The date written out in full
+    text: String,
+}
+
+/// Everything there is to know about a day of the campaign
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CalendarDay {
+    date: Date,
+    /// Day of the year, starting from 1
+    day_of_year: u16,
+    leap_year: bool,
+    season: Season,
+    /// Map hexes a company covers in a day on foot in this season
+    hexes_per_day: u32,
+    /// Extra Fatigue gained from every Journey Event in this season
+    extra_fatigue: u32,
+    shire: ReckonedDate,
+    stewards: ReckonedDate,
+    kings: ReckonedDate,
+}
+
+impl From<Date> for CalendarDay {
+    fn from(date: Date) -> Self {
+        let season = date.season();
+        Self {
+            date,
+            day_of_year: date.day,
+            leap_year: is_leap_year(date.year),
+            season,
+            hexes_per_day: season.hexes_per_day(),
+            extra_fatigue: season.extra_fatigue(),
+            shire: date.reckon(Reckoning::Shire),
+            stewards: date.reckon(Reckoning::Stewards),
+            kings: date.reckon(Reckoning::Kings),
+        }
+    }
+}
+
+/// Lowercase a name and strip its accents and apostrophes, so that "loende" matches "Loëndë"
+fn fold(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, '\'' | '.'))
+        .map(|c| match c {
+            'á' | 'Á' => 'a',
+            'é' | 'ë' | 'É' => 'e',
+            'í' | 'Í' => 'i',
+            'ó' | 'Ó' => 'o',
+            'ú' | 'Ú' => 'u',
+            c => c.to_ascii_lowercase(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn every_reckoning_fills_the_year() {
+        for reckoning in Reckoning::iter() {
+            for leap in [false, true] {
+                let days = reckoning
+                    .periods(leap)
+                    .map(|period| period.days)
+                    .sum::<u16>();
+                assert_eq!(days, if leap { 366 } else { 365 });
+            }
+        }
+    }
+
+    #[test]
+    fn midyears_day_falls_together() {
+        let midyear = "Midyear's Day 2965".parse::<Date>().unwrap();
+
+        assert_eq!(midyear.day, 183);
+        assert_eq!(midyear.reckon(Reckoning::Stewards).name, "Loëndë");
+        assert_eq!(midyear.reckon(Reckoning::Kings).name, "Loëndë");
+        assert_eq!(midyear.season(), Season::Summer);
+    }
+
+    #[test]
+    fn converts_between_reckonings() {
+        let date = "22 Halimath 2965".parse::<Date>().unwrap();
+
+        assert_eq!(date.reckon(Reckoning::Shire).text, "22 Halimath 1365");
+        assert_eq!(date.reckon(Reckoning::Stewards).text, "23 Yavannië 2965");
+        assert_eq!(date.reckon(Reckoning::Kings).text, "23 Yavannië 2965");
+        assert_eq!(
+            Date::parse("23 Yavannie 2965", Reckoning::Stewards),
+            Ok(date)
+        );
+        assert_eq!("22 Halimath S.R. 1365".parse::<Date>(), Ok(date));
+        assert_eq!(date.to_string(), "22 Halimath 2965");
+    }
+
+    #[test]
+    fn overlithe_only_in_leap_years() {
+        assert!("Overlithe 2964".parse::<Date>().is_ok());
+        assert!("Overlithe 2965".parse::<Date>().is_err());
+        assert!("Overlithe 3000".parse::<Date>().is_err());
+        assert_eq!(
+            "Overlithe 2964"
+                .parse::<Date>()
+                .unwrap()
+                .reckon(Reckoning::Stewards)
+                .name,
+            "Enderi"
+        );
+    }
+
+    #[test]
+    fn adding_days_crosses_into_next_year() {
+        let yule = "1 Yule 2965".parse::<Date>().unwrap();
+
+        assert_eq!(yule.day, 365);
+        assert_eq!(yule.add_days(1), Date::new_year(2966));
+        assert_eq!(yule.add_days(1).to_string(), "2 Yule 2966");
+        assert_eq!(Date::new_year(2964).add_days(366), Date::new_year(2965));
+    }
+
+    #[test]
+    fn seasons_follow_the_months() {
+        let season = |date: &str| date.parse::<Date>().unwrap().season();
+
+        assert_eq!(season("1 Solmath 2965"), Season::Winter);
+        assert_eq!(season("1 Rethe 2965"), Season::Spring);
+        assert_eq!(season("30 Wedmath 2964"), Season::Summer);
+        assert_eq!(season("1 Halimath 2964"), Season::Autumn);
+        assert_eq!(season("1 Foreyule 2965"), Season::Winter);
+        assert_eq!(Date::campaign_start().season(), Season::Spring);
+    }
+
+    #[test]
+    fn rejects_unknown_dates() {
+        assert!("31 Halimath 2965".parse::<Date>().is_err());
+        assert!("Halimath".parse::<Date>().is_err());
+        assert!("3 Smaugsday 2965".parse::<Date>().is_err());
+    }
+}
@@ -0,0 +1,130 @@
+/*!
+# Callings
+
+The vocation that drives a Player-hero to leave the comforts of home for a life of adventure.
+*/
+use rand::{
+    Rng,
+    distr::{Distribution, StandardUniform},
+    seq::IteratorRandom,
+};
+use serde::{Deserialize, Serialize};
+use strum::{EnumIter, EnumString, IntoEnumIterator};
+use utoipa::ToSchema;
+
+/// The calling chosen by a Player-hero
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    strum::Display,
+    EnumIter,
+    EnumString,
+    Eq,
+    Hash,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    Serialize,
+    ToSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum Calling {
+    /// Captains are leaders of folk, bearing the responsibility of guiding others through
+    /// darkness and danger.
+    Captain,
+    /// Champions are warriors who meet the Shadow head on, seeking to overcome evil by strength
+    /// of arms.
+    Champion,
+    /// Messengers carry news and tidings between distant folks, knowing that the roads must be
+    /// kept open if the Free Peoples are to stand together.
+    Messenger,
+    /// Scholars seek out forgotten lore and hidden knowledge, for wisdom can be a weapon against
+    /// the Enemy.
+    Scholar,
+    /// Treasure Hunters are lured into the Wild by tales of lost wealth, hoping to find hoards
+    /// forgotten since elder days.
+    TreasureHunter,
+    /// Wardens watch over the borders of the lands of the Free Peoples, guarding them against
+    /// what creeps out of the Wild.
+    Warden,
+}
+
+impl Calling {
+    /// The Shadow Path the hero's calling leads them down when the Shadow takes hold
+    #[must_use]
+    pub fn shadow_path(self) -> ShadowPath {
+        match self {
+            Calling::Captain => ShadowPath::LureOfPower,
+            Calling::Champion => ShadowPath::CurseOfVengeance,
+            Calling::Messenger => ShadowPath::WanderingMadness,
+            Calling::Scholar => ShadowPath::LureOfSecrets,
+            Calling::TreasureHunter => ShadowPath::DragonSickness,
+            Calling::Warden => ShadowPath::PathOfDespair,
+        }
+    }
+}
+
+/// The way the Shadow corrupts a hero of a given calling, one Flaw at a time
+#[derive(Clone, Copy, Debug, Deserialize, strum::Display, Eq, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "title_case")]
+pub enum ShadowPath {
+    CurseOfVengeance,
+    DragonSickness,
+    LureOfPower,
+    LureOfSecrets,
+    PathOfDespair,
+    WanderingMadness,
+}
+
+impl ShadowPath {
+    /// Flaws acquired along the path, in the order they are gained
+    #[must_use]
+    pub fn flaws(self) -> [&'static str; 4] {
+        match self {
+            ShadowPath::CurseOfVengeance => ["Spiteful", "Brutal", "Cruel", "Murderous"],
+            ShadowPath::DragonSickness => ["Grasping", "Mistrustful", "Deceitful", "Thieving"],
+            ShadowPath::LureOfPower => ["Resentful", "Arrogant", "Overconfident", "Tyrannical"],
+            ShadowPath::LureOfSecrets => ["Haughty", "Scornful", "Scheming", "Treacherous"],
+            ShadowPath::PathOfDespair => ["Baleful", "Bitter", "Melancholic", "Despairing"],
+            ShadowPath::WanderingMadness => ["Idle", "Forgetful", "Fearful", "Lost"],
+        }
+    }
+}
+
+impl Distribution<Calling> for StandardUniform {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Calling {
+        Calling::iter().choose(rng).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use strum::ParseError;
+
+    use super::*;
+
+    #[test]
+    fn can_parse_from_strings() {
+        for calling in Calling::iter() {
+            assert_eq!(Ok(calling), Calling::try_from(calling.to_string().as_str()));
+        }
+    }
+
+    #[test]
+    fn every_calling_has_its_own_shadow_path() {
+        let paths = Calling::iter()
+            .map(|calling| calling.shadow_path().to_string())
+            .collect::<std::collections::HashSet<_>>();
+
+        assert_eq!(paths.len(), Calling::iter().count());
+    }
+
+    #[test]
+    fn returns_error_for_unknown_string() {
+        assert_eq!(Err(ParseError::VariantNotFound), Calling::try_from("foo"));
+    }
+}
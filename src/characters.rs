@@ -4,36 +4,291 @@
 Contains the information necessary to fill out a character sheet.
 */
 
+use anyhow::bail;
 use rand::{
     Rng,
     distr::{Distribution, StandardUniform},
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use strum::{EnumIter, IntoEnumIterator};
 use utoipa::ToSchema;
 
-use crate::cultures::HeroicCulture;
+use crate::{callings::Calling, cultures::HeroicCulture, treasure::MagicalItem};
+
+pub mod shadow;
 
 /// Contains the information necessary to fill out a character sheet.
-#[derive(Debug, Serialize, ToSchema)]
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct Character {
     heroic_culture: HeroicCulture,
+    #[serde(default = "unknown_calling")]
+    calling: Calling,
     name: String,
+    /// Renown earned through deeds of courage
+    #[serde(default = "starting_rating")]
+    valour: u8,
+    /// Understanding of the world and the self
+    #[serde(default = "starting_rating")]
+    wisdom: u8,
+    /// Famous Weapons and Armour the hero carries
+    #[serde(default)]
+    famous_gear: Vec<String>,
+    /// Magical treasure the hero carries
+    #[serde(default)]
+    magical_items: Vec<MagicalItem>,
+    /// Heart attribute, setting how much Hope is recovered during a Fellowship phase
+    #[serde(default = "starting_heart")]
+    heart: u8,
+    /// Hope the hero has left to spend
+    #[serde(default = "starting_hope")]
+    hope: u8,
+    /// Hope the hero has when fully rested
+    #[serde(default = "starting_hope")]
+    max_hope: u8,
+    /// Shadow points weighing on the hero
+    #[serde(default)]
+    shadow: u8,
+    /// Permanent Shadow points, only removed by healing scars during Yule
+    #[serde(default)]
+    shadow_scars: u8,
+    /// Flaws acquired along the calling's Shadow Path, in the order they were gained
+    #[serde(default)]
+    flaws: Vec<String>,
+    /// Treasure points carried home from adventures
+    #[serde(default)]
+    treasure: u32,
+    #[serde(default)]
+    standard_of_living: StandardOfLiving,
+}
+
+/// How comfortably a hero lives between adventures
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Deserialize,
+    strum::Display,
+    EnumIter,
+    Eq,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    Serialize,
+    ToSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "title_case")]
+pub enum StandardOfLiving {
+    Poor,
+    Frugal,
+    #[default]
+    Common,
+    Prosperous,
+    Rich,
+    VeryRich,
+}
+
+impl StandardOfLiving {
+    /// The next standard up, if any
+    #[must_use]
+    pub fn next(self) -> Option<Self> {
+        Self::iter().skip_while(|standard| *standard != self).nth(1)
+    }
+
+    /// Treasure to spend to reach this standard from the one below
+    #[must_use]
+    pub fn cost(self) -> u32 {
+        match self {
+            StandardOfLiving::Poor => 0,
+            StandardOfLiving::Frugal => 30,
+            StandardOfLiving::Common => 60,
+            StandardOfLiving::Prosperous => 90,
+            StandardOfLiving::Rich => 120,
+            StandardOfLiving::VeryRich => 150,
+        }
+    }
+}
+
+/// Calling given to heroes saved before they had one
+fn unknown_calling() -> Calling {
+    Calling::Warden
+}
+
+/// Valour and Wisdom of a newly created hero
+fn starting_rating() -> u8 {
+    1
+}
+
+/// Heart of a hero whose attributes were not given
+fn starting_heart() -> u8 {
+    4
+}
+
+/// Hope of a hero whose attributes were not given
+fn starting_hope() -> u8 {
+    12
 }
 
 impl Character {
     #[must_use]
-    pub fn new(heroic_culture: HeroicCulture, name: impl Into<String>) -> Self {
+    pub fn new(heroic_culture: HeroicCulture, calling: Calling, name: impl Into<String>) -> Self {
         Self {
             heroic_culture,
+            calling,
             name: name.into(),
+            valour: starting_rating(),
+            wisdom: starting_rating(),
+            famous_gear: vec![],
+            magical_items: vec![],
+            heart: starting_heart(),
+            hope: starting_hope(),
+            max_hope: starting_hope(),
+            shadow: 0,
+            shadow_scars: 0,
+            flaws: vec![],
+            treasure: 0,
+            standard_of_living: StandardOfLiving::default(),
         }
     }
+
+    /// Generate a character of the given culture and calling
+    pub fn random<R: Rng + ?Sized>(
+        rng: &mut R,
+        heroic_culture: HeroicCulture,
+        calling: Calling,
+    ) -> Self {
+        Self::new(heroic_culture, calling, heroic_culture.random_name(rng))
+    }
+
+    #[must_use]
+    pub fn heroic_culture(&self) -> HeroicCulture {
+        self.heroic_culture
+    }
+
+    #[must_use]
+    pub fn calling(&self) -> Calling {
+        self.calling
+    }
+
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[must_use]
+    pub fn valour(&self) -> u8 {
+        self.valour
+    }
+
+    #[must_use]
+    pub fn wisdom(&self) -> u8 {
+        self.wisdom
+    }
+
+    #[must_use]
+    pub fn famous_gear(&self) -> &[String] {
+        &self.famous_gear
+    }
+
+    #[must_use]
+    pub fn magical_items(&self) -> &[MagicalItem] {
+        &self.magical_items
+    }
+
+    /// Whether the hero carries a Famous Weapon, Armour or magical item with the given name
+    #[must_use]
+    pub fn carries(&self, item: &str) -> bool {
+        self.famous_gear.iter().any(|gear| gear == item)
+            || self
+                .magical_items
+                .iter()
+                .any(|magical| magical.name() == item)
+    }
+
+    #[must_use]
+    pub fn heart(&self) -> u8 {
+        self.heart
+    }
+
+    #[must_use]
+    pub fn hope(&self) -> u8 {
+        self.hope
+    }
+
+    #[must_use]
+    pub fn shadow(&self) -> u8 {
+        self.shadow
+    }
+
+    #[must_use]
+    pub fn shadow_scars(&self) -> u8 {
+        self.shadow_scars
+    }
+
+    #[must_use]
+    pub fn treasure(&self) -> u32 {
+        self.treasure
+    }
+
+    #[must_use]
+    pub fn standard_of_living(&self) -> StandardOfLiving {
+        self.standard_of_living
+    }
+
+    /// Recover up to `amount` Hope without going over the hero's maximum, returning how much was
+    /// recovered
+    pub fn recover_hope(&mut self, amount: u8) -> u8 {
+        let recovered = amount.min(self.max_hope.saturating_sub(self.hope));
+        self.hope += recovered;
+        recovered
+    }
+
+    /// Remove up to `amount` Shadow points, returning how many were removed. Shadow Scars are
+    /// not affected.
+    pub fn remove_shadow(&mut self, amount: u8) -> u8 {
+        let removed = amount.min(self.shadow);
+        self.shadow -= removed;
+        removed
+    }
+
+    /// Heal one Shadow Scar, returning whether the hero had one
+    pub fn heal_scar(&mut self) -> bool {
+        if self.shadow_scars == 0 {
+            return false;
+        }
+        self.shadow_scars -= 1;
+        true
+    }
+
+    /// Spend Treasure to reach the next Standard of Living
+    ///
+    /// # Errors
+    ///
+    /// If the hero already lives as well as possible or cannot afford it.
+    pub fn raise_standard_of_living(&mut self) -> anyhow::Result<StandardOfLiving> {
+        let Some(next) = self.standard_of_living.next() else {
+            bail!("{} already lives as well as anyone can", self.name);
+        };
+        if self.treasure < next.cost() {
+            bail!(
+                "{} needs {} Treasure to live a {next} life, but only has {}",
+                self.name,
+                next.cost(),
+                self.treasure
+            );
+        }
+        self.treasure -= next.cost();
+        self.standard_of_living = next;
+        Ok(next)
+    }
 }
 
 impl Distribution<Character> for StandardUniform {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Character {
         let heroic_culture = rng.random::<HeroicCulture>();
-        Character::new(heroic_culture, heroic_culture.random_name(rng))
+        let calling = rng.random::<Calling>();
+        Character::random(rng, heroic_culture, calling)
     }
 }
 
@@ -50,4 +305,49 @@ mod test {
 
         assert!(!character.name.is_empty());
     }
+
+    #[test]
+    fn loads_characters_saved_without_calling() {
+        let character = serde_json::from_str::<Character>(
+            r#"{"heroic_culture": "men-of-bree", "name": "Barliman Butterbur"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(character.calling, Calling::Warden);
+        assert_eq!(character.name, "Barliman Butterbur");
+    }
+
+    #[test]
+    fn hope_does_not_exceed_maximum() {
+        let mut character = Character::new(HeroicCulture::Bardings, Calling::Captain, "Lifstan");
+        character.hope = 10;
+
+        assert_eq!(character.recover_hope(4), 2);
+        assert_eq!(character.hope, character.max_hope);
+    }
+
+    #[test]
+    fn raising_standard_of_living_costs_treasure() {
+        let mut character = Character::new(HeroicCulture::Bardings, Calling::Captain, "Lifstan");
+        character.treasure = 100;
+
+        assert_eq!(
+            character.raise_standard_of_living().unwrap(),
+            StandardOfLiving::Prosperous
+        );
+        assert_eq!(character.treasure, 10);
+        assert!(character.raise_standard_of_living().is_err());
+        assert_eq!(character.standard_of_living, StandardOfLiving::Prosperous);
+    }
+
+    #[test]
+    fn missing_attributes_get_defaults() {
+        let character = serde_json::from_str::<Character>(
+            r#"{"heroic_culture": "men-of-bree", "calling": "warden", "name": "Tom"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(character.hope, starting_hope());
+        assert_eq!(character.standard_of_living, StandardOfLiving::Common);
+    }
 }
@@ -0,0 +1,286 @@
+/*!
+# Shadow
+
+Tracks how far a hero has fallen under the Shadow. Dread, Greed and Sorcery can be resisted with
+a Shadow test, rolling Valour or Wisdom, while Misdeeds always leave their mark. A hero whose
+Shadow reaches their Hope is Miserable, and a Miserable hero rolling an Eye of Sauron suffers a
+bout of madness: their Shadow points turn into a Shadow Scar and they take a step down the Shadow
+Path of their calling, gaining a Flaw.
+*/
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::dice::{Favour, FeatDie, Roll};
+
+use super::Character;
+
+/// What a hero gains Shadow points from
+#[derive(Clone, Copy, Debug, Deserialize, strum::Display, Eq, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "title_case")]
+pub enum ShadowSource {
+    /// Terrifying sights and the presence of the Enemy's servants, resisted with Valour
+    Dread,
+    /// The lure of treasure and power, resisted with Wisdom
+    Greed,
+    /// The spells and foul places of the Enemy, resisted with Wisdom
+    Sorcery,
+    /// Cruel or dishonourable deeds, which cannot be resisted
+    Misdeeds,
+}
+
+/// Rating a hero rolls to resist Shadow
+#[derive(Clone, Copy, Debug, Deserialize, strum::Display, Eq, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "title_case")]
+pub enum Virtue {
+    Valour,
+    Wisdom,
+}
+
+impl ShadowSource {
+    /// Rating rolled to resist Shadow from this source, if it can be resisted at all
+    #[must_use]
+    pub fn resisted_with(self) -> Option<Virtue> {
+        match self {
+            ShadowSource::Dread => Some(Virtue::Valour),
+            ShadowSource::Greed | ShadowSource::Sorcery => Some(Virtue::Wisdom),
+            ShadowSource::Misdeeds => None,
+        }
+    }
+}
+
+/// The roll made to resist Shadow
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ShadowRoll {
+    virtue: Virtue,
+    roll: Roll,
+    target_number: u32,
+    success: bool,
+}
+
+/// Outcome of a bout of madness
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BoutOfMadness {
+    /// Shadow Scars the hero has after the bout
+    shadow_scars: u8,
+    /// Flaw gained, unless the hero had no more to gain
+    #[serde(skip_serializing_if = "Option::is_none")]
+    flaw: Option<String>,
+    /// Whether the hero reached the end of their Shadow Path and is lost to the Shadow
+    lost_to_shadow: bool,
+}
+
+/// Outcome of exposing a hero to a source of Shadow
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ShadowTest {
+    source: ShadowSource,
+    /// Shadow points the hero was threatened with
+    points: u8,
+    /// Shadow test made to resist, unless the source cannot be resisted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    roll: Option<ShadowRoll>,
+    /// Shadow points actually gained
+    gained: u8,
+    /// Whether the hero is Miserable after the test
+    miserable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    madness: Option<BoutOfMadness>,
+}
+
+impl Character {
+    #[must_use]
+    pub fn flaws(&self) -> &[String] {
+        &self.flaws
+    }
+
+    /// Total Shadow, counting both Shadow points and Shadow Scars
+    #[must_use]
+    pub fn total_shadow(&self) -> u8 {
+        self.shadow.saturating_add(self.shadow_scars)
+    }
+
+    /// Whether the hero's Shadow has reached their current Hope
+    #[must_use]
+    pub fn is_miserable(&self) -> bool {
+        self.total_shadow() > 0 && self.total_shadow() >= self.hope
+    }
+
+    /// Target Number for the hero's Valour and Wisdom rolls: 20 minus Heart
+    #[must_use]
+    pub fn shadow_target_number(&self) -> u32 {
+        20u32.saturating_sub(u32::from(self.heart))
+    }
+
+    /// Expose the hero to `points` of Shadow from `source`.
+    ///
+    /// Unless the source is Misdeeds, the hero rolls Valour or Wisdom to resist: a success
+    /// cancels one point, plus one for every Tengwar rune. A Miserable hero rolling an Eye of
+    /// Sauron fails and suffers a bout of madness.
+    pub fn shadow_test<R: Rng + ?Sized>(
+        &mut self,
+        rng: &mut R,
+        source: ShadowSource,
+        points: u8,
+    ) -> ShadowTest {
+        let was_miserable = self.is_miserable();
+        let roll = source.resisted_with().map(|virtue| {
+            let rating = match virtue {
+                Virtue::Valour => self.valour,
+                Virtue::Wisdom => self.wisdom,
+            };
+            let roll = Roll::new(rng, rating, Favour::Normal, false);
+            let target_number = self.shadow_target_number();
+            let success = !(was_miserable && roll.feat_die() == FeatDie::Eye)
+                && roll.is_success(target_number);
+            ShadowRoll {
+                virtue,
+                roll,
+                target_number,
+                success,
+            }
+        });
+
+        let resisted = roll.as_ref().filter(|roll| roll.success).map_or(0, |roll| {
+            u8::try_from(roll.roll.tengwars() + 1).unwrap_or(u8::MAX)
+        });
+        let gained = points.saturating_sub(resisted);
+        self.shadow = self.shadow.saturating_add(gained);
+
+        let madness = roll
+            .as_ref()
+            .is_some_and(|roll| was_miserable && roll.roll.feat_die() == FeatDie::Eye)
+            .then(|| self.bout_of_madness());
+
+        ShadowTest {
+            source,
+            points,
+            roll,
+            gained,
+            miserable: self.is_miserable(),
+            madness,
+        }
+    }
+
+    /// Suffer a bout of madness: Shadow points are cleared, one of them becomes a Shadow Scar
+    /// and the hero gains the next Flaw of their calling's Shadow Path.
+    pub fn bout_of_madness(&mut self) -> BoutOfMadness {
+        self.shadow = 0;
+        self.shadow_scars = self.shadow_scars.saturating_add(1);
+
+        let flaw = self
+            .calling
+            .shadow_path()
+            .flaws()
+            .get(self.flaws.len())
+            .map(|flaw| (*flaw).to_owned());
+        if let Some(flaw) = &flaw {
+            self.flaws.push(flaw.clone());
+        }
+
+        BoutOfMadness {
+            shadow_scars: self.shadow_scars,
+            lost_to_shadow: flaw.is_none(),
+            flaw,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{callings::Calling, cultures::HeroicCulture, rand::rng_from_seed};
+
+    use super::*;
+
+    fn hero() -> Character {
+        Character::new(HeroicCulture::Bardings, Calling::Champion, "Lifstan")
+    }
+
+    #[test]
+    fn misdeeds_cannot_be_resisted() {
+        let mut hero = hero();
+        let test = hero.shadow_test(&mut rng_from_seed(1), ShadowSource::Misdeeds, 2);
+
+        assert!(test.roll.is_none());
+        assert_eq!(test.gained, 2);
+        assert_eq!(hero.shadow, 2);
+    }
+
+    #[test]
+    fn successful_tests_cancel_shadow() {
+        let mut rng = rng_from_seed(2965);
+        for source in [
+            ShadowSource::Dread,
+            ShadowSource::Greed,
+            ShadowSource::Sorcery,
+        ] {
+            for _ in 0..50 {
+                let mut hero = hero();
+                let test = hero.shadow_test(&mut rng, source, 3);
+                let roll = test.roll.unwrap();
+
+                assert_eq!(Some(roll.virtue), source.resisted_with());
+                assert_eq!(roll.target_number, 16);
+                if roll.success {
+                    assert_eq!(
+                        test.gained,
+                        3u8.saturating_sub(u8::try_from(roll.roll.tengwars()).unwrap() + 1)
+                    );
+                } else {
+                    assert_eq!(test.gained, 3);
+                }
+                assert_eq!(hero.shadow, test.gained);
+            }
+        }
+    }
+
+    #[test]
+    fn shadow_reaching_hope_makes_hero_miserable() {
+        let mut hero = hero();
+        hero.hope = 3;
+        hero.shadow = 2;
+        assert!(!hero.is_miserable());
+
+        hero.shadow_scars = 1;
+        assert!(hero.is_miserable());
+    }
+
+    #[test]
+    fn miserable_heroes_rolling_an_eye_go_mad() {
+        let mut rng = rng_from_seed(7);
+        let mut mad = false;
+        for _ in 0..100 {
+            let mut hero = hero();
+            hero.hope = 1;
+            hero.shadow = 1;
+            let test = hero.shadow_test(&mut rng, ShadowSource::Dread, 1);
+            let eye = test.roll.as_ref().unwrap().roll.feat_die() == FeatDie::Eye;
+
+            assert_eq!(test.madness.is_some(), eye);
+            if eye {
+                assert!(!test.roll.unwrap().success);
+                assert_eq!(hero.shadow, 0);
+                assert_eq!(hero.shadow_scars, 1);
+                assert_eq!(hero.flaws, ["Spiteful"]);
+                mad = true;
+            }
+        }
+        assert!(mad);
+    }
+
+    #[test]
+    fn flaws_follow_shadow_path_until_lost() {
+        let mut hero = hero();
+        for _ in 0..4 {
+            assert!(!hero.bout_of_madness().lost_to_shadow);
+        }
+        let madness = hero.bout_of_madness();
+
+        assert_eq!(hero.flaws, Calling::Champion.shadow_path().flaws());
+        assert!(madness.lost_to_shadow);
+        assert!(madness.flaw.is_none());
+        assert_eq!(madness.shadow_scars, 5);
+    }
+}
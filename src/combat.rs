@@ -0,0 +1,633 @@
+/*!
+# Combat
+
+Resolves fights between a company and its adversaries, round after round, until one side is
+overcome. Running the same encounter many times gives an idea of how dangerous it is before
+bringing it to the table.
+*/
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::dice::{Favour, FeatDie, Roll};
+
+use self::fell_abilities::{Condition, Effect, FellAbility};
+
+pub mod adversaries;
+pub mod fell_abilities;
+
+/// Rounds fought before a combat is called off as a stalemate
+const MAX_ROUNDS: u32 = 20;
+
+/// How a hero positions themselves in the fight
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, strum::Display, Eq, PartialEq, Serialize, ToSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum Stance {
+    /// Pressing the attack: rolls to hit are favoured, and so are rolls to hit the hero
+    Forward,
+    /// Fighting in the thick of the melee
+    #[default]
+    Open,
+    /// Holding back behind shield and blade: rolls to hit are ill-favoured, and so are rolls to
+    /// hit the hero
+    Defensive,
+    /// Shooting from behind the lines, only attacked once nobody is left in close combat
+    Rearward,
+    /// Harrying the enemy from a distance: the hero makes no attacks, and rolls to hit them are
+    /// ill-favoured
+    Skirmish,
+}
+
+impl Stance {
+    /// How the hero's attack rolls are made, if they attack at all
+    #[must_use]
+    pub fn attack_favour(self) -> Option<Favour> {
+        match self {
+            Stance::Forward => Some(Favour::Favoured),
+            Stance::Open | Stance::Rearward => Some(Favour::Normal),
+            Stance::Defensive => Some(Favour::IllFavoured),
+            Stance::Skirmish => None,
+        }
+    }
+
+    /// How adversaries' attack rolls against the hero are made
+    #[must_use]
+    pub fn defence_favour(self) -> Favour {
+        match self {
+            Stance::Forward => Favour::Favoured,
+            Stance::Open | Stance::Rearward => Favour::Normal,
+            Stance::Defensive | Stance::Skirmish => Favour::IllFavoured,
+        }
+    }
+
+    /// Whether the hero stands in close combat
+    #[must_use]
+    pub fn is_close_combat(self) -> bool {
+        matches!(self, Stance::Forward | Stance::Open | Stance::Defensive)
+    }
+}
+
+/// A weapon and how well it is wielded
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct Weapon {
+    name: String,
+    /// Success dice rolled when attacking with the weapon
+    rating: u8,
+    /// Endurance lost by the target of a hit
+    damage: u32,
+    /// Target Number of the Protection roll against a Piercing Blow
+    injury: u32,
+}
+
+/// A Player-hero taking part in a fight
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct Hero {
+    name: String,
+    #[serde(default)]
+    stance: Stance,
+    endurance: u32,
+    /// Strength attribute: the hero's attacks are made against a TN of 20 minus Strength, and
+    /// a Heavy Blow adds Strength to the Endurance lost
+    strength: u32,
+    /// Target Number of adversaries' attacks against the hero
+    parry: u32,
+    /// Success dice rolled for Protection
+    #[serde(default)]
+    armour: u8,
+    weapon: Weapon,
+}
+
+/// An adversary the company faces
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct Adversary {
+    name: String,
+    /// Overall prowess of the adversary, added to the Endurance lost by a Heavy Blow
+    attribute_level: u32,
+    endurance: u32,
+    /// Number of Wounds needed to kill the adversary
+    might: u32,
+    /// Hate or Resolve: lost as the adversary's companions fall. At 0 the adversary is Weary.
+    hate: u32,
+    /// Added to the Target Number of heroes' attacks
+    parry: u32,
+    /// Success dice rolled for Protection
+    #[serde(default)]
+    armour: u8,
+    /// Weapons carried. The adversary attacks with the first one.
+    weapons: Vec<Weapon>,
+    #[serde(default)]
+    fell_abilities: Vec<FellAbility>,
+}
+
+impl Adversary {
+    /// Effects of the adversary's Fell Abilities
+    fn effects(&self) -> impl Iterator<Item = Effect> {
+        self.fell_abilities.iter().map(|ability| ability.effect())
+    }
+}
+
+/// Heroes and adversaries meeting in battle
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct Encounter {
+    heroes: Vec<Hero>,
+    adversaries: Vec<Adversary>,
+    /// Circumstances of the fight that may weaken some adversaries
+    #[serde(default)]
+    conditions: Vec<Condition>,
+}
+
+/// Side that won a fight
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Victor {
+    Heroes,
+    Adversaries,
+    /// Neither side was overcome before the fight was called off
+    Nobody,
+}
+
+/// How a single fight ended
+#[derive(Debug)]
+pub struct Fight {
+    victor: Victor,
+    rounds: u32,
+    /// Endurance lost by each hero, in the order they were listed
+    endurance_lost: Vec<u32>,
+    /// Wounds taken by each hero, in the order they were listed
+    wounds: Vec<u32>,
+    heroes_down: u32,
+    adversaries_defeated: u32,
+}
+
+/// A hero's condition during a fight
+struct HeroState {
+    endurance: u32,
+    wounds: u32,
+}
+
+impl HeroState {
+    /// Unconscious or dying heroes take no further part in the fight
+    fn is_down(&self) -> bool {
+        self.endurance == 0 || self.wounds >= 2
+    }
+}
+
+/// An adversary's condition during a fight
+struct AdversaryState {
+    endurance: u32,
+    wounds: u32,
+    hate: u32,
+}
+
+impl AdversaryState {
+    fn is_defeated(&self, adversary: &Adversary) -> bool {
+        self.endurance == 0 || self.wounds >= adversary.might.max(1)
+    }
+}
+
+/// Running state while resolving a fight
+struct Battle<'a> {
+    encounter: &'a Encounter,
+    heroes: Vec<HeroState>,
+    adversaries: Vec<AdversaryState>,
+    /// Whether the heroes' attacks are hampered by Dread this round
+    dread: bool,
+}
+
+impl Battle<'_> {
+    fn standing_heroes(&self) -> Vec<usize> {
+        (0..self.heroes.len())
+            .filter(|index| !self.heroes[*index].is_down())
+            .collect()
+    }
+
+    fn standing_adversaries(&self) -> Vec<usize> {
+        (0..self.adversaries.len())
+            .filter(|index| {
+                !self.adversaries[*index].is_defeated(&self.encounter.adversaries[*index])
+            })
+            .collect()
+    }
+
+    /// Spend the Hate needed for the adversary's first effect picked by `wanted`, returning
+    /// whether the effect can be used
+    fn use_effect(&mut self, index: usize, wanted: impl Fn(Effect) -> bool) -> bool {
+        let Some(cost) = self.encounter.adversaries[index]
+            .effects()
+            .filter(|effect| wanted(*effect))
+            .find_map(Effect::hate_cost)
+        else {
+            return false;
+        };
+        let state = &mut self.adversaries[index];
+        if state.hate < cost {
+            return false;
+        }
+        state.hate -= cost;
+        true
+    }
+
+    /// Adversaries are Weary once their Hate is spent, or when a condition they fear holds
+    fn is_weary(&self, index: usize) -> bool {
+        self.adversaries[index].hate == 0
+            || self.encounter.adversaries[index]
+                .effects()
+                .any(|effect| match effect {
+                    Effect::WearyWhen { condition } => {
+                        self.encounter.conditions.contains(&condition)
+                    }
+                    _ => false,
+                })
+    }
+
+    /// Effects adversaries use at the start of every round
+    fn start_round(&mut self) {
+        let encounter = self.encounter;
+        self.dread = false;
+        for index in self.standing_adversaries() {
+            let adversary = &encounter.adversaries[index];
+            if self.adversaries[index].endurance < adversary.endurance
+                && self.use_effect(index, |effect| matches!(effect, Effect::Regenerate { .. }))
+            {
+                let state = &mut self.adversaries[index];
+                state.endurance =
+                    (state.endurance + adversary.attribute_level).min(adversary.endurance);
+            }
+            if !self.dread
+                && self.use_effect(index, |effect| matches!(effect, Effect::Dread { .. }))
+            {
+                self.dread = true;
+            }
+        }
+    }
+
+    /// Every hero able to attack strikes at an adversary still standing
+    fn heroes_attack<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        let encounter = self.encounter;
+        for (turn, index) in self.standing_heroes().into_iter().enumerate() {
+            let hero = &encounter.heroes[index];
+            let Some(favour) = hero.stance.attack_favour() else {
+                continue;
+            };
+            let favour = if self.dread {
+                favour.worsened()
+            } else {
+                favour
+            };
+            let targets = self.standing_adversaries();
+            let Some(&target) = targets.get(turn % targets.len().max(1)) else {
+                return;
+            };
+            let adversary = &encounter.adversaries[target];
+
+            let attack = Roll::new(rng, hero.weapon.rating, favour, false);
+            let target_number = 20u32.saturating_sub(hero.strength) + adversary.parry;
+            if !attack.is_success(target_number)
+                || self.use_effect(target, |effect| matches!(effect, Effect::CancelHit { .. }))
+            {
+                continue;
+            }
+
+            let mut damage = hero.weapon.damage;
+            if attack.tengwars() > 0 {
+                damage += hero.strength;
+            }
+            let wounded = is_piercing(attack.feat_die())
+                && !Roll::new(rng, adversary.armour, Favour::Normal, false)
+                    .is_success(hero.weapon.injury)
+                && !self.use_effect(target, |effect| {
+                    matches!(effect, Effect::IgnoreWound { .. })
+                });
+            let state = &mut self.adversaries[target];
+            state.endurance = state.endurance.saturating_sub(damage);
+            if wounded {
+                state.wounds += 1;
+            }
+
+            if state.is_defeated(adversary) {
+                let steadied = self.standing_adversaries().into_iter().any(|other| {
+                    encounter.adversaries[other]
+                        .effects()
+                        .any(|effect| effect == Effect::SteadyAllies)
+                });
+                if !steadied {
+                    for other in &mut self.adversaries {
+                        other.hate = other.hate.saturating_sub(1);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Every adversary still standing strikes at a hero, preferring those in close combat
+    fn adversaries_attack<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        for (turn, index) in self.standing_adversaries().into_iter().enumerate() {
+            if self.encounter.adversaries[index].weapons.is_empty() {
+                continue;
+            }
+            let extra_attack =
+                self.use_effect(index, |effect| matches!(effect, Effect::ExtraAttack { .. }));
+            for _ in 0..=usize::from(extra_attack) {
+                self.adversary_strikes(rng, index, turn);
+            }
+        }
+    }
+
+    /// One attack by an adversary against a hero still standing
+    fn adversary_strikes<R: Rng + ?Sized>(&mut self, rng: &mut R, index: usize, turn: usize) {
+        let encounter = self.encounter;
+        let adversary = &encounter.adversaries[index];
+        let Some(weapon) = adversary.weapons.first() else {
+            return;
+        };
+        let standing = self.standing_heroes();
+        let close_combat = standing
+            .iter()
+            .copied()
+            .filter(|hero| encounter.heroes[*hero].stance.is_close_combat())
+            .collect::<Vec<_>>();
+        let targets = if close_combat.is_empty() {
+            standing
+        } else {
+            close_combat
+        };
+        let Some(&target) = targets.get(turn % targets.len().max(1)) else {
+            return;
+        };
+        let hero = &encounter.heroes[target];
+
+        let attack = Roll::new(
+            rng,
+            weapon.rating,
+            hero.stance.defence_favour(),
+            self.is_weary(index),
+        );
+        if !attack.is_success(hero.parry) {
+            return;
+        }
+
+        let state = &mut self.heroes[target];
+        let mut damage = weapon.damage;
+        if attack.tengwars() > 0 {
+            damage += adversary.attribute_level;
+        }
+        state.endurance = state.endurance.saturating_sub(damage);
+        if is_piercing(attack.feat_die())
+            && !Roll::new(rng, hero.armour, Favour::Normal, false).is_success(weapon.injury)
+        {
+            state.wounds += 1;
+        }
+    }
+}
+
+/// A Feat die showing 10 or the Gandalf rune on a hit strikes a Piercing Blow
+fn is_piercing(feat_die: FeatDie) -> bool {
+    feat_die >= FeatDie::Number(10)
+}
+
+impl Encounter {
+    /// Name of a hero that appears more than once, as the simulation tells heroes apart by name
+    #[must_use]
+    pub fn repeated_hero_name(&self) -> Option<&str> {
+        let mut names = BTreeSet::new();
+        self.heroes
+            .iter()
+            .map(|hero| hero.name.as_str())
+            .find(|name| !names.insert(*name))
+    }
+
+    /// Fight until one side is overcome, or until the fight drags on too long
+    pub fn fight<R: Rng + ?Sized>(&self, rng: &mut R) -> Fight {
+        let mut battle = Battle {
+            encounter: self,
+            heroes: self
+                .heroes
+                .iter()
+                .map(|hero| HeroState {
+                    endurance: hero.endurance,
+                    wounds: 0,
+                })
+                .collect(),
+            adversaries: self
+                .adversaries
+                .iter()
+                .map(|adversary| AdversaryState {
+                    endurance: adversary.endurance,
+                    wounds: 0,
+                    hate: adversary.hate,
+                })
+                .collect(),
+            dread: false,
+        };
+
+        let mut rounds = 0;
+        let victor = loop {
+            if battle.standing_adversaries().is_empty() {
+                break Victor::Heroes;
+            }
+            if battle.standing_heroes().is_empty() {
+                break Victor::Adversaries;
+            }
+            if rounds == MAX_ROUNDS {
+                break Victor::Nobody;
+            }
+            rounds += 1;
+            battle.start_round();
+            battle.heroes_attack(rng);
+            battle.adversaries_attack(rng);
+        };
+
+        Fight {
+            victor,
+            rounds,
+            endurance_lost: self
+                .heroes
+                .iter()
+                .zip(&battle.heroes)
+                .map(|(hero, state)| hero.endurance - state.endurance)
+                .collect(),
+            wounds: battle.heroes.iter().map(|state| state.wounds).collect(),
+            heroes_down: count(battle.heroes.iter().filter(|state| state.is_down())),
+            adversaries_defeated: count(
+                self.adversaries
+                    .iter()
+                    .zip(&battle.adversaries)
+                    .filter(|(adversary, state)| state.is_defeated(adversary)),
+            ),
+        }
+    }
+
+    /// Fight the encounter `runs` times and summarise the results
+    pub fn simulate<R: Rng + ?Sized>(&self, rng: &mut R, runs: u32) -> Simulation {
+        let fights = (0..runs).map(|_| self.fight(rng)).collect::<Vec<_>>();
+        let average = |total: u32| f64::from(total) / f64::from(runs.max(1));
+        let victories =
+            |victor: Victor| count(fights.iter().filter(|fight| fight.victor == victor));
+
+        Simulation {
+            runs,
+            hero_win_rate: average(victories(Victor::Heroes)),
+            adversary_win_rate: average(victories(Victor::Adversaries)),
+            stalemate_rate: average(victories(Victor::Nobody)),
+            average_rounds: average(fights.iter().map(|fight| fight.rounds).sum()),
+            average_endurance_lost: self
+                .heroes
+                .iter()
+                .enumerate()
+                .map(|(index, hero)| {
+                    let total = fights.iter().map(|fight| fight.endurance_lost[index]).sum();
+                    (hero.name.clone(), average(total))
+                })
+                .collect(),
+            average_wounds: self
+                .heroes
+                .iter()
+                .enumerate()
+                .map(|(index, hero)| {
+                    let total = fights.iter().map(|fight| fight.wounds[index]).sum();
+                    (hero.name.clone(), average(total))
+                })
+                .collect(),
+            average_heroes_down: average(fights.iter().map(|fight| fight.heroes_down).sum()),
+            average_adversaries_defeated: average(
+                fights.iter().map(|fight| fight.adversaries_defeated).sum(),
+            ),
+        }
+    }
+}
+
+fn count<T>(items: impl Iterator<Item = T>) -> u32 {
+    u32::try_from(items.count()).unwrap_or(u32::MAX)
+}
+
+/// Summary of many simulated fights of the same encounter
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Simulation {
+    runs: u32,
+    /// Share of fights won by the heroes, from 0 to 1
+    hero_win_rate: f64,
+    /// Share of fights won by the adversaries, from 0 to 1
+    adversary_win_rate: f64,
+    /// Share of fights called off after 20 rounds, from 0 to 1
+    stalemate_rate: f64,
+    average_rounds: f64,
+    /// Endurance lost by each hero per fight
+    average_endurance_lost: BTreeMap<String, f64>,
+    /// Wounds taken by each hero per fight
+    average_wounds: BTreeMap<String, f64>,
+    /// Heroes left unconscious or dying per fight
+    average_heroes_down: f64,
+    average_adversaries_defeated: f64,
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rand::rng_from_seed;
+
+    use super::*;
+
+    fn weapon(rating: u8, damage: u32, injury: u32) -> Weapon {
+        Weapon {
+            name: "Sword".to_owned(),
+            rating,
+            damage,
+            injury,
+        }
+    }
+
+    fn hero(name: &str, stance: Stance) -> Hero {
+        Hero {
+            name: name.to_owned(),
+            stance,
+            endurance: 26,
+            strength: 5,
+            parry: 14,
+            armour: 3,
+            weapon: weapon(3, 5, 16),
+        }
+    }
+
+    fn orc() -> Adversary {
+        Adversary {
+            name: "Orc Soldier".to_owned(),
+            attribute_level: 3,
+            endurance: 12,
+            might: 1,
+            hate: 3,
+            parry: 1,
+            armour: 2,
+            weapons: vec![weapon(2, 3, 16)],
+            fell_abilities: vec![],
+        }
+    }
+
+    fn encounter(heroes: Vec<Hero>, adversaries: usize) -> Encounter {
+        Encounter {
+            heroes,
+            adversaries: vec![orc(); adversaries],
+            conditions: vec![],
+        }
+    }
+
+    #[test]
+    fn fight_ends_with_one_side_overcome() {
+        let encounter = encounter(vec![hero("Aragorn", Stance::Open)], 2);
+        let fight = encounter.fight(&mut rng_from_seed(2965));
+
+        match fight.victor {
+            Victor::Heroes => assert_eq!(fight.adversaries_defeated, 2),
+            Victor::Adversaries => assert_eq!(fight.heroes_down, 1),
+            Victor::Nobody => assert_eq!(fight.rounds, MAX_ROUNDS),
+        }
+    }
+
+    #[test]
+    fn outnumbered_adversaries_are_beaten() {
+        let heroes = ["Aragorn", "Halbarad", "Lifstan", "Frár"]
+            .into_iter()
+            .map(|name| hero(name, Stance::Forward))
+            .collect();
+        let simulation = encounter(heroes, 1).simulate(&mut rng_from_seed(1), 200);
+
+        assert!(simulation.hero_win_rate > 0.95);
+        assert_eq!(simulation.average_endurance_lost.len(), 4);
+    }
+
+    #[test]
+    fn rates_add_up() {
+        let simulation = encounter(vec![hero("Aragorn", Stance::Defensive)], 3)
+            .simulate(&mut rng_from_seed(7), 100);
+
+        let total =
+            simulation.hero_win_rate + simulation.adversary_win_rate + simulation.stalemate_rate;
+        assert!((total - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rearward_heroes_are_spared_while_others_stand() {
+        let heroes = vec![
+            Hero {
+                endurance: 500,
+                armour: 20,
+                ..hero("Lifstan", Stance::Open)
+            },
+            hero("Odo", Stance::Rearward),
+        ];
+        let simulation = encounter(heroes, 3).simulate(&mut rng_from_seed(3), 50);
+
+        assert!(simulation.average_endurance_lost["Odo"].abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn skirmishers_make_no_attacks() {
+        let simulation =
+            encounter(vec![hero("Odo", Stance::Skirmish)], 1).simulate(&mut rng_from_seed(5), 20);
+
+        assert!(simulation.average_adversaries_defeated.abs() < f64::EPSILON);
+    }
+}
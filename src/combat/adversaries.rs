@@ -0,0 +1,386 @@
+/*!
+# Adversaries
+
+Statblocks of the foes a company is most likely to cross swords with in Eriador.
+*/
+
+use std::ops::RangeInclusive;
+
+use rand::{Rng, seq::IndexedRandom};
+use serde::{Deserialize, Serialize};
+use strum::{EnumIter, EnumString, IntoEnumIterator};
+use utoipa::ToSchema;
+
+use super::{Adversary, Weapon, fell_abilities::FellAbility};
+
+/// Valour ratings a company can average
+pub const VALOUR_RATINGS: RangeInclusive<u32> = 0..=6;
+
+/// Broad family an adversary belongs to
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    strum::Display,
+    EnumIter,
+    EnumString,
+    Eq,
+    Hash,
+    PartialEq,
+    Serialize,
+    ToSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum AdversaryType {
+    /// Goblins of the mountains and the soldiers of the Enemy's armies
+    Orcs,
+    /// Huge, cruel and stupid creatures of stone and darkness
+    Trolls,
+    /// Wild wolves and the Wargs who lead them
+    Wolves,
+    /// Restless spirits of the dead, bound to barrows and ruins
+    Undead,
+    /// Brigands, ruffians and servants of the Shadow among Men
+    EvilMen,
+}
+
+/// An adversary in the catalogue
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    strum::Display,
+    EnumIter,
+    EnumString,
+    Eq,
+    Hash,
+    PartialEq,
+    Serialize,
+    ToSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum AdversaryKind {
+    OrcSoldier,
+    OrcArcher,
+    GreatOrcChieftain,
+    HillTroll,
+    StoneTroll,
+    WildWolf,
+    WargChieftain,
+    BarrowWight,
+    Brigand,
+    RuffianChief,
+}
+
+impl AdversaryKind {
+    #[must_use]
+    pub fn adversary_type(self) -> AdversaryType {
+        match self {
+            AdversaryKind::OrcSoldier
+            | AdversaryKind::OrcArcher
+            | AdversaryKind::GreatOrcChieftain => AdversaryType::Orcs,
+            AdversaryKind::HillTroll | AdversaryKind::StoneTroll => AdversaryType::Trolls,
+            AdversaryKind::WildWolf | AdversaryKind::WargChieftain => AdversaryType::Wolves,
+            AdversaryKind::BarrowWight => AdversaryType::Undead,
+            AdversaryKind::Brigand | AdversaryKind::RuffianChief => AdversaryType::EvilMen,
+        }
+    }
+
+    /// Whether the adversary leads others of its kind. A group has at most one of each leader.
+    #[must_use]
+    pub fn is_leader(self) -> bool {
+        matches!(
+            self,
+            AdversaryKind::GreatOrcChieftain
+                | AdversaryKind::WargChieftain
+                | AdversaryKind::RuffianChief
+        )
+    }
+
+    /// Full statblock of the adversary, ready to take part in a fight
+    #[must_use]
+    #[expect(clippy::too_many_lines)]
+    pub fn statblock(self) -> Adversary {
+        match self {
+            AdversaryKind::OrcSoldier => Adversary {
+                name: "Orc Soldier".to_owned(),
+                attribute_level: 3,
+                endurance: 12,
+                might: 1,
+                hate: 3,
+                parry: 1,
+                armour: 2,
+                weapons: vec![weapon("Scimitar", 3, 4, 16), weapon("Orc Bow", 2, 3, 14)],
+                fell_abilities: vec![FellAbility::HateSunlight],
+            },
+            AdversaryKind::OrcArcher => Adversary {
+                name: "Orc Archer".to_owned(),
+                attribute_level: 3,
+                endurance: 10,
+                might: 1,
+                hate: 3,
+                parry: 0,
+                armour: 1,
+                weapons: vec![
+                    weapon("Orc Bow", 3, 3, 14),
+                    weapon("Jagged Knife", 2, 3, 14),
+                ],
+                fell_abilities: vec![FellAbility::HateSunlight, FellAbility::SnakeLikeSpeed],
+            },
+            AdversaryKind::GreatOrcChieftain => Adversary {
+                name: "Great Orc Chieftain".to_owned(),
+                attribute_level: 5,
+                endurance: 20,
+                might: 2,
+                hate: 5,
+                parry: 3,
+                armour: 3,
+                weapons: vec![weapon("Broad-bladed Sword", 5, 5, 16)],
+                fell_abilities: vec![FellAbility::HideousToughness, FellAbility::CommandingVoice],
+            },
+            AdversaryKind::HillTroll => Adversary {
+                name: "Hill-troll".to_owned(),
+                attribute_level: 6,
+                endurance: 42,
+                might: 3,
+                hate: 6,
+                parry: 2,
+                armour: 4,
+                weapons: vec![weapon("Heavy Club", 4, 8, 14)],
+                fell_abilities: vec![FellAbility::HideousToughness, FellAbility::SavageAssault],
+            },
+            AdversaryKind::StoneTroll => Adversary {
+                name: "Stone-troll".to_owned(),
+                attribute_level: 7,
+                endurance: 48,
+                might: 3,
+                hate: 7,
+                parry: 3,
+                armour: 4,
+                weapons: vec![weapon("Crushing Grip", 5, 9, 16)],
+                fell_abilities: vec![FellAbility::HideousToughness, FellAbility::HateSunlight],
+            },
+            AdversaryKind::WildWolf => Adversary {
+                name: "Wild Wolf".to_owned(),
+                attribute_level: 3,
+                endurance: 10,
+                might: 1,
+                hate: 2,
+                parry: 1,
+                armour: 1,
+                weapons: vec![weapon("Bite", 3, 3, 14)],
+                fell_abilities: vec![FellAbility::FearOfFire],
+            },
+            AdversaryKind::WargChieftain => Adversary {
+                name: "Warg Chieftain".to_owned(),
+                attribute_level: 5,
+                endurance: 20,
+                might: 2,
+                hate: 5,
+                parry: 2,
+                armour: 2,
+                weapons: vec![weapon("Rending Bite", 4, 5, 16)],
+                fell_abilities: vec![FellAbility::FearOfFire, FellAbility::SavageAssault],
+            },
+            AdversaryKind::BarrowWight => Adversary {
+                name: "Barrow-wight".to_owned(),
+                attribute_level: 6,
+                endurance: 24,
+                might: 2,
+                hate: 8,
+                parry: 3,
+                armour: 3,
+                weapons: vec![weapon("Ancient Blade", 5, 5, 16)],
+                fell_abilities: vec![FellAbility::Deathless, FellAbility::DreadfulSpells],
+            },
+            AdversaryKind::Brigand => Adversary {
+                name: "Brigand".to_owned(),
+                attribute_level: 3,
+                endurance: 12,
+                might: 1,
+                hate: 2,
+                parry: 2,
+                armour: 2,
+                weapons: vec![weapon("Sword", 3, 4, 16), weapon("Bow", 2, 3, 14)],
+                fell_abilities: vec![],
+            },
+            AdversaryKind::RuffianChief => Adversary {
+                name: "Ruffian Chief".to_owned(),
+                attribute_level: 4,
+                endurance: 16,
+                might: 2,
+                hate: 4,
+                parry: 3,
+                armour: 3,
+                weapons: vec![weapon("Broadsword", 4, 5, 16)],
+                fell_abilities: vec![FellAbility::CommandingVoice],
+            },
+        }
+    }
+}
+
+fn weapon(name: &str, rating: u8, damage: u32, injury: u32) -> Weapon {
+    Weapon {
+        name: name.to_owned(),
+        rating,
+        damage,
+        injury,
+    }
+}
+
+/// An adversary as listed in the catalogue
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AdversaryDescription {
+    id: AdversaryKind,
+    #[serde(rename = "type")]
+    adversary_type: AdversaryType,
+    #[serde(flatten)]
+    statblock: Adversary,
+}
+
+impl From<AdversaryKind> for AdversaryDescription {
+    fn from(kind: AdversaryKind) -> Self {
+        Self {
+            id: kind,
+            adversary_type: kind.adversary_type(),
+            statblock: kind.statblock(),
+        }
+    }
+}
+
+/// All adversaries in the catalogue, or only those of the given type
+#[must_use]
+pub fn catalogue(adversary_type: Option<AdversaryType>) -> Vec<AdversaryDescription> {
+    AdversaryKind::iter()
+        .filter(|kind| adversary_type.is_none_or(|wanted| kind.adversary_type() == wanted))
+        .map(AdversaryDescription::from)
+        .collect()
+}
+
+/// A band of adversaries sized to challenge a company
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AdversaryGroup {
+    #[serde(rename = "type")]
+    adversary_type: AdversaryType,
+    /// Sum of the adversaries' Attribute Levels
+    threat: u32,
+    adversaries: Vec<Adversary>,
+}
+
+impl AdversaryGroup {
+    /// Gather adversaries of one type until their combined Attribute Level matches the company.
+    ///
+    /// Each hero is worth 2 points of threat, plus their Valour.
+    pub fn generate<R: Rng + ?Sized>(
+        rng: &mut R,
+        adversary_type: AdversaryType,
+        heroes: u32,
+        valour: u32,
+    ) -> Self {
+        let budget = heroes.max(1).saturating_mul(valour.saturating_add(2));
+        let kinds = AdversaryKind::iter()
+            .filter(|kind| kind.adversary_type() == adversary_type)
+            .collect::<Vec<_>>();
+
+        let mut picked = Vec::<AdversaryKind>::new();
+        let mut threat = 0u32;
+        loop {
+            let affordable = kinds
+                .iter()
+                .copied()
+                .filter(|kind| threat.saturating_add(kind.statblock().attribute_level) <= budget)
+                .filter(|kind| !kind.is_leader() || !picked.contains(kind))
+                .collect::<Vec<_>>();
+            let Some(&kind) = affordable.choose(rng) else {
+                break;
+            };
+            threat += kind.statblock().attribute_level;
+            picked.push(kind);
+        }
+
+        // Even the weakest company meets at least one foe
+        if picked.is_empty()
+            && let Some(&weakest) = kinds
+                .iter()
+                .min_by_key(|kind| kind.statblock().attribute_level)
+        {
+            threat = weakest.statblock().attribute_level;
+            picked.push(weakest);
+        }
+
+        Self {
+            adversary_type,
+            threat,
+            adversaries: picked.into_iter().map(AdversaryKind::statblock).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rand::rng_from_seed;
+
+    use super::*;
+
+    #[test]
+    fn every_type_has_adversaries() {
+        for adversary_type in AdversaryType::iter() {
+            assert!(!catalogue(Some(adversary_type)).is_empty());
+        }
+        assert_eq!(catalogue(None).len(), AdversaryKind::iter().count());
+    }
+
+    #[test]
+    fn every_adversary_is_armed() {
+        for kind in AdversaryKind::iter() {
+            let statblock = kind.statblock();
+
+            assert!(!statblock.weapons.is_empty());
+            assert!(statblock.might >= 1);
+        }
+    }
+
+    #[test]
+    fn group_threat_matches_company() {
+        let mut rng = rng_from_seed(2965);
+        let group = AdversaryGroup::generate(&mut rng, AdversaryType::Orcs, 4, 2);
+
+        assert!(group.threat <= 16);
+        assert!(group.threat > 16 - 3);
+        assert_eq!(
+            group
+                .adversaries
+                .iter()
+                .map(|adversary| adversary.attribute_level)
+                .sum::<u32>(),
+            group.threat
+        );
+    }
+
+    #[test]
+    fn groups_have_at_most_one_leader_of_each_kind() {
+        let mut rng = rng_from_seed(7);
+        for _ in 0..20 {
+            let group = AdversaryGroup::generate(&mut rng, AdversaryType::EvilMen, 8, 4);
+            let chiefs = group
+                .adversaries
+                .iter()
+                .filter(|adversary| adversary.name == "Ruffian Chief")
+                .count();
+
+            assert!(chiefs <= 1);
+        }
+    }
+
+    #[test]
+    fn weak_company_still_meets_a_foe() {
+        let group = AdversaryGroup::generate(&mut rng_from_seed(1), AdversaryType::Trolls, 1, 0);
+
+        assert_eq!(group.adversaries.len(), 1);
+        assert_eq!(group.adversaries[0].name, "Hill-troll");
+    }
+}
@@ -0,0 +1,346 @@
+/*!
+# Fell Abilities
+
+Special powers of adversaries, described as effects the combat engine applies on its own. Most
+of them are fuelled by the adversary's Hate or Resolve.
+*/
+
+use serde::{Deserialize, Serialize};
+use strum::{EnumIter, EnumString, IntoEnumIterator};
+use utoipa::ToSchema;
+
+/// Circumstances of a fight some adversaries cannot bear
+#[derive(Clone, Copy, Debug, Deserialize, strum::Display, Eq, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum Condition {
+    /// The heroes brandish torches or fight beside a blazing fire
+    Fire,
+    /// The fight takes place under the open sky by day
+    Daylight,
+}
+
+/// What a Fell Ability does in a fight
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, ToSchema)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum Effect {
+    /// Spend Hate to shrug off a Wound from a Piercing Blow
+    IgnoreWound { hate_cost: u32 },
+    /// Spend Hate to attack a second time in the round
+    ExtraAttack { hate_cost: u32 },
+    /// Spend Hate to turn a hit against the adversary into a miss
+    CancelHit { hate_cost: u32 },
+    /// Spend Hate at the start of each round to recover Endurance equal to Attribute Level
+    Regenerate { hate_cost: u32 },
+    /// Spend Hate at the start of each round to make the heroes' attacks worse-favoured
+    Dread { hate_cost: u32 },
+    /// The adversary is Weary while the condition holds
+    WearyWhen { condition: Condition },
+    /// Companions lose no Hate when one of them falls, as long as this adversary stands
+    SteadyAllies,
+}
+
+impl Effect {
+    /// Hate spent on the effect, for effects that are paid for
+    #[must_use]
+    pub fn hate_cost(self) -> Option<u32> {
+        match self {
+            Effect::IgnoreWound { hate_cost }
+            | Effect::ExtraAttack { hate_cost }
+            | Effect::CancelHit { hate_cost }
+            | Effect::Regenerate { hate_cost }
+            | Effect::Dread { hate_cost } => Some(hate_cost),
+            Effect::WearyWhen { .. } | Effect::SteadyAllies => None,
+        }
+    }
+}
+
+/// A Fell Ability in the registry, referenced by its kebab-case id
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    strum::Display,
+    EnumIter,
+    EnumString,
+    Eq,
+    Hash,
+    PartialEq,
+    Serialize,
+    ToSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum FellAbility {
+    CommandingVoice,
+    Deathless,
+    DreadfulSpells,
+    FearOfFire,
+    HateSunlight,
+    HideousToughness,
+    SavageAssault,
+    SnakeLikeSpeed,
+}
+
+impl FellAbility {
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            FellAbility::CommandingVoice => "Commanding Voice",
+            FellAbility::Deathless => "Deathless",
+            FellAbility::DreadfulSpells => "Dreadful Spells",
+            FellAbility::FearOfFire => "Fear of Fire",
+            FellAbility::HateSunlight => "Hate Sunlight",
+            FellAbility::HideousToughness => "Hideous Toughness",
+            FellAbility::SavageAssault => "Savage Assault",
+            FellAbility::SnakeLikeSpeed => "Snake-like Speed",
+        }
+    }
+
+    #[must_use]
+    pub fn description(self) -> &'static str {
+        match self {
+            FellAbility::CommandingVoice => {
+                "A cruel leader keeps its followers fighting even as their companions fall."
+            }
+            FellAbility::Deathless => {
+                "Dark sorcery knits the adversary's wounds together as long as its Hate endures."
+            }
+            FellAbility::DreadfulSpells => {
+                "Chilling words sap the heroes' will to fight, hampering their blows."
+            }
+            FellAbility::FearOfFire => "The adversary shrinks back from flame and burning brands.",
+            FellAbility::HateSunlight => "The adversary is weakened and cowed by the light of day.",
+            FellAbility::HideousToughness => {
+                "The adversary fights on through wounds that would fell any other creature."
+            }
+            FellAbility::SavageAssault => "The adversary strikes again and again in a frenzy.",
+            FellAbility::SnakeLikeSpeed => {
+                "The adversary twists away from blows that should have struck home."
+            }
+        }
+    }
+
+    /// What the ability does when the adversary fights
+    #[must_use]
+    pub fn effect(self) -> Effect {
+        match self {
+            FellAbility::CommandingVoice => Effect::SteadyAllies,
+            FellAbility::Deathless => Effect::Regenerate { hate_cost: 1 },
+            FellAbility::DreadfulSpells => Effect::Dread { hate_cost: 1 },
+            FellAbility::FearOfFire => Effect::WearyWhen {
+                condition: Condition::Fire,
+            },
+            FellAbility::HateSunlight => Effect::WearyWhen {
+                condition: Condition::Daylight,
+            },
+            FellAbility::HideousToughness => Effect::IgnoreWound { hate_cost: 1 },
+            FellAbility::SavageAssault => Effect::ExtraAttack { hate_cost: 1 },
+            FellAbility::SnakeLikeSpeed => Effect::CancelHit { hate_cost: 1 },
+        }
+    }
+}
+
+/// A Fell Ability as listed in the registry
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FellAbilityDescription {
+    id: FellAbility,
+    name: &'static str,
+    description: &'static str,
+    effect: Effect,
+}
+
+impl From<FellAbility> for FellAbilityDescription {
+    fn from(ability: FellAbility) -> Self {
+        Self {
+            id: ability,
+            name: ability.name(),
+            description: ability.description(),
+            effect: ability.effect(),
+        }
+    }
+}
+
+/// Every Fell Ability adversaries can reference
+#[must_use]
+pub fn registry() -> Vec<FellAbilityDescription> {
+    FellAbility::iter()
+        .map(FellAbilityDescription::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        combat::{Adversary, Encounter, Hero, Simulation, Stance, Weapon},
+        rand::rng_from_seed,
+    };
+
+    use super::*;
+
+    const RUNS: u32 = 400;
+
+    fn weapon(rating: u8, damage: u32, injury: u32) -> Weapon {
+        Weapon {
+            name: "Blade".to_owned(),
+            rating,
+            damage,
+            injury,
+        }
+    }
+
+    fn hero(name: &str) -> Hero {
+        Hero {
+            name: name.to_owned(),
+            stance: Stance::Open,
+            endurance: 30,
+            strength: 5,
+            parry: 14,
+            armour: 2,
+            weapon: weapon(3, 5, 16),
+        }
+    }
+
+    fn adversary(fell_abilities: Vec<FellAbility>) -> Adversary {
+        Adversary {
+            name: "Foe".to_owned(),
+            attribute_level: 4,
+            endurance: 20,
+            might: 1,
+            hate: 4,
+            parry: 1,
+            armour: 2,
+            weapons: vec![weapon(3, 4, 16)],
+            fell_abilities,
+        }
+    }
+
+    /// Simulate the same fight with and without an ability
+    fn compare(
+        heroes: Vec<Hero>,
+        adversaries: impl Fn(Vec<FellAbility>) -> Vec<Adversary>,
+        ability: FellAbility,
+        conditions: Vec<Condition>,
+    ) -> (Simulation, Simulation) {
+        let simulate = |fell_abilities| {
+            Encounter {
+                heroes: heroes.clone(),
+                adversaries: adversaries(fell_abilities),
+                conditions: conditions.clone(),
+            }
+            .simulate(&mut rng_from_seed(2965), RUNS)
+        };
+        (simulate(vec![ability]), simulate(vec![]))
+    }
+
+    fn one_on_one(ability: FellAbility, conditions: Vec<Condition>) -> (Simulation, Simulation) {
+        compare(
+            vec![hero("Aragorn")],
+            |fell_abilities| vec![adversary(fell_abilities)],
+            ability,
+            conditions,
+        )
+    }
+
+    #[test]
+    fn every_ability_is_registered() {
+        assert_eq!(registry().len(), FellAbility::iter().count());
+        assert_eq!(
+            serde_json::from_str::<FellAbility>(r#""snake-like-speed""#).unwrap(),
+            FellAbility::SnakeLikeSpeed
+        );
+    }
+
+    #[test]
+    fn commanding_voice_keeps_companions_fighting() {
+        let (with, without) = compare(
+            vec![hero("Aragorn"), hero("Halbarad")],
+            |fell_abilities| {
+                let mut leader = adversary(fell_abilities);
+                leader.endurance = 60;
+                let mut followers = vec![
+                    Adversary {
+                        endurance: 6,
+                        hate: 1,
+                        ..adversary(vec![])
+                    };
+                    4
+                ];
+                followers.insert(0, leader);
+                followers
+            },
+            FellAbility::CommandingVoice,
+            vec![],
+        );
+
+        assert!(with.hero_win_rate < without.hero_win_rate);
+    }
+
+    #[test]
+    fn deathless_recovers_endurance() {
+        let (with, without) = one_on_one(FellAbility::Deathless, vec![]);
+
+        assert!(with.average_rounds > without.average_rounds);
+    }
+
+    #[test]
+    fn dreadful_spells_hamper_heroes() {
+        let (with, without) = one_on_one(FellAbility::DreadfulSpells, vec![]);
+
+        assert!(with.average_rounds > without.average_rounds);
+    }
+
+    #[test]
+    fn fear_of_fire_weakens_attacks_near_flames() {
+        let (with, without) = one_on_one(FellAbility::FearOfFire, vec![Condition::Fire]);
+        let (unlit, _) = one_on_one(FellAbility::FearOfFire, vec![]);
+
+        assert!(with.average_endurance_lost["Aragorn"] < without.average_endurance_lost["Aragorn"]);
+        assert!(
+            (unlit.average_endurance_lost["Aragorn"] - without.average_endurance_lost["Aragorn"])
+                .abs()
+                < f64::EPSILON
+        );
+    }
+
+    #[test]
+    fn hate_sunlight_weakens_attacks_by_day() {
+        let (with, without) = one_on_one(FellAbility::HateSunlight, vec![Condition::Daylight]);
+
+        assert!(with.average_endurance_lost["Aragorn"] < without.average_endurance_lost["Aragorn"]);
+    }
+
+    #[test]
+    fn hideous_toughness_ignores_wounds() {
+        let mut piercing = hero("Aragorn");
+        piercing.weapon = weapon(6, 1, 40);
+        let (with, without) = compare(
+            vec![piercing],
+            |fell_abilities| {
+                vec![Adversary {
+                    endurance: 200,
+                    ..adversary(fell_abilities)
+                }]
+            },
+            FellAbility::HideousToughness,
+            vec![],
+        );
+
+        assert!(with.average_rounds > without.average_rounds);
+    }
+
+    #[test]
+    fn savage_assault_strikes_twice() {
+        let (with, without) = one_on_one(FellAbility::SavageAssault, vec![]);
+
+        assert!(with.average_endurance_lost["Aragorn"] > without.average_endurance_lost["Aragorn"]);
+    }
+
+    #[test]
+    fn snake_like_speed_avoids_hits() {
+        let (with, without) = one_on_one(FellAbility::SnakeLikeSpeed, vec![]);
+
+        assert!(with.average_rounds > without.average_rounds);
+    }
+}
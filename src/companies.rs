@@ -0,0 +1,286 @@
+/*!
+# Companies
+
+A company (or fellowship) of Player-heroes who have chosen to face the Shadow together.
+*/
+
+use std::ops::RangeInclusive;
+
+use rand::{Rng, seq::SliceRandom};
+use serde::{Deserialize, Deserializer, Serialize};
+use strum::IntoEnumIterator;
+use utoipa::ToSchema;
+
+use crate::{
+    calendar::Date, callings::Calling, characters::Character, cultures::HeroicCulture,
+    patrons::Patron, sanctuaries::Sanctuary,
+};
+
+pub mod fellowship;
+
+/// Number of heroes a generated company can have
+pub const COMPANY_SIZES: RangeInclusive<usize> = 1..=8;
+
+/// A hero's chosen Fellowship Focus: the companion they care for most.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, ToSchema)]
+pub struct FellowshipFocus {
+    /// Name of the hero who chose the focus
+    hero: String,
+    /// Name of the companion chosen as their focus
+    focus: String,
+}
+
+/// A group of Player-heroes and what they share.
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct Company {
+    heroes: Vec<Character>,
+    /// Points the company can spend to recover Hope
+    fellowship_rating: u32,
+    /// Fellowship points left to spend until the next Fellowship phase
+    #[serde(default)]
+    fellowship_points: u32,
+    /// The patron supporting the company, if any
+    patron: Option<Patron>,
+    /// The sanctuary the company returns to during the Fellowship phase
+    #[serde(default, deserialize_with = "lenient_safe_haven")]
+    safe_haven: Option<Sanctuary>,
+    fellowship_focus: Vec<FellowshipFocus>,
+    /// Songs written by the heroes, which can be sung to lift the company's spirits
+    #[serde(default)]
+    songs: Vec<String>,
+    /// The company's current date in the campaign
+    date: Option<Date>,
+}
+
+/// Read a Safe Haven by id or by name, as companies saved it before sanctuaries had rules of
+/// their own. Havens that are not a known sanctuary are left out, so requests check theirs
+/// before a company is read.
+fn lenient_safe_haven<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Sanctuary>, D::Error> {
+    Ok(Option::<String>::deserialize(deserializer)?
+        .as_deref()
+        .and_then(Sanctuary::from_name))
+}
+
+impl Company {
+    /// Generate a balanced company of `size` heroes.
+    ///
+    /// Callings and cultures are only repeated once every other option has been used, and
+    /// Fellowship Focus links form a single circle so that every hero is someone's focus.
+    pub fn generate<R: Rng + ?Sized>(rng: &mut R, size: usize) -> Self {
+        let cultures = balanced(rng, HeroicCulture::iter(), size);
+        let callings = balanced(rng, Calling::iter(), size);
+        let heroes = cultures
+            .into_iter()
+            .zip(callings)
+            .map(|(culture, calling)| Character::random(rng, culture, calling))
+            .collect::<Vec<_>>();
+
+        let fellowship_focus = if heroes.len() < 2 {
+            vec![]
+        } else {
+            heroes
+                .iter()
+                .zip(heroes.iter().cycle().skip(1))
+                .map(|(hero, focus)| FellowshipFocus {
+                    hero: hero.name().to_owned(),
+                    focus: focus.name().to_owned(),
+                })
+                .collect()
+        };
+
+        let fellowship_rating = fellowship_rating(heroes.len(), None);
+        Self {
+            fellowship_rating,
+            fellowship_points: fellowship_rating,
+            heroes,
+            patron: None,
+            safe_haven: None,
+            fellowship_focus,
+            songs: vec![],
+            date: Some(Date::campaign_start()),
+        }
+    }
+
+    /// A lone hero travelling without companions, as in Strider Mode. With nobody to share
+    /// the road with, they have no Fellowship pool.
+    #[must_use]
+    pub fn alone(
+        hero: Character,
+        patron: Option<Patron>,
+        safe_haven: Option<Sanctuary>,
+        date: Option<Date>,
+    ) -> Self {
+        Self {
+            heroes: vec![hero],
+            fellowship_rating: 0,
+            fellowship_points: 0,
+            patron,
+            safe_haven,
+            fellowship_focus: vec![],
+            songs: vec![],
+            date,
+        }
+    }
+
+    #[must_use]
+    pub fn heroes(&self) -> &[Character] {
+        &self.heroes
+    }
+
+    /// Seek out the patron best suited to the company's heroes, adding their Fellowship bonus
+    /// to the Fellowship Rating.
+    pub fn choose_patron<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        let patron = Patron::suitable_for(rng, &self.heroes);
+        self.patron = Some(patron);
+        self.fellowship_rating = fellowship_rating(self.heroes.len(), self.patron);
+        self.fellowship_points = self.fellowship_rating;
+    }
+
+    /// Choose the sanctuary most of the heroes call home as the company's Safe Haven
+    pub fn choose_safe_haven(&mut self) {
+        self.safe_haven = Some(Sanctuary::suitable_for(&self.heroes));
+    }
+}
+
+/// Starting Fellowship Rating: one point for every Player-hero in the company, plus the
+/// patron's Fellowship bonus.
+#[must_use]
+pub fn fellowship_rating(heroes: usize, patron: Option<Patron>) -> u32 {
+    u32::try_from(heroes)
+        .unwrap_or(u32::MAX)
+        .saturating_add(patron.map_or(0, Patron::fellowship_bonus))
+}
+
+/// Pick `size` values, only repeating a value once all the others have been used.
+fn balanced<T: Clone, R: Rng + ?Sized>(
+    rng: &mut R,
+    values: impl Iterator<Item = T>,
+    size: usize,
+) -> Vec<T> {
+    let values = values.collect::<Vec<_>>();
+    let mut picked = Vec::with_capacity(size);
+
+    while picked.len() < size {
+        let mut round = values.clone();
+        round.shuffle(rng);
+        picked.extend(round.into_iter().take(size - picked.len()));
+    }
+
+    picked
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use strum::IntoEnumIterator;
+
+    use crate::rand::rng_from_os_rng;
+
+    use super::*;
+
+    #[test]
+    fn fellowship_rating_equals_number_of_heroes() {
+        let mut rng = rng_from_os_rng();
+        for size in COMPANY_SIZES {
+            let company = Company::generate(&mut rng, size);
+
+            assert_eq!(company.heroes.len(), size);
+            assert_eq!(company.fellowship_rating, u32::try_from(size).unwrap());
+        }
+    }
+
+    #[test]
+    fn callings_and_cultures_are_not_repeated_until_exhausted() {
+        let mut rng = rng_from_os_rng();
+        let company = Company::generate(&mut rng, 6);
+
+        let callings = company.heroes.iter().map(Character::calling);
+        let cultures = company.heroes.iter().map(Character::heroic_culture);
+        assert_eq!(callings.collect::<HashSet<_>>().len(), 6);
+        assert_eq!(cultures.collect::<HashSet<_>>().len(), 6);
+    }
+
+    #[test]
+    fn every_hero_is_someones_focus() {
+        let mut rng = rng_from_os_rng();
+        let company = Company::generate(&mut rng, 4);
+
+        assert_eq!(company.fellowship_focus.len(), 4);
+        for hero in &company.heroes {
+            assert!(
+                company
+                    .fellowship_focus
+                    .iter()
+                    .any(|link| link.focus == hero.name() && link.hero != hero.name())
+            );
+        }
+    }
+
+    #[test]
+    fn patron_adds_fellowship_bonus() {
+        let mut rng = rng_from_os_rng();
+        let mut company = Company::generate(&mut rng, 3);
+        company.choose_patron(&mut rng);
+        let patron = company.patron.unwrap();
+
+        assert_eq!(company.fellowship_rating, 3 + patron.fellowship_bonus());
+        assert_eq!(
+            patron.affinity(&company.heroes),
+            Patron::iter()
+                .map(|patron| patron.affinity(&company.heroes))
+                .max()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn safe_haven_is_home_to_heroes() {
+        let mut company = Company::generate(&mut rng_from_os_rng(), 6);
+        company.choose_safe_haven();
+        let haven = company.safe_haven.unwrap();
+
+        assert_eq!(haven.affinity(&company.heroes), 1);
+    }
+
+    #[test]
+    fn lone_hero_has_no_focus() {
+        let company = Company::generate(&mut rng_from_os_rng(), 1);
+
+        assert!(company.fellowship_focus.is_empty());
+    }
+
+    #[test]
+    fn loads_companies_with_free_text_safe_haven() {
+        let mut company =
+            serde_json::to_value(Company::generate(&mut rng_from_os_rng(), 2)).unwrap();
+        // Companies saved before then had no date either
+        company.as_object_mut().unwrap().remove("date");
+        let load = |company: &serde_json::Value, haven: &str| {
+            let mut company = company.clone();
+            company["safe_haven"] = haven.into();
+            serde_json::from_value::<Company>(company)
+                .unwrap()
+                .safe_haven
+        };
+
+        assert_eq!(load(&company, "Bree"), Some(Sanctuary::Bree));
+        assert_eq!(load(&company, "the Shire"), Some(Sanctuary::TheShire));
+        assert_eq!(load(&company, "GREY HAVENS"), Some(Sanctuary::GreyHavens));
+        assert_eq!(
+            load(&company, "thorins-halls"),
+            Some(Sanctuary::ThorinsHalls)
+        );
+        assert_eq!(load(&company, "Weathertop"), None);
+
+        company.as_object_mut().unwrap().remove("safe_haven");
+        assert!(
+            serde_json::from_value::<Company>(company)
+                .unwrap()
+                .safe_haven
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,518 @@
+/*!
+# Fellowship phase
+
+The time the heroes spend at rest between adventures. Each hero recovers Hope and sheds some of
+their Shadow, then pursues the undertaking they chose. The Fellowship phase at the end of the
+year is Yule, when heroes recover all their Hope and can heal their Shadow Scars.
+
+Most undertakings can only be pursued at a sanctuary offering them, so the company's Safe Haven
+decides what its heroes can do. When the company keeps a campaign date, the phase moves it on by
+a month, or to the start of the new year at Yule.
+*/
+
+use std::collections::BTreeSet;
+
+use anyhow::{Context, bail};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{calendar::Date, characters::Character, sanctuaries::Sanctuary};
+
+use super::Company;
+
+/// Something a hero can do during a Fellowship phase
+#[derive(Clone, Debug, Deserialize, strum::EnumDiscriminants, Serialize, ToSchema)]
+#[serde(tag = "undertaking", rename_all = "kebab-case")]
+#[strum_discriminants(
+    name(UndertakingKind),
+    derive(Deserialize, strum::Display, Serialize, ToSchema),
+    serde(rename_all = "kebab-case"),
+    strum(serialize_all = "title_case")
+)]
+pub enum Undertaking {
+    /// Remove a Shadow Scar. Only possible during Yule.
+    HealScars,
+    /// Visit the company's patron to seek advice or a new errand
+    MeetPatron,
+    /// Spend Treasure to reach the next Standard of Living
+    RaiseStandardOfLiving,
+    /// Add a point to the company's Fellowship pool until the next Fellowship phase
+    StrengthenFellowship,
+    /// Learn more about a Famous Weapon, Armour or magical item the hero carries
+    StudyMagicalItems { item: String },
+    /// Compose a song the company can sing to lift their spirits
+    WriteASong { title: String },
+}
+
+/// Days a Fellowship phase lasts, unless it is Yule
+const FELLOWSHIP_PHASE_DAYS: u32 = 30;
+
+/// Undertakings heroes can pursue without a Safe Haven
+const ANYWHERE: &[UndertakingKind] = &[
+    UndertakingKind::StrengthenFellowship,
+    UndertakingKind::WriteASong,
+];
+
+/// The undertaking chosen by one of the heroes
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct ChosenUndertaking {
+    hero: String,
+    #[serde(flatten)]
+    undertaking: Undertaking,
+}
+
+/// Everything needed to resolve a Fellowship phase
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct FellowshipPhase {
+    company: Company,
+    /// Whether this is the Fellowship phase at the end of the year
+    #[serde(default)]
+    yule: bool,
+    /// At most one undertaking per hero
+    #[serde(default)]
+    undertakings: Vec<ChosenUndertaking>,
+}
+
+/// The company at the end of the Fellowship phase, and how it got there
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FellowshipOutcome {
+    company: Company,
+    /// Every rule applied, in order
+    log: Vec<String>,
+}
+
+impl ChosenUndertaking {
+    #[must_use]
+    pub fn new(hero: &str, undertaking: Undertaking) -> Self {
+        Self {
+            hero: hero.to_owned(),
+            undertaking,
+        }
+    }
+}
+
+impl FellowshipPhase {
+    #[must_use]
+    pub fn new(company: Company, yule: bool, undertakings: Vec<ChosenUndertaking>) -> Self {
+        Self {
+            company,
+            yule,
+            undertakings,
+        }
+    }
+
+    /// Apply the rules of the Fellowship phase to the company
+    ///
+    /// # Errors
+    ///
+    /// If an undertaking was chosen by someone who isn't in the company, by a hero who already
+    /// chose one, is not available at the company's Safe Haven, or cannot be carried out.
+    pub fn resolve(self) -> anyhow::Result<FellowshipOutcome> {
+        let Self {
+            mut company,
+            yule,
+            undertakings,
+        } = self;
+        let mut log = vec![];
+
+        let mut chosen = BTreeSet::new();
+        for ChosenUndertaking { hero, undertaking } in &undertakings {
+            if !company.heroes.iter().any(|h| h.name() == hero) {
+                bail!("{hero} is not part of the company");
+            }
+            if !chosen.insert(hero) {
+                bail!("{hero} can only choose one undertaking");
+            }
+            let kind = UndertakingKind::from(undertaking);
+            match company.safe_haven {
+                Some(haven) if !haven.undertakings().contains(&kind) => {
+                    bail!("{kind} is not possible in {}", haven.name());
+                }
+                None if !ANYWHERE.contains(&kind) => {
+                    bail!("{kind} is only possible at a Safe Haven");
+                }
+                _ => {}
+            }
+        }
+
+        log.push(if yule {
+            "Yule: the year ends, and the heroes rest with their kin.".to_owned()
+        } else {
+            "Fellowship phase: the heroes rest from their adventures.".to_owned()
+        });
+        if let Some(haven) = company.safe_haven {
+            log.push(format!("The company spends the phase in {}.", haven.name()));
+        }
+
+        for hero in &mut company.heroes {
+            let recovered = if yule {
+                hero.recover_hope(u8::MAX)
+            } else {
+                hero.recover_hope(hero.heart())
+            };
+            log.push(format!(
+                "{} recovers {recovered} Hope ({} Hope).",
+                hero.name(),
+                hero.hope()
+            ));
+
+            let removed = if yule {
+                hero.remove_shadow(hero.wisdom())
+            } else if company.safe_haven.is_some_and(Sanctuary::sheds_shadow) {
+                hero.remove_shadow(1)
+            } else {
+                0
+            };
+            if removed > 0 {
+                log.push(format!(
+                    "{} sheds {removed} Shadow ({} Shadow left).",
+                    hero.name(),
+                    hero.shadow()
+                ));
+            }
+        }
+
+        company.fellowship_points = company.fellowship_rating;
+        log.push(format!(
+            "The company's Fellowship pool is refreshed to {} points.",
+            company.fellowship_rating
+        ));
+
+        for ChosenUndertaking { hero, undertaking } in undertakings {
+            let entry = company.undertake(&hero, undertaking, yule)?;
+            log.push(entry);
+        }
+
+        if let Some(date) = company.date {
+            let end = if yule {
+                Date::new_year(date.year().saturating_add(1))
+            } else {
+                date.add_days(FELLOWSHIP_PHASE_DAYS)
+            };
+            company.date = Some(end);
+            log.push(format!("The Fellowship phase ends on {end}."));
+        }
+
+        Ok(FellowshipOutcome { company, log })
+    }
+}
+
+impl Company {
+    fn hero_mut(&mut self, name: &str) -> anyhow::Result<&mut Character> {
+        self.heroes
+            .iter_mut()
+            .find(|hero| hero.name() == name)
+            .with_context(|| format!("{name} is not part of the company"))
+    }
+
+    /// Carry out a hero's undertaking, describing what happened
+    fn undertake(
+        &mut self,
+        hero: &str,
+        undertaking: Undertaking,
+        yule: bool,
+    ) -> anyhow::Result<String> {
+        match undertaking {
+            Undertaking::HealScars => {
+                if !yule {
+                    bail!("{hero} can only heal scars during Yule");
+                }
+                let character = self.hero_mut(hero)?;
+                if !character.heal_scar() {
+                    bail!("{hero} has no Shadow Scars to heal");
+                }
+                Ok(format!(
+                    "{hero} heals a Shadow Scar ({} left).",
+                    character.shadow_scars()
+                ))
+            }
+            Undertaking::MeetPatron => {
+                let Some(patron) = self.patron else {
+                    bail!("{hero} cannot meet a patron: the company has none");
+                };
+                if let Some(haven) = self.safe_haven
+                    && !haven.patrons().contains(&patron)
+                {
+                    bail!("{} cannot be met in {}", patron.name(), haven.name());
+                }
+                Ok(format!(
+                    "{hero} meets {}, who offers advice and perhaps a new errand.",
+                    patron.name()
+                ))
+            }
+            Undertaking::RaiseStandardOfLiving => {
+                let character = self.hero_mut(hero)?;
+                let standard = character.raise_standard_of_living()?;
+                Ok(format!(
+                    "{hero} spends {} Treasure to live a {standard} life ({} Treasure left).",
+                    standard.cost(),
+                    character.treasure()
+                ))
+            }
+            Undertaking::StrengthenFellowship => {
+                self.fellowship_points += 1;
+                Ok(format!(
+                    "{hero} strengthens the fellowship: the pool rises to {} points.",
+                    self.fellowship_points
+                ))
+            }
+            Undertaking::StudyMagicalItems { item } => {
+                if !self.hero_mut(hero)?.carries(&item) {
+                    bail!("{hero} does not carry {item}");
+                }
+                Ok(format!("{hero} studies {item}, learning more of its past."))
+            }
+            Undertaking::WriteASong { title } => {
+                if self.songs.contains(&title) {
+                    bail!("The company already has a song called {title:?}");
+                }
+                let entry = format!("{hero} writes a song: {title:?}.");
+                self.songs.push(title);
+                Ok(entry)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{characters::StandardOfLiving, patrons::Patron};
+
+    use super::*;
+
+    fn company() -> Company {
+        serde_json::from_str(
+            r#"{
+                "heroes": [
+                    {
+                        "heroic_culture": "bardings",
+                        "calling": "captain",
+                        "name": "Lifstan",
+                        "wisdom": 2,
+                        "heart": 5,
+                        "hope": 4,
+                        "max_hope": 14,
+                        "shadow": 3,
+                        "shadow_scars": 1,
+                        "treasure": 100,
+                        "famous_gear": ["Dragon-bane"]
+                    },
+                    {
+                        "heroic_culture": "hobbits-of-the-shire",
+                        "calling": "scholar",
+                        "name": "Marigold",
+                        "hope": 10,
+                        "shadow": 2
+                    }
+                ],
+                "fellowship_rating": 2,
+                "patron": null,
+                "safe_haven": "rivendell",
+                "fellowship_focus": []
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn phase(yule: bool, undertakings: &[(&str, Undertaking)]) -> FellowshipPhase {
+        FellowshipPhase {
+            company: company(),
+            yule,
+            undertakings: undertakings
+                .iter()
+                .map(|(hero, undertaking)| ChosenUndertaking {
+                    hero: (*hero).to_owned(),
+                    undertaking: undertaking.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn phase_advances_campaign_date() {
+        let mut autumn = phase(false, &[]);
+        autumn.company.date = Some("20 Blotmath 2965".parse().unwrap());
+        let outcome = autumn.resolve().unwrap();
+        assert_eq!(
+            outcome.company.date.unwrap().to_string(),
+            "20 Foreyule 2965"
+        );
+
+        let mut yule = phase(true, &[]);
+        yule.company.date = Some("20 Foreyule 2965".parse().unwrap());
+        let outcome = yule.resolve().unwrap();
+        assert_eq!(outcome.company.date, Some(Date::new_year(2966)));
+        assert!(outcome.log.last().unwrap().contains("2 Yule 2966"));
+    }
+
+    #[test]
+    fn heroes_recover_hope_equal_to_heart() {
+        let mut phase = phase(false, &[]);
+        phase.company.safe_haven = None;
+        let outcome = phase.resolve().unwrap();
+        let [lifstan, marigold] = &outcome.company.heroes[..] else {
+            panic!("expected two heroes");
+        };
+
+        assert_eq!(lifstan.hope(), 9);
+        assert_eq!(marigold.hope(), 12);
+        assert_eq!(lifstan.shadow(), 3);
+        assert_eq!(outcome.company.fellowship_points, 2);
+    }
+
+    #[test]
+    fn yule_restores_all_hope_and_removes_shadow() {
+        let outcome = phase(true, &[("Lifstan", Undertaking::HealScars)])
+            .resolve()
+            .unwrap();
+        let lifstan = &outcome.company.heroes[0];
+
+        assert_eq!(lifstan.hope(), 14);
+        assert_eq!(lifstan.shadow(), 1);
+        assert_eq!(lifstan.shadow_scars(), 0);
+        assert_eq!(outcome.company.heroes[1].shadow(), 1);
+    }
+
+    #[test]
+    fn safe_haven_removes_shadow() {
+        let outcome = phase(false, &[]).resolve().unwrap();
+        assert_eq!(outcome.company.heroes[0].shadow(), 2);
+
+        let mut phase = phase(false, &[]);
+        phase.company.safe_haven = Some(Sanctuary::TharbadRuins);
+        let outcome = phase.resolve().unwrap();
+        assert_eq!(outcome.company.heroes[0].shadow(), 3);
+    }
+
+    #[test]
+    fn undertakings_must_be_available_at_safe_haven() {
+        let undertakings = [("Lifstan", Undertaking::RaiseStandardOfLiving)];
+        let at = |safe_haven| {
+            let mut phase = phase(false, &undertakings);
+            phase.company.safe_haven = safe_haven;
+            phase.resolve()
+        };
+
+        assert!(at(Some(Sanctuary::Bree)).is_ok());
+        assert_eq!(
+            at(Some(Sanctuary::GreyHavens)).unwrap_err().to_string(),
+            "Raise Standard Of Living is not possible in the Grey Havens"
+        );
+        assert!(at(None).is_err());
+    }
+
+    #[test]
+    fn scars_are_only_healed_at_yule() {
+        assert!(
+            phase(false, &[("Lifstan", Undertaking::HealScars)])
+                .resolve()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn undertakings_update_company() {
+        let mut phase = phase(
+            false,
+            &[
+                ("Lifstan", Undertaking::RaiseStandardOfLiving),
+                (
+                    "Marigold",
+                    Undertaking::WriteASong {
+                        title: "The Road Goes Ever On".to_owned(),
+                    },
+                ),
+            ],
+        );
+        phase.company.fellowship_points = 0;
+        let outcome = phase.resolve().unwrap();
+
+        assert_eq!(
+            outcome.company.heroes[0].standard_of_living(),
+            StandardOfLiving::Prosperous
+        );
+        assert_eq!(outcome.company.heroes[0].treasure(), 10);
+        assert_eq!(outcome.company.songs, ["The Road Goes Ever On"]);
+        assert!(
+            outcome
+                .log
+                .last()
+                .unwrap()
+                .contains("Marigold writes a song")
+        );
+    }
+
+    #[test]
+    fn strengthening_fellowship_adds_to_pool() {
+        let outcome = phase(
+            false,
+            &[
+                ("Lifstan", Undertaking::StrengthenFellowship),
+                ("Marigold", Undertaking::StrengthenFellowship),
+            ],
+        )
+        .resolve()
+        .unwrap();
+
+        assert_eq!(outcome.company.fellowship_points, 4);
+    }
+
+    #[test]
+    fn meeting_patron_needs_a_patron() {
+        let undertakings = [("Marigold", Undertaking::MeetPatron)];
+        assert!(phase(false, &undertakings).resolve().is_err());
+
+        let mut phase = phase(false, &undertakings);
+        phase.company.patron = Some(Patron::Bilbo);
+        assert!(
+            phase
+                .resolve()
+                .unwrap()
+                .log
+                .last()
+                .unwrap()
+                .contains("Bilbo Baggins")
+        );
+    }
+
+    #[test]
+    fn patron_must_be_at_safe_haven() {
+        let mut phase = phase(false, &[("Marigold", Undertaking::MeetPatron)]);
+        phase.company.patron = Some(Patron::Balin);
+
+        assert!(phase.resolve().is_err());
+    }
+
+    #[test]
+    fn studied_items_must_be_carried() {
+        let study = |item: &str| Undertaking::StudyMagicalItems {
+            item: item.to_owned(),
+        };
+
+        assert!(
+            phase(false, &[("Lifstan", study("Dragon-bane"))])
+                .resolve()
+                .is_ok()
+        );
+        assert!(
+            phase(false, &[("Marigold", study("Dragon-bane"))])
+                .resolve()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn heroes_choose_one_undertaking() {
+        let undertakings = [
+            ("Lifstan", Undertaking::StrengthenFellowship),
+            ("Lifstan", Undertaking::MeetPatron),
+        ];
+
+        assert!(phase(false, &undertakings).resolve().is_err());
+        assert!(
+            phase(false, &[("Gandalf", Undertaking::StrengthenFellowship)])
+                .resolve()
+                .is_err()
+        );
+    }
+}
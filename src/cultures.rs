@@ -1,19 +1,18 @@
 /*!
 # Heroic Cultures
 */
+use std::sync::LazyLock;
+
 use rand::{
     Rng,
     distr::{Distribution, StandardUniform},
     seq::IteratorRandom,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use strum::{EnumIter, EnumString, IntoEnumIterator};
 use utoipa::ToSchema;
 
-use self::{
-    bardings::BardingName, bree::ManOfBreeName, dwarves::DwarfOfDurinsFolkName,
-    elves::ElfOfLindonName, hobbits::HobbitOfTheShireName, rangers::RangerOfTheNorthName,
-};
+use crate::tables::{Table, Tables};
 
 mod bardings;
 mod bree;
@@ -22,9 +21,23 @@ mod elves;
 mod hobbits;
 mod rangers;
 
+/// Name tables of every culture, built the first time a name is generated
+static NAME_TABLES: LazyLock<Tables> = LazyLock::new(Tables::builtin);
+
 /// Generate a name for one of the following Heroic Cultures
 #[derive(
-    Clone, Copy, Debug, strum::Display, EnumIter, EnumString, Eq, PartialEq, Serialize, ToSchema,
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    strum::Display,
+    EnumIter,
+    EnumString,
+    Eq,
+    Hash,
+    PartialEq,
+    Serialize,
+    ToSchema,
 )]
 #[serde(rename_all = "kebab-case")]
 #[strum(serialize_all = "kebab-case")]
@@ -74,14 +87,88 @@ impl HeroicCulture {
     /// let name = HeroicCulture::HobbitsOfTheShire.random_name(&mut rand::thread_rng());
     /// ```
     pub fn random_name<R: Rng + ?Sized>(self, rng: &mut R) -> String {
+        NAME_TABLES
+            .roll(rng, &self.names_table())
+            .expect("name tables are complete")
+            .text()
+            .to_owned()
+    }
+
+    /// Name the folk is known by
+    #[must_use]
+    pub fn name(self) -> &'static str {
         match self {
-            HeroicCulture::Bardings => rng.random::<BardingName>().to_string(),
-            HeroicCulture::DwarvesOfDurinsFolk => rng.random::<DwarfOfDurinsFolkName>().to_string(),
-            HeroicCulture::ElvesOfLindon => rng.random::<ElfOfLindonName>().to_string(),
-            HeroicCulture::HobbitsOfTheShire => rng.random::<HobbitOfTheShireName>().to_string(),
-            HeroicCulture::MenOfBree => rng.random::<ManOfBreeName>().to_string(),
-            HeroicCulture::RangersOfTheNorth => rng.random::<RangerOfTheNorthName>().to_string(),
+            HeroicCulture::Bardings => "Bardings",
+            HeroicCulture::DwarvesOfDurinsFolk => "Dwarves of Durin's Folk",
+            HeroicCulture::ElvesOfLindon => "Elves of Lindon",
+            HeroicCulture::HobbitsOfTheShire => "Hobbits of the Shire",
+            HeroicCulture::MenOfBree => "Men of Bree",
+            HeroicCulture::RangersOfTheNorth => "Rangers of the North",
+        }
+    }
+
+    /// Id of the table names of the culture are rolled on
+    #[must_use]
+    pub fn names_table(self) -> String {
+        format!("{self}-names")
+    }
+
+    /// Tables making up names of the culture: male and female first names, and family names for
+    /// the folk who use them
+    #[must_use]
+    pub fn name_tables(self) -> Vec<Table> {
+        let (male_names, female_names, family_names) = match self {
+            HeroicCulture::Bardings => (bardings::MALE_NAMES, bardings::FEMALE_NAMES, None),
+            HeroicCulture::DwarvesOfDurinsFolk => {
+                (dwarves::MALE_NAMES, dwarves::FEMALE_NAMES, None)
+            }
+            HeroicCulture::ElvesOfLindon => (elves::MALE_NAMES, elves::FEMALE_NAMES, None),
+            HeroicCulture::HobbitsOfTheShire => (
+                hobbits::MALE_NAMES,
+                hobbits::FEMALE_NAMES,
+                Some(hobbits::FAMILY_NAMES),
+            ),
+            HeroicCulture::MenOfBree => (
+                bree::MALE_NAMES,
+                bree::FEMALE_NAMES,
+                Some(bree::FAMILY_NAMES),
+            ),
+            HeroicCulture::RangersOfTheNorth => (rangers::MALE_NAMES, rangers::FEMALE_NAMES, None),
+        };
+
+        let family = if family_names.is_some() {
+            format!(" {{table:{self}-family-names}}")
+        } else {
+            String::new()
+        };
+        let mut tables = vec![
+            Table::list(
+                &format!("{self}-male-names"),
+                &format!("Male names of the {}", self.name()),
+                male_names,
+            ),
+            Table::list(
+                &format!("{self}-female-names"),
+                &format!("Female names of the {}", self.name()),
+                female_names,
+            ),
+            Table::list(
+                &self.names_table(),
+                &format!("Names of the {}", self.name()),
+                &[
+                    &format!("{{table:{self}-male-names}}{family}"),
+                    &format!("{{table:{self}-female-names}}{family}"),
+                ],
+            ),
+        ];
+        if let Some(family_names) = family_names {
+            tables.push(Table::list(
+                &format!("{self}-family-names"),
+                &format!("Family names of the {}", self.name()),
+                family_names,
+            ));
         }
+        tables
     }
 }
 
@@ -1,34 +1,14 @@
-use std::fmt;
-
-use rand::{
-    Rng,
-    distr::{Distribution, StandardUniform},
-    seq::IndexedRandom,
-};
-
-#[derive(Debug)]
-pub struct ManOfBreeName {
-    first_name: &'static str,
-    family_name: &'static str,
-}
-
-impl fmt::Display for ManOfBreeName {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{} {}", self.first_name, self.family_name)
-    }
-}
-
-const MALE_NAMES: &[&str] = &[
+pub const MALE_NAMES: &[&str] = &[
     // Core Rules
     "Alfred", "Artie", "Bill", "Bob", "Carl", "Ed", "Fred", "Giles", "Herb", "Larry", "Nob",
     "Oswald", "Percy", "Perry", "Sid", "Tom", "Harry",
 ];
-const FEMALE_NAMES: &[&str] = &[
+pub const FEMALE_NAMES: &[&str] = &[
     // Core Rules
     "Daisy", "Emma", "Etta", "Fay", "Fern", "Flora", "Gert", "Holly", "Lily", "Myrtle", "Poppy",
     "Rose", "Sage", "Tilly", "Violet",
 ];
-const FAMILY_NAMES: &[&str] = &[
+pub const FAMILY_NAMES: &[&str] = &[
     // Core Rules
     "Appledore",
     "Asterfire",
@@ -58,48 +38,18 @@ const FAMILY_NAMES: &[&str] = &[
     "Wayward",
 ];
 
-impl Distribution<ManOfBreeName> for StandardUniform {
-    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> ManOfBreeName {
-        ManOfBreeName {
-            first_name: [MALE_NAMES, FEMALE_NAMES]
-                .choose(rng)
-                .unwrap()
-                .choose(rng)
-                .unwrap(),
-            family_name: FAMILY_NAMES.choose(rng).unwrap(),
-        }
-    }
-}
-
 #[cfg(test)]
 mod test {
-    use rand::Rng;
-
-    use crate::rand::rng_from_os_rng;
+    use crate::{cultures::HeroicCulture, rand::rng_from_os_rng};
 
     use super::*;
 
-    #[test]
-    fn name_can_be_displayed() {
-        let mut rng = rng_from_os_rng();
-        let name = rng.random::<ManOfBreeName>();
-
-        assert_eq!(
-            format!("{name}"),
-            format!("{} {}", name.first_name, name.family_name)
-        );
-    }
-
     #[test]
     fn name_can_be_randomly_generated() {
-        let mut rng = rng_from_os_rng();
-        let name = rng.random::<ManOfBreeName>();
+        let name = HeroicCulture::MenOfBree.random_name(&mut rng_from_os_rng());
+        let (first_name, family_name) = name.split_once(' ').unwrap();
 
-        assert!(
-            [MALE_NAMES, FEMALE_NAMES]
-                .concat()
-                .contains(&name.first_name)
-        );
-        assert!(FAMILY_NAMES.contains(&name.family_name));
+        assert!([MALE_NAMES, FEMALE_NAMES].concat().contains(&first_name));
+        assert!(FAMILY_NAMES.contains(&family_name));
     }
 }
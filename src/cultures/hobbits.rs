@@ -1,24 +1,4 @@
-use std::fmt;
-
-use rand::{
-    Rng,
-    distr::{Distribution, StandardUniform},
-    seq::IndexedRandom,
-};
-
-#[derive(Debug)]
-pub struct HobbitOfTheShireName {
-    first_name: &'static str,
-    family_name: &'static str,
-}
-
-impl fmt::Display for HobbitOfTheShireName {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{} {}", self.first_name, self.family_name)
-    }
-}
-
-const MALE_NAMES: &[&str] = &[
+pub const MALE_NAMES: &[&str] = &[
     // Core Rules
     "Andwise",
     "Berilac",
@@ -53,7 +33,7 @@ const MALE_NAMES: &[&str] = &[
     "Tobold",
     "Tolman",
 ];
-const FEMALE_NAMES: &[&str] = &[
+pub const FEMALE_NAMES: &[&str] = &[
     // Core Rules
     "Adaldrida",
     "Amaranth",
@@ -87,7 +67,7 @@ const FEMALE_NAMES: &[&str] = &[
     "Ruby",
     "Salvia",
 ];
-const FAMILY_NAMES: &[&str] = &[
+pub const FAMILY_NAMES: &[&str] = &[
     // Core Rules
     "Baggins",
     "Boffin",
@@ -121,48 +101,18 @@ const FAMILY_NAMES: &[&str] = &[
     "Whitfoot",
 ];
 
-impl Distribution<HobbitOfTheShireName> for StandardUniform {
-    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> HobbitOfTheShireName {
-        HobbitOfTheShireName {
-            first_name: [MALE_NAMES, FEMALE_NAMES]
-                .choose(rng)
-                .unwrap()
-                .choose(rng)
-                .unwrap(),
-            family_name: FAMILY_NAMES.choose(rng).unwrap(),
-        }
-    }
-}
-
 #[cfg(test)]
 mod test {
-    use rand::Rng;
-
-    use crate::rand::rng_from_os_rng;
+    use crate::{cultures::HeroicCulture, rand::rng_from_os_rng};
 
     use super::*;
 
-    #[test]
-    fn name_can_be_displayed() {
-        let mut rng = rng_from_os_rng();
-        let name = rng.random::<HobbitOfTheShireName>();
-
-        assert_eq!(
-            format!("{name}"),
-            format!("{} {}", name.first_name, name.family_name)
-        );
-    }
-
     #[test]
     fn name_can_be_randomly_generated() {
-        let mut rng = rng_from_os_rng();
-        let name = rng.random::<HobbitOfTheShireName>();
+        let name = HeroicCulture::HobbitsOfTheShire.random_name(&mut rng_from_os_rng());
+        let (first_name, family_name) = name.split_once(' ').unwrap();
 
-        assert!(
-            [MALE_NAMES, FEMALE_NAMES]
-                .concat()
-                .contains(&name.first_name)
-        );
-        assert!(FAMILY_NAMES.contains(&name.family_name));
+        assert!([MALE_NAMES, FEMALE_NAMES].concat().contains(&first_name));
+        assert!(FAMILY_NAMES.contains(&family_name));
     }
 }
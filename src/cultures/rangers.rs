@@ -1,23 +1,4 @@
-use std::fmt;
-
-use rand::{
-    Rng,
-    distr::{Distribution, StandardUniform},
-    seq::IndexedRandom,
-};
-
-#[derive(Debug)]
-pub struct RangerOfTheNorthName {
-    name: &'static str,
-}
-
-impl fmt::Display for RangerOfTheNorthName {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.name)
-    }
-}
-
-const MALE_NAMES: &[&str] = &[
+pub const MALE_NAMES: &[&str] = &[
     // Core Rules
     "Adrahil",
     "Amlaith",
@@ -45,7 +26,7 @@ const MALE_NAMES: &[&str] = &[
     "Tarannon",
     "Targon",
 ];
-const FEMALE_NAMES: &[&str] = &[
+pub const FEMALE_NAMES: &[&str] = &[
     // Core Rules
     "Anwen",
     "Arbereth",
@@ -74,39 +55,16 @@ const FEMALE_NAMES: &[&str] = &[
     "Tarandîs",
 ];
 
-impl Distribution<RangerOfTheNorthName> for StandardUniform {
-    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> RangerOfTheNorthName {
-        RangerOfTheNorthName {
-            name: [MALE_NAMES, FEMALE_NAMES]
-                .choose(rng)
-                .unwrap()
-                .choose(rng)
-                .unwrap(),
-        }
-    }
-}
-
 #[cfg(test)]
 mod test {
-    use rand::Rng;
-
-    use crate::rand::rng_from_os_rng;
+    use crate::{cultures::HeroicCulture, rand::rng_from_os_rng};
 
     use super::*;
 
-    #[test]
-    fn name_can_be_displayed() {
-        let mut rng = rng_from_os_rng();
-        let name = rng.random::<RangerOfTheNorthName>();
-
-        assert_eq!(format!("{name}"), format!("{}", name.name));
-    }
-
     #[test]
     fn name_can_be_randomly_generated() {
-        let mut rng = rng_from_os_rng();
-        let name = rng.random::<RangerOfTheNorthName>();
+        let name = HeroicCulture::RangersOfTheNorth.random_name(&mut rng_from_os_rng());
 
-        assert!([MALE_NAMES, FEMALE_NAMES].concat().contains(&name.name));
+        assert!([MALE_NAMES, FEMALE_NAMES].concat().contains(&name.as_str()));
     }
 }
@@ -0,0 +1,326 @@
+/*!
+# Dice
+
+The dice of The One Ring: a single twelve-sided Feat die, bearing the Eye of Sauron and the
+Gandalf rune alongside the numbers 1 to 10, and a number of six-sided Success dice whose 6 shows
+a Tengwar rune.
+*/
+
+use std::{borrow::Cow, cmp::Ordering, fmt};
+
+use rand::{
+    Rng,
+    distr::{Distribution, StandardUniform},
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{
+    PartialSchema, ToSchema,
+    openapi::{ObjectBuilder, OneOfBuilder, RefOr, Schema, schema::Type},
+};
+
+pub mod notation;
+
+/// Result of rolling the Feat die
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(try_from = "FeatDieFace", into = "FeatDieFace")]
+pub enum FeatDie {
+    /// The Eye of Sauron: counts as zero, and often brings trouble
+    Eye,
+    /// One of the numbered faces, from 1 to 10
+    Number(u8),
+    /// The Gandalf rune: an automatic success
+    Gandalf,
+}
+
+impl FeatDie {
+    /// Numeric value added to a roll's total. The Eye counts as 0 and the Gandalf rune as 12.
+    #[must_use]
+    pub fn value(self) -> u8 {
+        match self {
+            FeatDie::Eye => 0,
+            FeatDie::Number(number) => number,
+            FeatDie::Gandalf => 12,
+        }
+    }
+
+    /// Roll two Feat dice and keep the better (favoured) or worse (ill-favoured) one
+    pub fn roll_with<R: Rng + ?Sized>(rng: &mut R, favour: Favour) -> Self {
+        let first = rng.random::<FeatDie>();
+        match favour {
+            Favour::Normal => first,
+            Favour::Favoured => first.max(rng.random()),
+            Favour::IllFavoured => first.min(rng.random()),
+        }
+    }
+}
+
+impl Ord for FeatDie {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.value().cmp(&other.value())
+    }
+}
+
+impl PartialOrd for FeatDie {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for FeatDie {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FeatDie::Eye => write!(f, "eye"),
+            FeatDie::Number(number) => write!(f, "{number}"),
+            FeatDie::Gandalf => write!(f, "gandalf"),
+        }
+    }
+}
+
+impl Distribution<FeatDie> for StandardUniform {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> FeatDie {
+        match rng.random_range(0..12) {
+            0 => FeatDie::Eye,
+            11 => FeatDie::Gandalf,
+            number => FeatDie::Number(number),
+        }
+    }
+}
+
+/// How a Feat die face is written in JSON: `"eye"`, `"gandalf"` or a number
+#[derive(Deserialize, Serialize)]
+#[serde(untagged)]
+enum FeatDieFace {
+    Rune(FeatRune),
+    Number(u8),
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum FeatRune {
+    Eye,
+    Gandalf,
+}
+
+impl TryFrom<FeatDieFace> for FeatDie {
+    type Error = String;
+
+    fn try_from(face: FeatDieFace) -> Result<Self, Self::Error> {
+        match face {
+            FeatDieFace::Rune(FeatRune::Eye) => Ok(FeatDie::Eye),
+            FeatDieFace::Rune(FeatRune::Gandalf) => Ok(FeatDie::Gandalf),
+            FeatDieFace::Number(number @ 1..=10) => Ok(FeatDie::Number(number)),
+            FeatDieFace::Number(number) => Err(format!(
+                "Feat die numbers range from 1 to 10, found {number}"
+            )),
+        }
+    }
+}
+
+impl From<FeatDie> for FeatDieFace {
+    fn from(die: FeatDie) -> Self {
+        match die {
+            FeatDie::Eye => FeatDieFace::Rune(FeatRune::Eye),
+            FeatDie::Number(number) => FeatDieFace::Number(number),
+            FeatDie::Gandalf => FeatDieFace::Rune(FeatRune::Gandalf),
+        }
+    }
+}
+
+impl ToSchema for FeatDie {
+    fn name() -> Cow<'static, str> {
+        Cow::Borrowed("FeatDie")
+    }
+}
+
+impl PartialSchema for FeatDie {
+    fn schema() -> RefOr<Schema> {
+        OneOfBuilder::new()
+            .item(
+                ObjectBuilder::new()
+                    .schema_type(Type::String)
+                    .enum_values(Some(["eye", "gandalf"])),
+            )
+            .item(
+                ObjectBuilder::new()
+                    .schema_type(Type::Integer)
+                    .minimum(Some(1))
+                    .maximum(Some(10)),
+            )
+            .description(Some(
+                "Face of the Feat die: the Eye of Sauron, the Gandalf rune or a number from 1 to 10",
+            ))
+            .into()
+    }
+}
+
+/// Whether a roll is made with an extra Feat die, keeping the best or worst result
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum Favour {
+    /// Roll a single Feat die
+    #[default]
+    Normal,
+    /// Roll two Feat dice and keep the best
+    Favoured,
+    /// Roll two Feat dice and keep the worst
+    IllFavoured,
+}
+
+impl Favour {
+    /// One step worse: favoured rolls become normal, and normal rolls ill-favoured
+    #[must_use]
+    pub fn worsened(self) -> Self {
+        match self {
+            Favour::Favoured => Favour::Normal,
+            Favour::Normal | Favour::IllFavoured => Favour::IllFavoured,
+        }
+    }
+}
+
+/// Result of rolling a Success die, from 1 to 6. A 6 shows a Tengwar rune.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize, ToSchema)]
+#[serde(transparent)]
+pub struct SuccessDie(u8);
+
+impl SuccessDie {
+    /// Value added to a roll's total. A Weary hero's 1s, 2s and 3s count as zero.
+    #[must_use]
+    pub fn value(self, weary: bool) -> u8 {
+        if weary && self.0 <= 3 { 0 } else { self.0 }
+    }
+
+    /// Whether the die shows a Tengwar rune, signalling a great or extraordinary success
+    #[must_use]
+    pub fn is_tengwar(self) -> bool {
+        self.0 == 6
+    }
+}
+
+impl Distribution<SuccessDie> for StandardUniform {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> SuccessDie {
+        SuccessDie(rng.random_range(1..=6))
+    }
+}
+
+/// A Feat die and any number of Success dice rolled together
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, ToSchema)]
+pub struct Roll {
+    feat_die: FeatDie,
+    success_dice: Vec<SuccessDie>,
+    weary: bool,
+}
+
+impl Roll {
+    /// Roll the Feat die along with `dice` Success dice
+    pub fn new<R: Rng + ?Sized>(rng: &mut R, dice: u8, favour: Favour, weary: bool) -> Self {
+        Self {
+            feat_die: FeatDie::roll_with(rng, favour),
+            success_dice: (0..dice).map(|_| rng.random()).collect(),
+            weary,
+        }
+    }
+
+    #[must_use]
+    pub fn feat_die(&self) -> FeatDie {
+        self.feat_die
+    }
+
+    /// Sum of the Feat die and all Success dice
+    #[must_use]
+    pub fn total(&self) -> u32 {
+        u32::from(self.feat_die.value())
+            + self
+                .success_dice
+                .iter()
+                .map(|die| u32::from(die.value(self.weary)))
+                .sum::<u32>()
+    }
+
+    /// Number of Success dice showing a Tengwar rune
+    #[must_use]
+    pub fn tengwars(&self) -> usize {
+        self.success_dice
+            .iter()
+            .filter(|die| die.is_tengwar())
+            .count()
+    }
+
+    /// Whether the roll meets the Target Number. The Gandalf rune always succeeds.
+    #[must_use]
+    pub fn is_success(&self, target_number: u32) -> bool {
+        self.feat_die == FeatDie::Gandalf || self.total() >= target_number
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rand::rng_from_os_rng;
+
+    use super::*;
+
+    #[test]
+    fn feat_die_rolls_every_face() {
+        let mut rng = rng_from_os_rng();
+        let faces = (0..1000)
+            .map(|_| rng.random::<FeatDie>())
+            .collect::<Vec<_>>();
+
+        assert!(faces.contains(&FeatDie::Eye));
+        assert!(faces.contains(&FeatDie::Gandalf));
+        for number in 1..=10 {
+            assert!(faces.contains(&FeatDie::Number(number)));
+        }
+    }
+
+    #[test]
+    fn feat_die_faces_are_ordered() {
+        assert!(FeatDie::Eye < FeatDie::Number(1));
+        assert!(FeatDie::Number(10) < FeatDie::Gandalf);
+    }
+
+    #[test]
+    fn feat_die_serializes_runes_and_numbers() {
+        assert_eq!(serde_json::to_string(&FeatDie::Eye).unwrap(), r#""eye""#);
+        assert_eq!(serde_json::to_string(&FeatDie::Number(7)).unwrap(), "7");
+        assert_eq!(
+            serde_json::from_str::<FeatDie>(r#""gandalf""#).unwrap(),
+            FeatDie::Gandalf
+        );
+        assert!(serde_json::from_str::<FeatDie>("11").is_err());
+    }
+
+    #[test]
+    fn weary_heroes_lose_low_success_dice() {
+        let roll = Roll {
+            feat_die: FeatDie::Number(5),
+            success_dice: vec![SuccessDie(2), SuccessDie(6)],
+            weary: true,
+        };
+
+        assert_eq!(roll.total(), 11);
+    }
+
+    #[test]
+    fn gandalf_rune_always_succeeds() {
+        let roll = Roll {
+            feat_die: FeatDie::Gandalf,
+            success_dice: vec![],
+            weary: false,
+        };
+
+        assert!(roll.is_success(30));
+    }
+
+    #[test]
+    fn favoured_rolls_keep_the_best_die() {
+        let mut rng = rng_from_os_rng();
+        let favoured = (0..1000)
+            .map(|_| u32::from(FeatDie::roll_with(&mut rng, Favour::Favoured).value()))
+            .sum::<u32>();
+        let ill_favoured = (0..1000)
+            .map(|_| u32::from(FeatDie::roll_with(&mut rng, Favour::IllFavoured).value()))
+            .sum::<u32>();
+
+        assert!(favoured > ill_favoured);
+    }
+}
@@ -0,0 +1,572 @@
+/*!
+# Dice notation
+
+Generic dice written the usual way, for tables and homebrew content that go beyond the Feat and
+Success dice:
+
+- `2d6+3`: dice and constants added or subtracted together
+- `d12` or `d%`: a single die, `%` standing for 100 sides
+- `4dF`: Fudge dice, showing -1, 0 or +1
+- `4d6kh3` and `2d20kl1`: keep the highest or lowest dice (`k` alone keeps the highest)
+- `3d6!`: exploding dice, rolled again and added whenever they show their highest face. With
+  keep, as in `4d6!kh3`, a die and the dice it exploded into are kept or dropped together.
+*/
+
+use std::{fmt, ops::Range, str::FromStr};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Most dice a single term can roll
+const MAX_DICE: u32 = 100;
+
+/// Most sides a die can have
+const MAX_SIDES: u32 = 1000;
+
+/// Most terms an expression can add up
+const MAX_TERMS: usize = 20;
+
+/// Most times a single die can explode, so a run of luck cannot go on forever
+const MAX_EXPLOSIONS: usize = 100;
+
+/// Dice and constants added together, like `2d6+3`
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Expression {
+    terms: Vec<(Sign, Term)>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Sign {
+    Plus,
+    Minus,
+}
+
+impl Sign {
+    fn apply(self, value: i64) -> i64 {
+        match self {
+            Sign::Plus => value,
+            Sign::Minus => -value,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Term {
+    Constant(u32),
+    Dice(DiceTerm),
+}
+
+/// A number of identical dice, with what to do with them
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct DiceTerm {
+    count: u32,
+    die: Die,
+    keep: Option<Keep>,
+    explode: bool,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Die {
+    Sides(u32),
+    Fudge,
+}
+
+impl Die {
+    fn lowest(self) -> i64 {
+        match self {
+            Die::Sides(_) => 1,
+            Die::Fudge => -1,
+        }
+    }
+
+    fn highest(self) -> i64 {
+        match self {
+            Die::Sides(sides) => i64::from(sides),
+            Die::Fudge => 1,
+        }
+    }
+
+    fn roll<R: Rng + ?Sized>(self, rng: &mut R) -> i64 {
+        rng.random_range(self.lowest()..=self.highest())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Keep {
+    Highest(u32),
+    Lowest(u32),
+}
+
+impl Expression {
+    /// Roll every die and add up the terms
+    pub fn roll<R: Rng + ?Sized>(&self, rng: &mut R) -> ExpressionRoll {
+        let terms = self
+            .terms
+            .iter()
+            .map(|(sign, term)| {
+                let label = match sign {
+                    Sign::Plus => term.to_string(),
+                    Sign::Minus => format!("-{term}"),
+                };
+                match term {
+                    Term::Constant(value) => TermRoll {
+                        term: label,
+                        dice: vec![],
+                        total: sign.apply(i64::from(*value)),
+                    },
+                    Term::Dice(dice) => {
+                        let rolled = dice.roll(rng);
+                        let total = rolled
+                            .iter()
+                            .filter(|die| die.kept)
+                            .map(|die| die.value)
+                            .sum();
+                        TermRoll {
+                            term: label,
+                            dice: rolled,
+                            total: sign.apply(total),
+                        }
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+
+        ExpressionRoll {
+            expression: self.to_string(),
+            total: terms.iter().map(|term| term.total).sum(),
+            terms,
+        }
+    }
+
+    /// Lowest and highest totals the expression can roll, unless dice explode
+    #[must_use]
+    pub fn bounds(&self) -> Option<(i64, i64)> {
+        let mut bounds = (0, 0);
+        for (sign, term) in &self.terms {
+            let (low, high) = match term {
+                Term::Constant(value) => (i64::from(*value), i64::from(*value)),
+                Term::Dice(dice) if dice.explode => return None,
+                Term::Dice(dice) => {
+                    let kept = i64::from(dice.kept());
+                    (kept * dice.die.lowest(), kept * dice.die.highest())
+                }
+            };
+            bounds = match sign {
+                Sign::Plus => (bounds.0 + low, bounds.1 + high),
+                Sign::Minus => (bounds.0 - high, bounds.1 - low),
+            };
+        }
+        Some(bounds)
+    }
+}
+
+impl DiceTerm {
+    /// Number of dice counting toward the total
+    fn kept(self) -> u32 {
+        match self.keep {
+            Some(Keep::Highest(kept) | Keep::Lowest(kept)) => kept,
+            None => self.count,
+        }
+    }
+
+    /// Roll every die, keeping or dropping each one along with the dice it exploded into
+    fn roll<R: Rng + ?Sized>(self, rng: &mut R) -> Vec<DieRoll> {
+        let mut dice = vec![];
+        // Range of `dice` each die and its explosions take up
+        let mut chains = vec![];
+        for _ in 0..self.count {
+            let start = dice.len();
+            let mut value = self.die.roll(rng);
+            dice.push(DieRoll::new(value, false));
+            let mut explosions = 0;
+            while self.explode && value == self.die.highest() && explosions < MAX_EXPLOSIONS {
+                value = self.die.roll(rng);
+                dice.push(DieRoll::new(value, true));
+                explosions += 1;
+            }
+            chains.push(start..dice.len());
+        }
+
+        if let Some(keep) = self.keep {
+            let chain_total =
+                |chain: &Range<usize>| dice[chain.clone()].iter().map(|die| die.value).sum::<i64>();
+            let mut order = (0..chains.len()).collect::<Vec<_>>();
+            order.sort_by_key(|&index| chain_total(&chains[index]));
+            let kept = match keep {
+                Keep::Highest(kept) => order.split_off(order.len() - kept as usize),
+                Keep::Lowest(kept) => order.into_iter().take(kept as usize).collect(),
+            };
+            for (index, chain) in chains.into_iter().enumerate() {
+                for die in &mut dice[chain] {
+                    die.kept = kept.contains(&index);
+                }
+            }
+        }
+        dice
+    }
+}
+
+/// Result of rolling a dice expression
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ExpressionRoll {
+    /// Expression rolled, written the standard way
+    expression: String,
+    total: i64,
+    terms: Vec<TermRoll>,
+}
+
+impl ExpressionRoll {
+    #[must_use]
+    pub fn total(&self) -> i64 {
+        self.total
+    }
+}
+
+/// Result of rolling one term of an expression
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TermRoll {
+    term: String,
+    /// Every die rolled, in order. Empty for constants.
+    dice: Vec<DieRoll>,
+    /// What the term adds to the expression, negative for subtracted terms
+    total: i64,
+}
+
+/// A single die rolled
+#[derive(Clone, Copy, Debug, Serialize, ToSchema)]
+pub struct DieRoll {
+    value: i64,
+    /// Whether the die counts toward the total, rather than being dropped by keep
+    kept: bool,
+    /// Whether the die was rolled because the previous one exploded
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    exploded: bool,
+}
+
+impl DieRoll {
+    fn new(value: i64, exploded: bool) -> Self {
+        Self {
+            value,
+            kept: true,
+            exploded,
+        }
+    }
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Term::Constant(value) => write!(f, "{value}"),
+            Term::Dice(dice) => {
+                write!(f, "{}d", dice.count)?;
+                match dice.die {
+                    Die::Sides(sides) => write!(f, "{sides}")?,
+                    Die::Fudge => write!(f, "F")?,
+                }
+                if dice.explode {
+                    write!(f, "!")?;
+                }
+                match dice.keep {
+                    Some(Keep::Highest(kept)) => write!(f, "kh{kept}"),
+                    Some(Keep::Lowest(kept)) => write!(f, "kl{kept}"),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, (sign, term)) in self.terms.iter().enumerate() {
+            match (index, sign) {
+                (0, Sign::Plus) => {}
+                (_, Sign::Plus) => write!(f, "+")?,
+                (_, Sign::Minus) => write!(f, "-")?,
+            }
+            write!(f, "{term}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads an expression one character at a time, allowing whitespace between tokens
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.input[self.position..].chars().next()
+    }
+
+    /// Move past any whitespace before the next token
+    fn skip_whitespace(&mut self) {
+        let rest = &self.input[self.position..];
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    /// Move past `expected` if it comes next, ignoring case
+    fn eat(&mut self, expected: char) -> bool {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(c) if c.eq_ignore_ascii_case(&expected) => {
+                self.position += c.len_utf8();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn number(&mut self) -> Result<Option<u32>, String> {
+        self.skip_whitespace();
+        let digits = self.input[self.position..]
+            .chars()
+            .take_while(char::is_ascii_digit)
+            .count();
+        if digits == 0 {
+            return Ok(None);
+        }
+        let number = &self.input[self.position..self.position + digits];
+        self.position += digits;
+        number
+            .parse()
+            .map(Some)
+            .map_err(|_| format!("{number} is too big"))
+    }
+
+    fn unexpected(&self) -> String {
+        match self.peek() {
+            Some(c) => format!("Unexpected {c:?} at position {}", self.position + 1),
+            None => "Unexpected end of expression".to_owned(),
+        }
+    }
+
+    fn term(&mut self) -> Result<Term, String> {
+        let count = self.number()?;
+        if !self.eat('d') {
+            return count.map(Term::Constant).ok_or_else(|| self.unexpected());
+        }
+
+        let count = count.unwrap_or(1);
+        if !(1..=MAX_DICE).contains(&count) {
+            return Err(format!("Roll between 1 and {MAX_DICE} dice at a time"));
+        }
+        let die = if self.eat('f') {
+            Die::Fudge
+        } else if self.eat('%') {
+            Die::Sides(100)
+        } else {
+            match self.number()? {
+                Some(sides @ 1..=MAX_SIDES) => Die::Sides(sides),
+                Some(_) => return Err(format!("Dice have between 1 and {MAX_SIDES} sides")),
+                None => return Err(self.unexpected()),
+            }
+        };
+
+        let explode = self.eat('!');
+        if explode && die.lowest() == die.highest() {
+            return Err("Dice with a single side cannot explode".to_owned());
+        }
+        let keep = if self.eat('k') {
+            let lowest = self.eat('l');
+            if !lowest {
+                self.eat('h');
+            }
+            let kept = self.number()?.ok_or_else(|| self.unexpected())?;
+            if !(1..=count).contains(&kept) {
+                return Err(format!("Keep between 1 and {count} dice"));
+            }
+            Some(if lowest {
+                Keep::Lowest(kept)
+            } else {
+                Keep::Highest(kept)
+            })
+        } else {
+            None
+        };
+
+        Ok(Term::Dice(DiceTerm {
+            count,
+            die,
+            keep,
+            explode,
+        }))
+    }
+}
+
+impl FromStr for Expression {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            input: s,
+            position: 0,
+        };
+
+        let mut terms = vec![];
+        let mut sign = if parser.eat('-') {
+            Sign::Minus
+        } else {
+            parser.eat('+');
+            Sign::Plus
+        };
+        loop {
+            terms.push((sign, parser.term()?));
+            if terms.len() > MAX_TERMS {
+                return Err(format!("Expressions add up to {MAX_TERMS} terms"));
+            }
+            sign = if parser.eat('+') {
+                Sign::Plus
+            } else if parser.eat('-') {
+                Sign::Minus
+            } else if parser.peek().is_none() {
+                return Ok(Self { terms });
+            } else {
+                return Err(parser.unexpected());
+            };
+        }
+    }
+}
+
+impl TryFrom<String> for Expression {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Expression> for String {
+    fn from(expression: Expression) -> Self {
+        expression.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rand::rng_from_seed;
+
+    use super::*;
+
+    fn parse(s: &str) -> Expression {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_standard_notation() {
+        for (input, canonical) in [
+            ("2d6+3", "2d6+3"),
+            ("d12", "1d12"),
+            ("4dF", "4dF"),
+            ("d%", "1d100"),
+            ("4d6k3", "4d6kh3"),
+            ("2D20KL1 - 1", "2d20kl1-1"),
+            (" 2 d 6 + 3 ", "2d6+3"),
+            ("-1+3d6!", "-1+3d6!"),
+        ] {
+            assert_eq!(parse(input).to_string(), canonical);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_expressions() {
+        for input in [
+            "", "2d", "2x6", "d6+", "0d6", "2d0", "4d6kh5", "1d1!", "2d6 3d", "2d6 3", "1 2d6",
+            "2d1 2",
+        ] {
+            assert!(input.parse::<Expression>().is_err(), "{input}");
+        }
+    }
+
+    #[test]
+    fn rolls_within_bounds() {
+        let mut rng = rng_from_seed(2965);
+        for input in ["2d6+3", "4dF", "4d6kh3-2", "d%"] {
+            let expression = parse(input);
+            let (lowest, highest) = expression.bounds().unwrap();
+            for _ in 0..200 {
+                let total = expression.roll(&mut rng).total();
+                assert!((lowest..=highest).contains(&total), "{input}: {total}");
+            }
+        }
+        assert_eq!(parse("2d6+3").bounds(), Some((5, 15)));
+        assert_eq!(parse("4dF").bounds(), Some((-4, 4)));
+        assert_eq!(parse("1d4-1d6").bounds(), Some((-5, 3)));
+        assert_eq!(parse("3d6!").bounds(), None);
+    }
+
+    #[test]
+    fn keeps_highest_dice() {
+        let roll = parse("4d6kh3").roll(&mut rng_from_seed(7));
+        let dice = &roll.terms[0].dice;
+        let dropped = dice.iter().find(|die| !die.kept).unwrap();
+
+        assert_eq!(dice.len(), 4);
+        assert_eq!(dice.iter().filter(|die| die.kept).count(), 3);
+        assert!(dice.iter().all(|die| die.value >= dropped.value));
+        assert_eq!(
+            roll.total,
+            dice.iter()
+                .filter(|die| die.kept)
+                .map(|die| die.value)
+                .sum::<i64>()
+        );
+    }
+
+    #[test]
+    fn exploding_dice_roll_again_on_highest_face() {
+        let mut rng = rng_from_seed(1);
+        let expression = parse("10d2!");
+        let roll = (0..20)
+            .map(|_| expression.roll(&mut rng))
+            .find(|roll| roll.terms[0].dice.len() > 10)
+            .unwrap();
+
+        for pair in roll.terms[0].dice.windows(2) {
+            if pair[1].exploded {
+                assert_eq!(pair[0].value, 2);
+            }
+        }
+    }
+
+    #[test]
+    fn keeps_exploded_dice_with_the_die_they_came_from() {
+        let mut rng = rng_from_seed(1);
+        let expression = parse("4d6!kh3");
+        for _ in 0..200 {
+            let roll = expression.roll(&mut rng);
+            let dice = &roll.terms[0].dice;
+
+            // Each chain of explosions is kept or dropped as a whole
+            for pair in dice.windows(2) {
+                if pair[1].exploded {
+                    assert_eq!(pair[0].kept, pair[1].kept);
+                }
+            }
+            let mut chains = Vec::<(i64, bool)>::new();
+            for die in dice {
+                match chains.last_mut() {
+                    Some((total, _)) if die.exploded => *total += die.value,
+                    _ => chains.push((die.value, die.kept)),
+                }
+            }
+            assert_eq!(chains.len(), 4);
+            let (kept, dropped) = chains.iter().partition::<Vec<_>, _>(|(_, kept)| *kept);
+            assert_eq!(kept.len(), 3);
+            assert!(kept.iter().all(|(total, _)| *total >= dropped[0].0));
+            assert_eq!(roll.total, kept.iter().map(|(total, _)| total).sum::<i64>());
+        }
+    }
+
+    #[test]
+    fn subtracted_terms_are_negative() {
+        let roll = parse("1d4-2").roll(&mut rng_from_seed(3));
+
+        assert_eq!(roll.terms[1].total, -2);
+        assert_eq!(roll.total, roll.terms[0].total - 2);
+    }
+}
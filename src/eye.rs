@@ -0,0 +1,217 @@
+/*!
+# The Eye of Mordor
+
+Tracks how close the Enemy is to noticing a company. Every Eye of Sauron rolled on the Feat die
+raises the company's Eye Awareness, and once it reaches the Hunt threshold of the region they are
+in, the Loremaster draws a Revelation Episode.
+*/
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{characters::Character, dice::FeatDie, regions::RegionType, treasure::MagicalItem};
+
+/// The Eye Awareness score of a company during an Adventuring phase
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct EyeAwareness {
+    /// Score the company starts with, and returns to after each Revelation Episode
+    initial: u32,
+    /// Current Eye Awareness
+    awareness: u32,
+    /// Type of region the company is currently in, setting the Hunt threshold
+    region: RegionType,
+    /// Number of Revelation Episodes triggered so far
+    revelation_episodes: u32,
+}
+
+/// What happened when rolls were recorded against a tracker
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct EyeUpdate {
+    /// Total Eye Awareness gained
+    pub increase: u32,
+    /// Number of times the Hunt threshold was reached
+    pub revelation_episodes: u32,
+}
+
+impl EyeAwareness {
+    /// Start tracking a company's Eye Awareness in the given region
+    #[must_use]
+    pub fn new(heroes: &[Character], region: RegionType) -> Self {
+        let initial = initial_awareness(heroes);
+        Self {
+            initial,
+            awareness: initial,
+            region,
+            revelation_episodes: 0,
+        }
+    }
+
+    /// Eye Awareness that triggers a Revelation Episode in the current region
+    #[must_use]
+    pub fn hunt_threshold(&self) -> u32 {
+        self.region.hunt_threshold()
+    }
+
+    /// Raise Eye Awareness for every Eye of Sauron among the Feat dice.
+    ///
+    /// Whenever the Hunt threshold is reached, a Revelation Episode is triggered and Eye
+    /// Awareness goes back to its initial score.
+    pub fn record_rolls(&mut self, feat_dice: impl IntoIterator<Item = FeatDie>) -> EyeUpdate {
+        let mut update = EyeUpdate::default();
+
+        for _ in feat_dice.into_iter().filter(|die| *die == FeatDie::Eye) {
+            update.increase += 1;
+            self.awareness = self.awareness.saturating_add(1);
+
+            if self.awareness >= self.hunt_threshold() {
+                update.revelation_episodes += 1;
+                self.revelation_episodes = self.revelation_episodes.saturating_add(1);
+                self.awareness = self.initial;
+            }
+        }
+
+        update
+    }
+}
+
+/// Eye Awareness at the start of an Adventuring phase: the highest Valour in the company, plus
+/// one for every Famous Weapon or Armour carried, plus whatever the magical treasure carried
+/// adds. Famous gear also listed among a hero's magical items only counts as the item.
+#[must_use]
+pub fn initial_awareness(heroes: &[Character]) -> u32 {
+    let valour = heroes
+        .iter()
+        .map(Character::valour)
+        .max()
+        .unwrap_or_default();
+    let famous_gear = heroes
+        .iter()
+        .map(|hero| {
+            hero.famous_gear()
+                .iter()
+                .filter(|gear| !hero.magical_items().iter().any(|item| item.name() == *gear))
+                .count()
+        })
+        .sum::<usize>();
+    let magical_items = heroes
+        .iter()
+        .flat_map(Character::magical_items)
+        .map(MagicalItem::eye_awareness)
+        .sum::<u32>();
+
+    u32::from(valour) + u32::try_from(famous_gear).unwrap_or(u32::MAX) + magical_items
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        callings::Calling,
+        cultures::HeroicCulture,
+        rand::rng_from_seed,
+        treasure::{MagicalItemKind, Origin},
+    };
+
+    use super::*;
+
+    fn hero(valour: u8, famous_gear: &[&str]) -> Character {
+        serde_json::from_value(serde_json::json!({
+            "heroic_culture": HeroicCulture::Bardings,
+            "calling": Calling::Champion,
+            "name": "Lifstan",
+            "valour": valour,
+            "famous_gear": famous_gear,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn initial_awareness_from_valour_and_famous_gear() {
+        let heroes = [hero(2, &["Dwarf-wrought hauberk"]), hero(4, &[])];
+
+        assert_eq!(initial_awareness(&heroes), 5);
+    }
+
+    #[test]
+    fn magical_treasure_raises_initial_awareness() {
+        let items = [
+            MagicalItemKind::MarvellousArtefact,
+            MagicalItemKind::WondrousItem,
+            MagicalItemKind::FamousWeapon,
+        ]
+        .map(|kind| MagicalItem::random(&mut rng_from_seed(1), kind, Origin::Dwarven));
+        let hero = serde_json::from_value(serde_json::json!({
+            "heroic_culture": HeroicCulture::DwarvesOfDurinsFolk,
+            "calling": Calling::TreasureHunter,
+            "name": "Frár",
+            "valour": 2,
+            "magical_items": items,
+        }))
+        .unwrap();
+
+        assert_eq!(initial_awareness(&[hero]), 4);
+    }
+
+    #[test]
+    fn famous_gear_that_is_also_a_magical_item_counts_once() {
+        let item = MagicalItem::random(
+            &mut rng_from_seed(1),
+            MagicalItemKind::FamousWeapon,
+            Origin::Dwarven,
+        );
+        let hero = serde_json::from_value(serde_json::json!({
+            "heroic_culture": HeroicCulture::DwarvesOfDurinsFolk,
+            "calling": Calling::TreasureHunter,
+            "name": "Frár",
+            "valour": 2,
+            "famous_gear": [item.name(), "Dwarf-wrought hauberk"],
+            "magical_items": [item],
+        }))
+        .unwrap();
+
+        assert_eq!(initial_awareness(&[hero]), 4);
+    }
+
+    #[test]
+    fn awareness_saturates_instead_of_overflowing() {
+        let mut eye = EyeAwareness::new(&[hero(1, &[])], RegionType::WildLand);
+        eye.awareness = u32::MAX;
+        eye.revelation_episodes = u32::MAX;
+        let update = eye.record_rolls([FeatDie::Eye]);
+
+        assert_eq!(update.revelation_episodes, 1);
+        assert_eq!(eye.revelation_episodes, u32::MAX);
+        assert_eq!(eye.awareness, eye.initial);
+    }
+
+    #[test]
+    fn only_eye_results_raise_awareness() {
+        let mut eye = EyeAwareness::new(&[hero(1, &[])], RegionType::WildLand);
+        let update = eye.record_rolls([
+            FeatDie::Eye,
+            FeatDie::Number(4),
+            FeatDie::Gandalf,
+            FeatDie::Eye,
+        ]);
+
+        assert_eq!(update.increase, 2);
+        assert_eq!(update.revelation_episodes, 0);
+        assert_eq!(eye.awareness, 3);
+    }
+
+    #[test]
+    fn reaching_hunt_threshold_triggers_revelation() {
+        let mut eye = EyeAwareness::new(&[hero(1, &[])], RegionType::DarkLand);
+        let update = eye.record_rolls([FeatDie::Eye; 12]);
+
+        assert_eq!(eye.hunt_threshold(), 12);
+        assert_eq!(update.revelation_episodes, 1);
+        assert_eq!(eye.awareness, 2);
+        assert_eq!(eye.revelation_episodes, 1);
+    }
+
+    #[test]
+    fn hunt_threshold_is_lower_in_darker_lands() {
+        assert!(RegionType::BorderLand.hunt_threshold() > RegionType::WildLand.hunt_threshold());
+        assert!(RegionType::WildLand.hunt_threshold() > RegionType::ShadowLand.hunt_threshold());
+        assert!(RegionType::ShadowLand.hunt_threshold() > RegionType::DarkLand.hunt_threshold());
+    }
+}
@@ -0,0 +1,685 @@
+/*!
+# Journeys
+
+Resolves travel across the Wild: how long a journey takes, the events that befall the company
+along the way, and how weary the heroes are when they arrive.
+*/
+
+use std::collections::BTreeMap;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use strum::{EnumIter, EnumString};
+use utoipa::ToSchema;
+
+use crate::{
+    calendar::Date,
+    dice::{Favour, FeatDie, Roll},
+    regions::RegionType,
+    tables::journey_events::{JourneyEvent, JourneyEventRoll},
+};
+
+/// Most hexes a journey's route can cover, well beyond the longest route across Eriador
+pub const MAX_ROUTE_HEXES: u32 = 200;
+
+/// Time of year the journey takes place in
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Deserialize,
+    strum::Display,
+    EnumIter,
+    EnumString,
+    Eq,
+    PartialEq,
+    Serialize,
+    ToSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum Season {
+    Spring,
+    #[default]
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    /// Map hexes (10 miles each) the company covers in a day on foot
+    #[must_use]
+    pub fn hexes_per_day(self) -> u32 {
+        match self {
+            Season::Spring | Season::Summer | Season::Autumn => 2,
+            Season::Winter => 1,
+        }
+    }
+
+    /// Extra Fatigue gained from every Journey Event
+    #[must_use]
+    pub fn extra_fatigue(self) -> u32 {
+        match self {
+            Season::Spring | Season::Summer | Season::Autumn => 0,
+            Season::Winter => 1,
+        }
+    }
+}
+
+impl RegionType {
+    /// Hexes travelled between Journey Events
+    #[must_use]
+    pub fn hexes_per_event(self) -> u32 {
+        match self {
+            RegionType::BorderLand => 3,
+            RegionType::WildLand => 2,
+            RegionType::ShadowLand | RegionType::DarkLand => 1,
+        }
+    }
+
+    /// How Journey Event rolls are made while travelling through the region
+    #[must_use]
+    pub fn event_favour(self) -> Favour {
+        match self {
+            RegionType::BorderLand => Favour::Favoured,
+            RegionType::WildLand => Favour::Normal,
+            RegionType::ShadowLand | RegionType::DarkLand => Favour::IllFavoured,
+        }
+    }
+}
+
+/// Tasks the heroes take on to see the company safely to its destination
+#[derive(Clone, Copy, Debug, Deserialize, strum::Display, Eq, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "title_case")]
+pub enum Role {
+    /// Leads the way, making Marching tests with TRAVEL
+    Guide,
+    /// Finds food and game, rolling HUNTING
+    Hunter,
+    /// Keeps watch for dangers, rolling AWARENESS
+    LookOut,
+    /// Searches out paths and campsites, rolling EXPLORE
+    Scout,
+}
+
+impl Role {
+    /// Skill the role holder rolls when targeted
+    #[must_use]
+    pub fn skill(self) -> &'static str {
+        match self {
+            Role::Guide => "TRAVEL",
+            Role::Hunter => "HUNTING",
+            Role::LookOut => "AWARENESS",
+            Role::Scout => "EXPLORE",
+        }
+    }
+}
+
+/// The hero filling a journey role
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct RoleHolder {
+    hero: String,
+    /// Rating in the role's skill: the number of Success dice rolled
+    #[serde(default)]
+    rating: u8,
+    /// Target Number for the hero's rolls
+    #[serde(default = "default_target_number")]
+    target_number: u32,
+}
+
+pub fn default_target_number() -> u32 {
+    14
+}
+
+/// Who fills each journey role
+#[derive(Clone, Debug, Default, Deserialize, Serialize, ToSchema)]
+pub struct Roles {
+    guide: Option<RoleHolder>,
+    hunter: Option<RoleHolder>,
+    look_out: Option<RoleHolder>,
+    scout: Option<RoleHolder>,
+}
+
+impl Roles {
+    /// Hero filling the given role, if any
+    #[must_use]
+    pub fn holder(&self, role: Role) -> Option<&RoleHolder> {
+        match role {
+            Role::Guide => self.guide.as_ref(),
+            Role::Hunter => self.hunter.as_ref(),
+            Role::LookOut => self.look_out.as_ref(),
+            Role::Scout => self.scout.as_ref(),
+        }
+    }
+
+    /// Every role filled by a lone hero, rolling their `rating` in each role's skill
+    pub fn alone(hero: &str, rating: impl Fn(Role) -> u8, target_number: u32) -> Self {
+        let holder = |role| {
+            Some(RoleHolder {
+                hero: hero.to_owned(),
+                rating: rating(role),
+                target_number,
+            })
+        };
+        Self {
+            guide: holder(Role::Guide),
+            hunter: holder(Role::Hunter),
+            look_out: holder(Role::LookOut),
+            scout: holder(Role::Scout),
+        }
+    }
+
+    fn heroes(&self) -> impl Iterator<Item = &str> {
+        [&self.guide, &self.hunter, &self.look_out, &self.scout]
+            .into_iter()
+            .flatten()
+            .map(|holder| holder.hero.as_str())
+    }
+}
+
+/// A stretch of the route through a single type of region
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, ToSchema)]
+pub struct RouteSegment {
+    region: RegionType,
+    /// Number of 10-mile map hexes travelled
+    hexes: u32,
+}
+
+impl RouteSegment {
+    #[must_use]
+    pub fn new(region: RegionType, hexes: u32) -> Self {
+        Self { region, hexes }
+    }
+}
+
+/// Everything needed to resolve a journey
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct Journey {
+    route: Vec<RouteSegment>,
+    #[serde(default)]
+    season: Season,
+    /// Date the company sets out, which decides the season when given
+    start: Option<Date>,
+    #[serde(default)]
+    roles: Roles,
+    /// Heroes travelling without a role
+    #[serde(default)]
+    heroes: Vec<String>,
+}
+
+/// What happened on one day of the journey
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JourneyDay {
+    day: u32,
+    region: RegionType,
+    entries: Vec<String>,
+}
+
+/// Day-by-day account of a resolved journey
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JourneyLog {
+    /// Total number of days spent travelling
+    days: u32,
+    log: Vec<JourneyDay>,
+    /// Fatigue gained by each hero
+    fatigue: BTreeMap<String, u32>,
+    /// Shadow points (Dread) gained by each hero
+    shadow: BTreeMap<String, u32>,
+    /// Hope regained by each hero
+    hope: BTreeMap<String, u32>,
+    /// Heroes who were Wounded along the way
+    wounded: Vec<String>,
+    /// Eye of Sauron results rolled, to add to the company's Eye Awareness
+    eye_results: u32,
+    /// Date the company reaches its destination, if the journey had a start date
+    #[serde(skip_serializing_if = "Option::is_none")]
+    arrival: Option<Date>,
+}
+
+/// Running state while resolving a journey
+struct Resolution<'a> {
+    journey: &'a Journey,
+    log: JourneyLog,
+    /// Days gained (or lost, when negative) through events
+    delay: i64,
+}
+
+impl<'a> Resolution<'a> {
+    fn entry(&mut self, day: u32, region: RegionType, entry: String) {
+        match self.log.log.last_mut() {
+            Some(last) if last.day == day && last.region == region => last.entries.push(entry),
+            _ => self.log.log.push(JourneyDay {
+                day,
+                region,
+                entries: vec![entry],
+            }),
+        }
+    }
+
+    fn heroes(&self) -> Vec<String> {
+        let mut heroes = self
+            .journey
+            .roles
+            .heroes()
+            .chain(self.journey.heroes.iter().map(String::as_str))
+            .map(ToOwned::to_owned)
+            .collect::<Vec<_>>();
+        heroes.sort();
+        heroes.dedup();
+        heroes
+    }
+
+    fn gain(map: &mut BTreeMap<String, u32>, hero: &str, amount: u32) {
+        *map.entry(hero.to_owned()).or_default() += amount;
+    }
+
+    /// Have the holder of `role` roll their skill, returning who rolled and whether they passed.
+    ///
+    /// When nobody fills the role, the company rolls the Feat die alone.
+    fn test<R: Rng + ?Sized>(
+        &mut self,
+        rng: &mut R,
+        role: Role,
+        favour: Favour,
+    ) -> (Option<&'a str>, Roll, bool) {
+        let holder = self.journey.roles.holder(role);
+        let (rating, target_number) = holder.map_or((0, default_target_number()), |holder| {
+            (holder.rating, holder.target_number)
+        });
+        let result = Roll::new(rng, rating, favour, false);
+        if result.feat_die() == FeatDie::Eye {
+            self.log.eye_results += 1;
+        }
+        let success = result.is_success(target_number);
+        (holder.map(|holder| holder.hero.as_str()), result, success)
+    }
+
+    /// Day on which the company reaches the given hex, never earlier than what was already logged
+    fn day(&self, hex: u32) -> u32 {
+        let day = i64::from(hex.saturating_sub(1) / self.journey.season().hexes_per_day() + 1);
+        let logged = self.log.log.last().map_or(1, |last| last.day);
+        u32::try_from(day + self.delay)
+            .unwrap_or_default()
+            .max(logged)
+    }
+
+    fn resolve_event<R: Rng + ?Sized>(&mut self, rng: &mut R, hex: u32, region: RegionType) {
+        let day = self.day(hex);
+
+        let (guide, march, marched) = self.test(rng, Role::Guide, Favour::Normal);
+        let favour = if marched {
+            region.event_favour()
+        } else {
+            region.event_favour().worsened()
+        };
+        self.entry(
+            day,
+            region,
+            format!(
+                "Marching test by {} ({}): {}",
+                guide.map_or_else(
+                    || format!("the company, with no {}", Role::Guide),
+                    ToOwned::to_owned
+                ),
+                march.total(),
+                if marched { "success" } else { "failure" }
+            ),
+        );
+
+        let event_roll = JourneyEventRoll::roll(rng, favour);
+        if event_roll.feat_die() == FeatDie::Eye {
+            self.log.eye_results += 1;
+        }
+        let event = event_roll.event();
+        let detail = event_roll.detail();
+        let target = detail.target();
+        let (hero, result, success) = self.test(rng, target, Favour::Normal);
+        self.entry(
+            day,
+            region,
+            format!(
+                "Journey Event: {event}, {} (Feat die {}). {} rolls {} ({}): {}",
+                detail.title(),
+                event_roll.feat_die(),
+                hero.map_or_else(
+                    || format!("The company, with no {target},"),
+                    ToOwned::to_owned
+                ),
+                target.skill(),
+                result.total(),
+                if success { "success" } else { "failure" }
+            ),
+        );
+
+        let outcome = self.apply(event, hero, success);
+        self.entry(day, region, outcome);
+
+        let fatigue = event.fatigue() + self.journey.season().extra_fatigue();
+        for hero in self.heroes() {
+            Self::gain(&mut self.log.fatigue, &hero, fatigue);
+        }
+    }
+
+    /// Apply the consequences of an event, describing what happened. What befalls the targeted
+    /// hero befalls every hero when nobody fills the targeted role.
+    fn apply(&mut self, event: JourneyEvent, hero: Option<&str>, success: bool) -> String {
+        let targeted = hero.map_or_else(|| self.heroes(), |hero| vec![hero.to_owned()]);
+        match (event, success) {
+            (JourneyEvent::TerribleMisfortune, false) => self.log.wounded.extend(targeted),
+            (JourneyEvent::Despair, false) => {
+                for hero in self.heroes() {
+                    Self::gain(&mut self.log.shadow, &hero, 2);
+                }
+            }
+            (JourneyEvent::IllChoices, false) => {
+                for hero in &targeted {
+                    Self::gain(&mut self.log.shadow, hero, 1);
+                }
+            }
+            (JourneyEvent::Mishap, false) => {
+                self.delay += 1;
+                for hero in &targeted {
+                    Self::gain(&mut self.log.fatigue, hero, 1);
+                }
+            }
+            (JourneyEvent::ShortCut, true) => self.delay -= 1,
+            (JourneyEvent::JoyfulSight, true) => {
+                for hero in self.heroes() {
+                    Self::gain(&mut self.log.hope, &hero, 1);
+                }
+            }
+            _ => {}
+        }
+
+        let outcome = if success {
+            event.on_success()
+        } else {
+            event.on_failure()
+        };
+        outcome
+            .replace("The targeted hero", hero.unwrap_or("Every hero"))
+            .replace("the targeted hero", hero.unwrap_or("every hero"))
+    }
+}
+
+impl Journey {
+    #[must_use]
+    pub fn new(
+        route: Vec<RouteSegment>,
+        season: Season,
+        start: Option<Date>,
+        roles: Roles,
+    ) -> Self {
+        Self {
+            route,
+            season,
+            start,
+            roles,
+            heroes: vec![],
+        }
+    }
+
+    /// Season the journey takes place in, taken from the start date when there is one
+    #[must_use]
+    pub fn season(&self) -> Season {
+        self.start.map_or(self.season, Date::season)
+    }
+
+    /// Number of hexes covered by the whole route
+    #[must_use]
+    pub fn hexes(&self) -> u64 {
+        self.route
+            .iter()
+            .map(|segment| u64::from(segment.hexes))
+            .sum()
+    }
+
+    /// Travel the route, rolling a Journey Event each time the company has covered enough
+    /// hexes for the region it is in.
+    pub fn resolve<R: Rng + ?Sized>(&self, rng: &mut R) -> JourneyLog {
+        let mut resolution = Resolution {
+            journey: self,
+            log: JourneyLog {
+                days: 0,
+                log: vec![],
+                fatigue: BTreeMap::new(),
+                shadow: BTreeMap::new(),
+                hope: BTreeMap::new(),
+                wounded: vec![],
+                eye_results: 0,
+                arrival: None,
+            },
+            delay: 0,
+        };
+        for hero in resolution.heroes() {
+            resolution.log.fatigue.insert(hero, 0);
+        }
+
+        let mut hex = 0;
+        let mut since_event = 0;
+        for segment in &self.route {
+            for _ in 0..segment.hexes {
+                hex += 1;
+                since_event += 1;
+                if since_event >= segment.region.hexes_per_event() {
+                    since_event = 0;
+                    resolution.resolve_event(rng, hex, segment.region);
+                }
+            }
+        }
+
+        let arrival = resolution.day(hex);
+        let last_region = self
+            .route
+            .last()
+            .map_or_else(RegionType::default, |segment| segment.region);
+        resolution.entry(
+            arrival,
+            last_region,
+            "The company reaches its destination.".to_owned(),
+        );
+        resolution.log.days = arrival;
+        resolution.log.arrival = self
+            .start
+            .map(|start| start.add_days(arrival.saturating_sub(1)));
+        resolution.log
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rand::{rng_from_os_rng, rng_from_seed};
+
+    use super::*;
+
+    fn holder(hero: &str, rating: u8) -> RoleHolder {
+        RoleHolder {
+            hero: hero.to_owned(),
+            rating,
+            target_number: 14,
+        }
+    }
+
+    fn journey(route: Vec<RouteSegment>, season: Season) -> Journey {
+        Journey {
+            route,
+            season,
+            start: None,
+            roles: Roles {
+                guide: Some(holder("Aragorn", 3)),
+                hunter: Some(holder("Halbarad", 2)),
+                look_out: Some(holder("Lifstan", 2)),
+                scout: Some(holder("Frár", 1)),
+            },
+            heroes: vec!["Odo Proudfoot".to_owned()],
+        }
+    }
+
+    #[test]
+    fn darker_lands_have_more_events() {
+        let journey = journey(
+            vec![
+                RouteSegment {
+                    region: RegionType::BorderLand,
+                    hexes: 6,
+                },
+                RouteSegment {
+                    region: RegionType::ShadowLand,
+                    hexes: 3,
+                },
+            ],
+            Season::Summer,
+        );
+        let log = journey.resolve(&mut rng_from_os_rng());
+        let events = log
+            .log
+            .iter()
+            .flat_map(|day| &day.entries)
+            .filter(|entry| entry.starts_with("Journey Event"))
+            .count();
+
+        assert_eq!(events, 2 + 3);
+    }
+
+    #[test]
+    fn every_hero_gains_fatigue() {
+        let journey = journey(
+            vec![RouteSegment {
+                region: RegionType::WildLand,
+                hexes: 10,
+            }],
+            Season::Winter,
+        );
+        let log = journey.resolve(&mut rng_from_os_rng());
+
+        assert_eq!(log.fatigue.len(), 5);
+        // Winter adds 1 Fatigue to each of the 5 events
+        assert!(log.fatigue.values().all(|fatigue| *fatigue >= 5));
+    }
+
+    #[test]
+    fn winter_journeys_take_longer() {
+        let route = vec![RouteSegment {
+            region: RegionType::BorderLand,
+            hexes: 2,
+        }];
+        let summer = journey(route.clone(), Season::Summer).resolve(&mut rng_from_os_rng());
+        let winter = journey(route, Season::Winter).resolve(&mut rng_from_os_rng());
+
+        assert_eq!(summer.days, 1);
+        assert_eq!(winter.days, 2);
+    }
+
+    #[test]
+    fn start_date_decides_season_and_arrival() {
+        let route = vec![RouteSegment {
+            region: RegionType::BorderLand,
+            hexes: 2,
+        }];
+        let mut journey = journey(route, Season::Summer);
+        journey.start = Some("30 Foreyule 2965".parse().unwrap());
+        let log = journey.resolve(&mut rng_from_os_rng());
+
+        assert_eq!(journey.season(), Season::Winter);
+        assert_eq!(log.days, 2);
+        assert_eq!(log.arrival.unwrap().to_string(), "1 Yule 2965");
+    }
+
+    #[test]
+    fn log_is_ordered_by_day() {
+        let journey = journey(
+            vec![RouteSegment {
+                region: RegionType::ShadowLand,
+                hexes: 12,
+            }],
+            Season::Autumn,
+        );
+        let log = journey.resolve(&mut rng_from_seed(2965));
+
+        assert!(log.log.windows(2).all(|days| days[0].day <= days[1].day));
+        assert_eq!(log.log.last().unwrap().day, log.days);
+    }
+
+    #[test]
+    fn same_seed_resolves_same_journey() {
+        let journey = journey(
+            vec![RouteSegment {
+                region: RegionType::WildLand,
+                hexes: 8,
+            }],
+            Season::Spring,
+        );
+        let first = journey.resolve(&mut rng_from_seed(1));
+        let second = journey.resolve(&mut rng_from_seed(1));
+
+        assert_eq!(first.days, second.days);
+        assert_eq!(first.fatigue, second.fatigue);
+    }
+
+    #[test]
+    fn unfilled_roles_are_not_heroes() {
+        let mut journey = journey(
+            vec![RouteSegment {
+                region: RegionType::DarkLand,
+                hexes: 40,
+            }],
+            Season::Autumn,
+        );
+        journey.roles.look_out = None;
+        journey.roles.scout = None;
+        let heroes = ["Aragorn", "Halbarad", "Odo Proudfoot"];
+
+        for seed in 0..20 {
+            let log = journey.resolve(&mut rng_from_seed(seed));
+            assert!(
+                log.fatigue
+                    .keys()
+                    .chain(log.shadow.keys())
+                    .chain(&log.wounded)
+                    .all(|hero| heroes.contains(&hero.as_str()))
+            );
+        }
+    }
+
+    #[test]
+    fn unfilled_role_consequences_befall_every_hero() {
+        let journey = journey(vec![], Season::Summer);
+        let mut resolution = Resolution {
+            journey: &journey,
+            log: journey.resolve(&mut rng_from_seed(1)),
+            delay: 0,
+        };
+        resolution.log.wounded.clear();
+
+        let outcome = resolution.apply(JourneyEvent::TerribleMisfortune, None, false);
+        assert_eq!(outcome, "Every hero is Wounded.");
+        assert_eq!(resolution.log.wounded.len(), 5);
+    }
+
+    #[test]
+    fn outcomes_name_the_targeted_hero() {
+        let journey = journey(vec![], Season::Summer);
+        let mut resolution = Resolution {
+            journey: &journey,
+            log: journey.resolve(&mut rng_from_seed(1)),
+            delay: 0,
+        };
+
+        assert_eq!(
+            resolution.apply(JourneyEvent::Mishap, Some("Frár"), false),
+            "The journey takes 1 more day, and Frár gains 1 extra Fatigue."
+        );
+        assert_eq!(
+            resolution.apply(JourneyEvent::IllChoices, Some("Frár"), false),
+            "Frár gains 1 Shadow point (Dread)."
+        );
+        assert_eq!(
+            resolution.apply(JourneyEvent::JoyfulSight, Some("Frár"), true),
+            JourneyEvent::JoyfulSight.on_success()
+        );
+        assert_eq!(resolution.log.shadow["Frár"], 1);
+        assert_eq!(resolution.delay, 1);
+    }
+}
@@ -12,9 +12,26 @@ use spin_sdk::{
 };
 
 mod api;
+mod calendar;
+mod callings;
 mod characters;
+mod combat;
+mod companies;
 mod cultures;
+mod dice;
+mod eye;
+mod journeys;
+mod map;
+mod npcs;
+mod patrons;
 mod rand;
+mod regions;
+mod sanctuaries;
+mod settlements;
+mod solo;
+mod storage;
+mod tables;
+mod treasure;
 
 /// A simple Spin HTTP component.
 #[http_component]
@@ -0,0 +1,552 @@
+/*!
+# Map of Eriador
+
+The regions of Eriador and the paths between its notable places, used to plan the route of a
+journey. Distances are counted in map hexes of 10 miles each.
+*/
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+};
+
+use serde::{Deserialize, Serialize};
+use strum::{EnumIter, EnumString, IntoEnumIterator};
+use utoipa::ToSchema;
+
+use crate::{journeys::RouteSegment, regions::RegionType};
+
+/// Lie of the land in a region
+#[derive(Clone, Copy, Debug, Deserialize, strum::Display, Eq, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum Terrain {
+    Farmland,
+    Plains,
+    Hills,
+    Forest,
+    Marshes,
+    Mountains,
+}
+
+/// A region of Eriador
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    strum::Display,
+    EnumIter,
+    EnumString,
+    Eq,
+    Hash,
+    PartialEq,
+    Serialize,
+    ToSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum Region {
+    Angmar,
+    BarrowDowns,
+    BlueMountains,
+    BreeLand,
+    Bruinen,
+    Eregion,
+    Ettenmoors,
+    Evendim,
+    Gwathlo,
+    Lindon,
+    LoneLands,
+    Minhiriath,
+    NorthDowns,
+    TheShire,
+    Trollshaws,
+    WeatherHills,
+}
+
+impl Region {
+    /// Name the region is known by
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Region::Angmar => "Angmar",
+            Region::BarrowDowns => "the Barrow-downs",
+            Region::BlueMountains => "the Blue Mountains",
+            Region::BreeLand => "Bree-land",
+            Region::Bruinen => "the Vale of Bruinen",
+            Region::Eregion => "Eregion",
+            Region::Ettenmoors => "the Ettenmoors",
+            Region::Evendim => "the Hills of Evendim",
+            Region::Gwathlo => "the Fens of the Gwathló",
+            Region::Lindon => "Lindon",
+            Region::LoneLands => "the Lone-lands",
+            Region::Minhiriath => "Minhiriath",
+            Region::NorthDowns => "the North Downs",
+            Region::TheShire => "the Shire",
+            Region::Trollshaws => "the Trollshaws",
+            Region::WeatherHills => "the Weather Hills",
+        }
+    }
+
+    /// How far the Shadow has reached into the region
+    #[must_use]
+    pub fn region_type(self) -> RegionType {
+        match self {
+            Region::BreeLand | Region::Bruinen | Region::Lindon | Region::TheShire => {
+                RegionType::BorderLand
+            }
+            Region::BlueMountains
+            | Region::Eregion
+            | Region::Evendim
+            | Region::Gwathlo
+            | Region::LoneLands
+            | Region::Minhiriath
+            | Region::NorthDowns
+            | Region::WeatherHills => RegionType::WildLand,
+            Region::BarrowDowns | Region::Ettenmoors | Region::Trollshaws => RegionType::ShadowLand,
+            Region::Angmar => RegionType::DarkLand,
+        }
+    }
+
+    #[must_use]
+    pub fn terrain(self) -> Terrain {
+        match self {
+            Region::BreeLand | Region::TheShire => Terrain::Farmland,
+            Region::LoneLands | Region::Lindon | Region::Minhiriath => Terrain::Plains,
+            Region::BarrowDowns
+            | Region::Bruinen
+            | Region::Eregion
+            | Region::Evendim
+            | Region::NorthDowns
+            | Region::WeatherHills => Terrain::Hills,
+            Region::Trollshaws => Terrain::Forest,
+            Region::Gwathlo => Terrain::Marshes,
+            Region::Angmar | Region::BlueMountains | Region::Ettenmoors => Terrain::Mountains,
+        }
+    }
+}
+
+/// A notable place journeys can start from or lead to
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    strum::Display,
+    EnumIter,
+    EnumString,
+    Eq,
+    Hash,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    Serialize,
+    ToSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum Location {
+    Annuminas,
+    Bree,
+    Buckland,
+    CarnDum,
+    Fornost,
+    GreyHavens,
+    Hobbiton,
+    LastBridge,
+    MichelDelving,
+    OstInEdhil,
+    Rivendell,
+    SarnFord,
+    Tharbad,
+    ThorinsHalls,
+    Weathertop,
+}
+
+impl Location {
+    /// Name the place is known by
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Location::Annuminas => "Annúminas",
+            Location::Bree => "Bree",
+            Location::Buckland => "Buckland",
+            Location::CarnDum => "Carn Dûm",
+            Location::Fornost => "Fornost",
+            Location::GreyHavens => "the Grey Havens",
+            Location::Hobbiton => "Hobbiton",
+            Location::LastBridge => "the Last Bridge",
+            Location::MichelDelving => "Michel Delving",
+            Location::OstInEdhil => "Ost-in-Edhil",
+            Location::Rivendell => "Rivendell",
+            Location::SarnFord => "Sarn Ford",
+            Location::Tharbad => "Tharbad",
+            Location::ThorinsHalls => "Thorin's Halls",
+            Location::Weathertop => "Weathertop",
+        }
+    }
+
+    /// Region the place lies in
+    #[must_use]
+    pub fn region(self) -> Region {
+        match self {
+            Location::Annuminas => Region::Evendim,
+            Location::Bree => Region::BreeLand,
+            Location::Buckland
+            | Location::Hobbiton
+            | Location::MichelDelving
+            | Location::SarnFord => Region::TheShire,
+            Location::CarnDum => Region::Angmar,
+            Location::Fornost => Region::NorthDowns,
+            Location::GreyHavens => Region::Lindon,
+            Location::LastBridge => Region::LoneLands,
+            Location::OstInEdhil => Region::Eregion,
+            Location::Rivendell => Region::Bruinen,
+            Location::Tharbad => Region::Gwathlo,
+            Location::ThorinsHalls => Region::BlueMountains,
+            Location::Weathertop => Region::WeatherHills,
+        }
+    }
+}
+
+/// Summary of a location, as listed on the map
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LocationDescription {
+    id: Location,
+    name: &'static str,
+    region: Region,
+    region_type: RegionType,
+    terrain: Terrain,
+}
+
+impl From<Location> for LocationDescription {
+    fn from(location: Location) -> Self {
+        let region = location.region();
+        Self {
+            id: location,
+            name: location.name(),
+            region,
+            region_type: region.region_type(),
+            terrain: region.terrain(),
+        }
+    }
+}
+
+/// All locations on the map
+#[must_use]
+pub fn locations() -> Vec<LocationDescription> {
+    Location::iter().map(LocationDescription::from).collect()
+}
+
+/// A way between two locations, crossing one or more regions
+struct Path {
+    ends: [Location; 2],
+    /// Regions crossed and the hexes travelled in each, from the first end to the second
+    legs: &'static [(Region, u32)],
+}
+
+impl Path {
+    fn hexes(&self) -> u32 {
+        self.legs.iter().map(|(_, hexes)| hexes).sum()
+    }
+
+    fn other_end(&self, location: Location) -> Location {
+        if self.ends[0] == location {
+            self.ends[1]
+        } else {
+            self.ends[0]
+        }
+    }
+
+    /// Legs in the order they are travelled when setting out from `start`
+    fn legs_from(&self, start: Location) -> Vec<(Region, u32)> {
+        let mut legs = self.legs.to_vec();
+        if self.ends[1] == start {
+            legs.reverse();
+        }
+        legs
+    }
+}
+
+const PATHS: [Path; 18] = [
+    Path {
+        ends: [Location::ThorinsHalls, Location::GreyHavens],
+        legs: &[(Region::BlueMountains, 3), (Region::Lindon, 3)],
+    },
+    Path {
+        ends: [Location::GreyHavens, Location::MichelDelving],
+        legs: &[(Region::Lindon, 3), (Region::TheShire, 2)],
+    },
+    Path {
+        ends: [Location::MichelDelving, Location::Hobbiton],
+        legs: &[(Region::TheShire, 3)],
+    },
+    Path {
+        ends: [Location::MichelDelving, Location::SarnFord],
+        legs: &[(Region::TheShire, 4)],
+    },
+    Path {
+        ends: [Location::Hobbiton, Location::Buckland],
+        legs: &[(Region::TheShire, 4)],
+    },
+    Path {
+        ends: [Location::Hobbiton, Location::Annuminas],
+        legs: &[(Region::TheShire, 3), (Region::Evendim, 3)],
+    },
+    Path {
+        ends: [Location::Buckland, Location::Bree],
+        legs: &[(Region::TheShire, 1), (Region::BreeLand, 4)],
+    },
+    Path {
+        ends: [Location::Bree, Location::Weathertop],
+        legs: &[
+            (Region::BreeLand, 2),
+            (Region::LoneLands, 3),
+            (Region::WeatherHills, 3),
+        ],
+    },
+    Path {
+        ends: [Location::Weathertop, Location::LastBridge],
+        legs: &[(Region::LoneLands, 8)],
+    },
+    Path {
+        ends: [Location::LastBridge, Location::Rivendell],
+        legs: &[(Region::Trollshaws, 5), (Region::Bruinen, 1)],
+    },
+    Path {
+        ends: [Location::Bree, Location::Fornost],
+        legs: &[(Region::BreeLand, 2), (Region::NorthDowns, 8)],
+    },
+    Path {
+        ends: [Location::Fornost, Location::Annuminas],
+        legs: &[(Region::NorthDowns, 3), (Region::Evendim, 3)],
+    },
+    Path {
+        ends: [Location::Fornost, Location::CarnDum],
+        legs: &[(Region::NorthDowns, 4), (Region::Angmar, 10)],
+    },
+    Path {
+        ends: [Location::Rivendell, Location::CarnDum],
+        legs: &[(Region::Ettenmoors, 10), (Region::Angmar, 6)],
+    },
+    Path {
+        ends: [Location::Bree, Location::Tharbad],
+        legs: &[
+            (Region::BreeLand, 1),
+            (Region::BarrowDowns, 2),
+            (Region::Minhiriath, 10),
+            (Region::Gwathlo, 1),
+        ],
+    },
+    Path {
+        ends: [Location::Tharbad, Location::SarnFord],
+        legs: &[(Region::Gwathlo, 1), (Region::Minhiriath, 6)],
+    },
+    Path {
+        ends: [Location::Tharbad, Location::OstInEdhil],
+        legs: &[(Region::Gwathlo, 2), (Region::Eregion, 9)],
+    },
+    Path {
+        ends: [Location::OstInEdhil, Location::Rivendell],
+        legs: &[
+            (Region::Eregion, 6),
+            (Region::Trollshaws, 3),
+            (Region::Bruinen, 1),
+        ],
+    },
+];
+
+/// Part of a route crossing a single region
+#[derive(Clone, Copy, Debug, Serialize, ToSchema)]
+pub struct RouteLeg {
+    region: Region,
+    name: &'static str,
+    region_type: RegionType,
+    terrain: Terrain,
+    hexes: u32,
+}
+
+/// The shortest way between two locations
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Route {
+    /// Total number of 10-mile map hexes travelled
+    hexes: u32,
+    /// Locations passed on the way, from the start to the destination
+    stops: Vec<Location>,
+    /// Regions crossed, in order
+    legs: Vec<RouteLeg>,
+    /// Hexes travelled through each type of region, ready to be used as a journey's route
+    segments: Vec<RouteSegment>,
+}
+
+impl Route {
+    fn new(stops: Vec<Location>, regions: impl IntoIterator<Item = (Region, u32)>) -> Self {
+        let mut legs = Vec::<RouteLeg>::new();
+        for (region, hexes) in regions {
+            match legs.last_mut() {
+                Some(last) if last.region == region => last.hexes += hexes,
+                _ => legs.push(RouteLeg {
+                    region,
+                    name: region.name(),
+                    region_type: region.region_type(),
+                    terrain: region.terrain(),
+                    hexes,
+                }),
+            }
+        }
+
+        let mut segments = Vec::<(RegionType, u32)>::new();
+        for leg in &legs {
+            match segments.last_mut() {
+                Some((region_type, hexes)) if *region_type == leg.region_type => {
+                    *hexes += leg.hexes;
+                }
+                _ => segments.push((leg.region_type, leg.hexes)),
+            }
+        }
+
+        Self {
+            hexes: legs.iter().map(|leg| leg.hexes).sum(),
+            stops,
+            legs,
+            segments: segments
+                .into_iter()
+                .map(|(region_type, hexes)| RouteSegment::new(region_type, hexes))
+                .collect(),
+        }
+    }
+
+    /// Find the route covering the fewest hexes between two locations
+    #[must_use]
+    pub fn find(from: Location, to: Location) -> Option<Self> {
+        let mut distances = HashMap::from([(from, 0)]);
+        let mut previous = HashMap::<Location, (Location, &Path)>::new();
+        let mut queue = BinaryHeap::from([Reverse((0, from))]);
+
+        while let Some(Reverse((distance, location))) = queue.pop() {
+            if location == to {
+                break;
+            }
+            if distances
+                .get(&location)
+                .is_some_and(|known| distance > *known)
+            {
+                continue;
+            }
+            for path in PATHS.iter().filter(|path| path.ends.contains(&location)) {
+                let next = path.other_end(location);
+                let distance = distance + path.hexes();
+                if distances.get(&next).is_none_or(|known| distance < *known) {
+                    distances.insert(next, distance);
+                    previous.insert(next, (location, path));
+                    queue.push(Reverse((distance, next)));
+                }
+            }
+        }
+
+        if !distances.contains_key(&to) {
+            return None;
+        }
+
+        let mut stops = vec![to];
+        let mut paths = vec![];
+        let mut current = to;
+        while let Some((start, path)) = previous.get(&current) {
+            stops.push(*start);
+            paths.push((*start, *path));
+            current = *start;
+        }
+        stops.reverse();
+        paths.reverse();
+
+        Some(Self::new(
+            stops,
+            paths
+                .into_iter()
+                .flat_map(|(start, path)| path.legs_from(start)),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::journeys::MAX_ROUTE_HEXES;
+
+    use super::*;
+
+    #[test]
+    fn every_path_leads_between_known_regions() {
+        for path in &PATHS {
+            assert_ne!(path.ends[0], path.ends[1]);
+            assert!(path.hexes() > 0);
+        }
+    }
+
+    #[test]
+    fn every_location_can_be_reached() {
+        for location in Location::iter() {
+            assert!(Route::find(Location::Bree, location).is_some());
+        }
+    }
+
+    #[test]
+    fn every_route_can_be_travelled() {
+        for from in Location::iter() {
+            for to in Location::iter() {
+                assert!(Route::find(from, to).unwrap().hexes <= MAX_ROUTE_HEXES);
+            }
+        }
+    }
+
+    #[test]
+    fn road_to_rivendell_passes_weathertop() {
+        let route = Route::find(Location::Bree, Location::Rivendell).unwrap();
+
+        assert_eq!(
+            route.stops,
+            [
+                Location::Bree,
+                Location::Weathertop,
+                Location::LastBridge,
+                Location::Rivendell
+            ]
+        );
+        assert_eq!(route.hexes, 22);
+        assert_eq!(route.legs.first().unwrap().region, Region::BreeLand);
+        assert_eq!(route.legs.last().unwrap().region, Region::Bruinen);
+    }
+
+    #[test]
+    fn legs_in_the_same_region_are_joined() {
+        let route = Route::find(Location::Hobbiton, Location::GreyHavens).unwrap();
+
+        assert_eq!(route.hexes, 8);
+        assert_eq!(route.legs.len(), 2);
+        assert_eq!(route.legs[0].hexes, 5);
+        assert_eq!(route.segments.len(), 1);
+    }
+
+    #[test]
+    fn way_back_is_as_long() {
+        let route = Route::find(Location::ThorinsHalls, Location::CarnDum).unwrap();
+        let back = Route::find(Location::CarnDum, Location::ThorinsHalls).unwrap();
+
+        assert_eq!(route.hexes, back.hexes);
+        assert_eq!(route.legs.first().unwrap().region, Region::BlueMountains);
+        assert_eq!(back.legs.first().unwrap().region, Region::Angmar);
+        assert_eq!(
+            serde_json::to_value(route.segments.last()).unwrap()["region"],
+            "dark-land"
+        );
+    }
+
+    #[test]
+    fn route_to_the_same_place_is_empty() {
+        let route = Route::find(Location::Tharbad, Location::Tharbad).unwrap();
+
+        assert_eq!(route.hexes, 0);
+        assert_eq!(route.stops, [Location::Tharbad]);
+        assert!(route.legs.is_empty());
+    }
+}
@@ -0,0 +1,243 @@
+/*!
+# Non-player characters
+
+The folk the company meets on the road, in taverns and at the gates of towns, with enough detail
+for the Loremaster to bring them to life on the spot.
+*/
+
+use rand::{
+    Rng,
+    distr::{Distribution, StandardUniform},
+    seq::IndexedRandom,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::cultures::HeroicCulture;
+
+/// How an NPC feels about the company when they first meet
+#[derive(Clone, Copy, Debug, Deserialize, strum::Display, Eq, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum Attitude {
+    Friendly,
+    Helpful,
+    Neutral,
+    Wary,
+    Hostile,
+}
+
+impl Distribution<Attitude> for StandardUniform {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Attitude {
+        // Most folk are neither friends nor foes of a band of strangers
+        match rng.random_range(0..10) {
+            0 => Attitude::Friendly,
+            1 | 2 => Attitude::Helpful,
+            3..=6 => Attitude::Neutral,
+            7 | 8 => Attitude::Wary,
+            _ => Attitude::Hostile,
+        }
+    }
+}
+
+/// A character played by the Loremaster
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Npc {
+    name: String,
+    culture: HeroicCulture,
+    occupation: &'static str,
+    /// Two Distinctive Features that stand out when meeting them
+    distinctive_features: Vec<&'static str>,
+    attitude: Attitude,
+    /// What they want most
+    motivation: &'static str,
+    /// Something they would rather the company did not learn
+    secret: &'static str,
+}
+
+impl Npc {
+    /// Generate an NPC of the given culture
+    pub fn random<R: Rng + ?Sized>(rng: &mut R, culture: HeroicCulture) -> Self {
+        let occupation = occupation(rng, culture);
+        Self::with_occupation(rng, culture, occupation)
+    }
+
+    /// Generate an NPC of the given culture who makes a living as `occupation`
+    pub fn with_occupation<R: Rng + ?Sized>(
+        rng: &mut R,
+        culture: HeroicCulture,
+        occupation: &'static str,
+    ) -> Self {
+        Self {
+            name: culture.random_name(rng),
+            culture,
+            occupation,
+            distinctive_features: DISTINCTIVE_FEATURES
+                .choose_multiple(rng, 2)
+                .copied()
+                .collect(),
+            attitude: rng.random(),
+            motivation: MOTIVATIONS.choose(rng).unwrap(),
+            secret: SECRETS.choose(rng).unwrap(),
+        }
+    }
+}
+
+/// Pick an occupation common among the given folk
+pub fn occupation<R: Rng + ?Sized>(rng: &mut R, culture: HeroicCulture) -> &'static str {
+    occupations(culture)
+        .choose_weighted(rng, |(_, weight)| *weight)
+        .map(|(occupation, _)| *occupation)
+        .unwrap()
+}
+
+/// Occupations of the given folk, with how common each one is
+#[must_use]
+pub fn occupations(culture: HeroicCulture) -> &'static [(&'static str, u32)] {
+    match culture {
+        HeroicCulture::Bardings => &[
+            ("merchant", 3),
+            ("boatman", 2),
+            ("guard", 2),
+            ("smith", 1),
+            ("herald", 1),
+        ],
+        HeroicCulture::DwarvesOfDurinsFolk => &[
+            ("smith", 4),
+            ("miner", 3),
+            ("merchant", 2),
+            ("stonemason", 2),
+            ("toymaker", 1),
+        ],
+        HeroicCulture::ElvesOfLindon => &[
+            ("wanderer", 3),
+            ("minstrel", 2),
+            ("loremaster", 2),
+            ("shipwright", 2),
+            ("healer", 1),
+        ],
+        HeroicCulture::HobbitsOfTheShire => &[
+            ("farmer", 5),
+            ("gardener", 3),
+            ("innkeeper", 2),
+            ("miller", 2),
+            ("shirriff", 1),
+            ("postmaster", 1),
+        ],
+        HeroicCulture::MenOfBree => &[
+            ("farmer", 5),
+            ("innkeeper", 2),
+            ("watchman", 2),
+            ("ostler", 2),
+            ("pedlar", 2),
+            ("gatekeeper", 1),
+            ("hunter", 1),
+        ],
+        HeroicCulture::RangersOfTheNorth => {
+            &[("wanderer", 4), ("tracker", 3), ("scout", 2), ("healer", 1)]
+        }
+    }
+}
+
+const DISTINCTIVE_FEATURES: &[&str] = &[
+    "bold",
+    "cunning",
+    "energetic",
+    "fair",
+    "fair-spoken",
+    "fierce",
+    "generous",
+    "honourable",
+    "inquisitive",
+    "keen-eyed",
+    "lordly",
+    "merry",
+    "patient",
+    "proud",
+    "rustic",
+    "secretive",
+    "steadfast",
+    "stern",
+    "subtle",
+    "swift",
+    "tall",
+    "true-hearted",
+    "wary",
+    "wilful",
+];
+
+const MOTIVATIONS: &[&str] = &[
+    "to pay off a debt before the winter comes",
+    "to find a missing relative who went east",
+    "to win the respect of their elders",
+    "to see the sea before they die",
+    "to keep their family safe from the troubles on the road",
+    "to be rid of a rival in trade",
+    "to recover an heirloom lost long ago",
+    "to hear news of the wider world",
+    "to earn enough to leave for good",
+    "to protect a friend who is in over their head",
+];
+
+const SECRETS: &[&str] = &[
+    "they owe money to unsavoury folk from the south",
+    "they once sold information to a stranger in a black cloak",
+    "they found an old ring in a barrow and kept it",
+    "they are not who they claim to be",
+    "they ran away from an apprenticeship years ago",
+    "they saw something terrible in the woods and told no one",
+    "they are secretly in love with a member of a rival family",
+    "they have been taking a little from the till",
+    "they sheltered a wounded Ranger without telling anyone",
+    "they know a hidden path that avoids the gate",
+];
+
+#[cfg(test)]
+mod test {
+    use strum::IntoEnumIterator;
+
+    use crate::rand::rng_from_seed;
+
+    use super::*;
+
+    #[test]
+    fn every_culture_has_occupations() {
+        for culture in HeroicCulture::iter() {
+            assert!(!occupations(culture).is_empty());
+        }
+    }
+
+    #[test]
+    fn bree_landers_are_mostly_farmers() {
+        let mut rng = rng_from_seed(2965);
+        let farmers = (0..1000)
+            .filter(|_| occupation(&mut rng, HeroicCulture::MenOfBree) == "farmer")
+            .count();
+
+        assert!(farmers > 250);
+        assert!(farmers < 450);
+    }
+
+    #[test]
+    fn distinctive_features_are_different() {
+        let mut rng = rng_from_seed(1);
+        for _ in 0..100 {
+            let npc = Npc::random(&mut rng, HeroicCulture::HobbitsOfTheShire);
+
+            assert_eq!(npc.distinctive_features.len(), 2);
+            assert_ne!(npc.distinctive_features[0], npc.distinctive_features[1]);
+        }
+    }
+
+    #[test]
+    fn occupation_matches_culture() {
+        let npc = Npc::random(&mut rng_from_seed(7), HeroicCulture::DwarvesOfDurinsFolk);
+
+        assert!(
+            occupations(HeroicCulture::DwarvesOfDurinsFolk)
+                .iter()
+                .any(|(occupation, _)| *occupation == npc.occupation)
+        );
+        assert!(!npc.name.is_empty());
+    }
+}
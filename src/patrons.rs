@@ -0,0 +1,198 @@
+/*!
+# Patrons
+
+Notable personalities of Eriador who support a company with advice, aid and the occasional
+errand.
+*/
+use rand::{Rng, seq::IndexedRandom};
+use serde::{Deserialize, Serialize};
+use strum::{EnumIter, EnumString, IntoEnumIterator};
+use utoipa::ToSchema;
+
+use crate::{callings::Calling, characters::Character, cultures::HeroicCulture};
+
+/// A patron a company can be associated with
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    strum::Display,
+    EnumIter,
+    EnumString,
+    Eq,
+    Hash,
+    PartialEq,
+    Serialize,
+    ToSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum Patron {
+    /// Balin son of Fundin dreams of reclaiming the lost halls of Moria, and looks favourably on
+    /// those who share an interest in the ancient realms of the Dwarves.
+    Balin,
+    /// Bilbo Baggins of Bag End, returned from his adventures, enjoys the company of those who
+    /// bring him news, songs and tales from the wide world.
+    Bilbo,
+    /// Círdan the Shipwright, Lord of the Havens, is the eldest of the Elves in Middle-earth and
+    /// watches the western shores against the Shadow.
+    Cirdan,
+    /// Gandalf the Grey wanders the lands of the Free Peoples, rekindling hearts and sending
+    /// companies on errands whose purpose they seldom fully grasp.
+    Gandalf,
+    /// Gilraen the Fair, mother of Aragorn, lives among her people in Eriador and sends the
+    /// Rangers and their friends to guard the land.
+    Gilraen,
+    /// Tom Bombadil, Master of wood, water and hill, and his wife Goldberry offer shelter to
+    /// travellers lost in the Old Forest.
+    TomBombadil,
+}
+
+impl Patron {
+    /// Name the patron is known by
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Patron::Balin => "Balin, son of Fundin",
+            Patron::Bilbo => "Bilbo Baggins",
+            Patron::Cirdan => "Círdan the Shipwright",
+            Patron::Gandalf => "Gandalf the Grey",
+            Patron::Gilraen => "Gilraen the Fair",
+            Patron::TomBombadil => "Tom Bombadil and Goldberry",
+        }
+    }
+
+    /// Points added to the Fellowship Rating of a company with this patron
+    #[must_use]
+    pub fn fellowship_bonus(self) -> u32 {
+        match self {
+            Patron::Cirdan | Patron::Gandalf => 0,
+            Patron::Balin | Patron::Gilraen => 1,
+            Patron::Bilbo => 2,
+            Patron::TomBombadil => 3,
+        }
+    }
+
+    /// Heroic cultures the patron is most inclined to take an interest in
+    #[must_use]
+    pub fn favoured_cultures(self) -> &'static [HeroicCulture] {
+        match self {
+            Patron::Balin => &[HeroicCulture::DwarvesOfDurinsFolk, HeroicCulture::Bardings],
+            Patron::Bilbo => &[
+                HeroicCulture::HobbitsOfTheShire,
+                HeroicCulture::ElvesOfLindon,
+            ],
+            Patron::Cirdan => &[HeroicCulture::ElvesOfLindon],
+            Patron::Gandalf => &[HeroicCulture::HobbitsOfTheShire, HeroicCulture::Bardings],
+            Patron::Gilraen => &[HeroicCulture::RangersOfTheNorth, HeroicCulture::MenOfBree],
+            Patron::TomBombadil => &[HeroicCulture::HobbitsOfTheShire, HeroicCulture::MenOfBree],
+        }
+    }
+
+    /// Callings the patron is most inclined to take an interest in
+    #[must_use]
+    pub fn favoured_callings(self) -> &'static [Calling] {
+        match self {
+            Patron::Balin => &[Calling::TreasureHunter, Calling::Champion],
+            Patron::Bilbo => &[Calling::Scholar, Calling::TreasureHunter],
+            Patron::Cirdan => &[Calling::Warden, Calling::Messenger],
+            Patron::Gandalf => &[Calling::Messenger, Calling::Captain],
+            Patron::Gilraen => &[Calling::Warden, Calling::Captain],
+            Patron::TomBombadil => &[Calling::Scholar, Calling::Messenger],
+        }
+    }
+
+    /// How well the patron suits the given heroes: one point per favoured culture or calling
+    #[must_use]
+    pub fn affinity(self, heroes: &[Character]) -> usize {
+        heroes
+            .iter()
+            .map(|hero| {
+                usize::from(self.favoured_cultures().contains(&hero.heroic_culture()))
+                    + usize::from(self.favoured_callings().contains(&hero.calling()))
+            })
+            .sum()
+    }
+
+    /// Pick one of the patrons best suited to the given heroes
+    pub fn suitable_for<R: Rng + ?Sized>(rng: &mut R, heroes: &[Character]) -> Self {
+        let best = Patron::iter()
+            .map(|patron| patron.affinity(heroes))
+            .max()
+            .unwrap_or_default();
+
+        *Patron::iter()
+            .filter(|patron| patron.affinity(heroes) == best)
+            .collect::<Vec<_>>()
+            .choose(rng)
+            .unwrap()
+    }
+}
+
+/// Summary of a patron, as listed in the catalogue
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PatronDescription {
+    id: Patron,
+    name: &'static str,
+    fellowship_bonus: u32,
+    favoured_cultures: &'static [HeroicCulture],
+    favoured_callings: &'static [Calling],
+}
+
+impl From<Patron> for PatronDescription {
+    fn from(patron: Patron) -> Self {
+        Self {
+            id: patron,
+            name: patron.name(),
+            fellowship_bonus: patron.fellowship_bonus(),
+            favoured_cultures: patron.favoured_cultures(),
+            favoured_callings: patron.favoured_callings(),
+        }
+    }
+}
+
+/// All patrons in the catalogue
+#[must_use]
+pub fn catalogue() -> Vec<PatronDescription> {
+    Patron::iter().map(PatronDescription::from).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rand::rng_from_os_rng;
+
+    use super::*;
+
+    #[test]
+    fn every_patron_favours_someone() {
+        for patron in Patron::iter() {
+            assert!(!patron.favoured_cultures().is_empty());
+            assert!(!patron.favoured_callings().is_empty());
+        }
+    }
+
+    #[test]
+    fn picks_patron_with_highest_affinity() {
+        let heroes = [
+            Character::new(
+                HeroicCulture::DwarvesOfDurinsFolk,
+                Calling::TreasureHunter,
+                "Frár",
+            ),
+            Character::new(HeroicCulture::Bardings, Calling::Champion, "Lifstan"),
+        ];
+
+        assert_eq!(
+            Patron::Balin,
+            Patron::suitable_for(&mut rng_from_os_rng(), &heroes)
+        );
+    }
+
+    #[test]
+    fn picks_any_patron_without_heroes() {
+        let patron = Patron::suitable_for(&mut rng_from_os_rng(), &[]);
+
+        assert_eq!(patron.affinity(&[]), 0);
+    }
+}
@@ -18,3 +18,45 @@ use rand_pcg::Pcg64;
 pub fn rng_from_os_rng() -> impl Rng {
     Pcg64::from_os_rng()
 }
+
+/// Creates a new instance of the RNG from a seed, so that the same results
+/// can be generated again later.
+///
+/// ```
+/// use rand::Rng;
+///
+/// let x: u32 = rand_utils::rng_from_seed(42).random();
+/// assert_eq!(x, rand_utils::rng_from_seed(42).random::<u32>());
+/// ```
+#[must_use]
+pub fn rng_from_seed(seed: u64) -> impl Rng {
+    Pcg64::seed_from_u64(seed)
+}
+
+/// The given seed, or a new random one if none was provided.
+///
+/// New seeds are kept within the range of a `u32` so they survive a round trip
+/// through JSON numbers in any client.
+#[must_use]
+pub fn seed_or_random(seed: Option<u64>) -> u64 {
+    seed.unwrap_or_else(|| rng_from_os_rng().random::<u32>().into())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn same_seed_generates_same_values() {
+        let seed = seed_or_random(None);
+        let first = rng_from_seed(seed).random::<u64>();
+        let second = rng_from_seed(seed).random::<u64>();
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn given_seed_is_kept() {
+        assert_eq!(seed_or_random(Some(u64::MAX)), u64::MAX);
+    }
+}
@@ -0,0 +1,54 @@
+/*!
+# Regions
+
+The lands of Middle-earth are classified by how far the Shadow has reached into them.
+*/
+use serde::{Deserialize, Serialize};
+use strum::{EnumIter, EnumString};
+use utoipa::ToSchema;
+
+/// How dangerous a region is to travel through
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Deserialize,
+    strum::Display,
+    EnumIter,
+    EnumString,
+    Eq,
+    Hash,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    Serialize,
+    ToSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+#[expect(clippy::enum_variant_names)]
+pub enum RegionType {
+    /// Lands close to the settlements of the Free Peoples, patrolled and mostly safe
+    BorderLand,
+    /// The untamed lands between settlements, where few folk dwell
+    #[default]
+    WildLand,
+    /// Lands where the Shadow has taken root and its servants roam freely
+    ShadowLand,
+    /// Lands directly under the dominion of the Enemy
+    DarkLand,
+}
+
+impl RegionType {
+    /// Eye Awareness at which the Enemy notices a company travelling through this region
+    #[must_use]
+    pub fn hunt_threshold(self) -> u32 {
+        match self {
+            RegionType::BorderLand => 18,
+            RegionType::WildLand => 16,
+            RegionType::ShadowLand => 14,
+            RegionType::DarkLand => 12,
+        }
+    }
+}
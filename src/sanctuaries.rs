@@ -0,0 +1,292 @@
+/*!
+# Sanctuaries
+
+Places of safety where a company can spend its Fellowship phases. A company chooses one of them
+as its Safe Haven, and the undertakings its heroes can pursue depend on where they rest.
+*/
+use serde::{Deserialize, Serialize};
+use strum::{EnumIter, EnumString, IntoEnumIterator};
+use utoipa::ToSchema;
+
+use crate::{
+    characters::Character, companies::fellowship::UndertakingKind, cultures::HeroicCulture,
+    map::Location, patrons::Patron,
+};
+
+/// A sanctuary a company can choose as its Safe Haven
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    strum::Display,
+    EnumIter,
+    EnumString,
+    Eq,
+    Hash,
+    PartialEq,
+    Serialize,
+    ToSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum Sanctuary {
+    /// The crossroads town of Men and Hobbits, where news from all of Eriador is heard at the
+    /// Prancing Pony.
+    Bree,
+    /// The harbour of Círdan on the Gulf of Lhûn, from which the Elves sail into the West.
+    GreyHavens,
+    /// The Last Homely House east of the Sea, where Elrond shelters all who oppose the Shadow.
+    Rivendell,
+    /// The green and pleasant country of the Hobbits, watched over by the Rangers.
+    TheShire,
+    /// The ruins of the once great river-port, now a haunt of outlaws and river-folk.
+    TharbadRuins,
+    /// The halls of the Longbeards in exile, delved into the Blue Mountains.
+    ThorinsHalls,
+}
+
+impl Sanctuary {
+    /// Sanctuary known by `name`, ignoring case and a leading "the", or by its id
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim();
+        let without_article = |name: &'static str| name.strip_prefix("the ").unwrap_or(name);
+        Sanctuary::iter().find(|sanctuary| {
+            name.eq_ignore_ascii_case(&sanctuary.to_string())
+                || name.eq_ignore_ascii_case(sanctuary.name())
+                || name.eq_ignore_ascii_case(without_article(sanctuary.name()))
+        })
+    }
+
+    /// Name the sanctuary is known by
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Sanctuary::Bree => "Bree",
+            Sanctuary::GreyHavens => "the Grey Havens",
+            Sanctuary::Rivendell => "Rivendell",
+            Sanctuary::TheShire => "the Shire",
+            Sanctuary::TharbadRuins => "the ruins of Tharbad",
+            Sanctuary::ThorinsHalls => "Thorin's Halls",
+        }
+    }
+
+    /// Where the sanctuary lies on the map
+    #[must_use]
+    pub fn location(self) -> Location {
+        match self {
+            Sanctuary::Bree => Location::Bree,
+            Sanctuary::GreyHavens => Location::GreyHavens,
+            Sanctuary::Rivendell => Location::Rivendell,
+            Sanctuary::TheShire => Location::Hobbiton,
+            Sanctuary::TharbadRuins => Location::Tharbad,
+            Sanctuary::ThorinsHalls => Location::ThorinsHalls,
+        }
+    }
+
+    /// Heroic cultures that count the sanctuary as their home
+    #[must_use]
+    pub fn home_to(self) -> &'static [HeroicCulture] {
+        match self {
+            Sanctuary::Bree => &[HeroicCulture::MenOfBree],
+            Sanctuary::GreyHavens => &[HeroicCulture::ElvesOfLindon],
+            Sanctuary::Rivendell => &[HeroicCulture::RangersOfTheNorth],
+            Sanctuary::TheShire => &[HeroicCulture::HobbitsOfTheShire],
+            Sanctuary::TharbadRuins => &[],
+            Sanctuary::ThorinsHalls => &[HeroicCulture::DwarvesOfDurinsFolk],
+        }
+    }
+
+    /// Undertakings heroes can pursue while resting at the sanctuary
+    #[must_use]
+    pub fn undertakings(self) -> &'static [UndertakingKind] {
+        match self {
+            Sanctuary::Bree => &[
+                UndertakingKind::MeetPatron,
+                UndertakingKind::RaiseStandardOfLiving,
+                UndertakingKind::StrengthenFellowship,
+                UndertakingKind::WriteASong,
+            ],
+            Sanctuary::GreyHavens => &[
+                UndertakingKind::HealScars,
+                UndertakingKind::MeetPatron,
+                UndertakingKind::StrengthenFellowship,
+                UndertakingKind::StudyMagicalItems,
+                UndertakingKind::WriteASong,
+            ],
+            Sanctuary::Rivendell => &[
+                UndertakingKind::HealScars,
+                UndertakingKind::MeetPatron,
+                UndertakingKind::RaiseStandardOfLiving,
+                UndertakingKind::StrengthenFellowship,
+                UndertakingKind::StudyMagicalItems,
+                UndertakingKind::WriteASong,
+            ],
+            Sanctuary::TheShire => &[
+                UndertakingKind::HealScars,
+                UndertakingKind::MeetPatron,
+                UndertakingKind::RaiseStandardOfLiving,
+                UndertakingKind::StrengthenFellowship,
+                UndertakingKind::WriteASong,
+            ],
+            Sanctuary::TharbadRuins => &[
+                UndertakingKind::StrengthenFellowship,
+                UndertakingKind::WriteASong,
+            ],
+            Sanctuary::ThorinsHalls => &[
+                UndertakingKind::MeetPatron,
+                UndertakingKind::RaiseStandardOfLiving,
+                UndertakingKind::StrengthenFellowship,
+                UndertakingKind::StudyMagicalItems,
+                UndertakingKind::WriteASong,
+            ],
+        }
+    }
+
+    /// Patrons who can be met at the sanctuary
+    #[must_use]
+    pub fn patrons(self) -> &'static [Patron] {
+        match self {
+            Sanctuary::Bree => &[Patron::Gandalf, Patron::Gilraen],
+            Sanctuary::GreyHavens => &[Patron::Cirdan],
+            Sanctuary::Rivendell => &[Patron::Bilbo, Patron::Gandalf, Patron::Gilraen],
+            Sanctuary::TheShire => &[Patron::Bilbo, Patron::Gandalf, Patron::TomBombadil],
+            Sanctuary::TharbadRuins => &[],
+            Sanctuary::ThorinsHalls => &[Patron::Balin],
+        }
+    }
+
+    /// Whether resting at the sanctuary lets heroes shed Shadow outside of Yule
+    #[must_use]
+    pub fn sheds_shadow(self) -> bool {
+        self != Sanctuary::TharbadRuins
+    }
+
+    /// Rules that only apply at this sanctuary
+    #[must_use]
+    pub fn special_rules(self) -> &'static str {
+        match self {
+            Sanctuary::Bree => {
+                "Heroes who rest at the Prancing Pony hear a rumour of the Bree-land before \
+                 setting out again."
+            }
+            Sanctuary::GreyHavens => {
+                "Only Elves and those they vouch for are welcome. Heroes of other cultures must \
+                 be accompanied by an Elf of Lindon."
+            }
+            Sanctuary::Rivendell => {
+                "The Last Homely House can only be found by those who know the way, or who are \
+                 guided there by a Ranger or an Elf."
+            }
+            Sanctuary::TheShire => {
+                "Big Folk are watched closely by the Shirriffs, and are expected to keep to the \
+                 roads and inns."
+            }
+            Sanctuary::TharbadRuins => {
+                "The ruins offer shelter but little rest: heroes shed no Shadow here outside \
+                 of Yule."
+            }
+            Sanctuary::ThorinsHalls => {
+                "Dwarf-smiths can mend and study arms and armour for heroes who pay them \
+                 in Treasure."
+            }
+        }
+    }
+
+    /// Number of heroes who count the sanctuary as their home
+    #[must_use]
+    pub fn affinity(self, heroes: &[Character]) -> usize {
+        heroes
+            .iter()
+            .filter(|hero| self.home_to().contains(&hero.heroic_culture()))
+            .count()
+    }
+
+    /// The sanctuary most of the heroes call home, preferring Bree when none of them do
+    #[must_use]
+    pub fn suitable_for(heroes: &[Character]) -> Self {
+        Sanctuary::iter()
+            .max_by_key(|sanctuary| (sanctuary.affinity(heroes), *sanctuary == Sanctuary::Bree))
+            .unwrap_or(Sanctuary::Bree)
+    }
+}
+
+/// Summary of a sanctuary, as listed in the catalogue
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SanctuaryDescription {
+    id: Sanctuary,
+    name: &'static str,
+    location: Location,
+    home_to: &'static [HeroicCulture],
+    undertakings: &'static [UndertakingKind],
+    patrons: &'static [Patron],
+    special_rules: &'static str,
+}
+
+impl From<Sanctuary> for SanctuaryDescription {
+    fn from(sanctuary: Sanctuary) -> Self {
+        Self {
+            id: sanctuary,
+            name: sanctuary.name(),
+            location: sanctuary.location(),
+            home_to: sanctuary.home_to(),
+            undertakings: sanctuary.undertakings(),
+            patrons: sanctuary.patrons(),
+            special_rules: sanctuary.special_rules(),
+        }
+    }
+}
+
+/// All sanctuaries in the catalogue
+#[must_use]
+pub fn catalogue() -> Vec<SanctuaryDescription> {
+    Sanctuary::iter().map(SanctuaryDescription::from).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::callings::Calling;
+
+    use super::*;
+
+    #[test]
+    fn every_sanctuary_allows_resting_undertakings() {
+        for sanctuary in Sanctuary::iter() {
+            assert!(
+                sanctuary
+                    .undertakings()
+                    .contains(&UndertakingKind::StrengthenFellowship)
+            );
+            assert_eq!(
+                sanctuary
+                    .undertakings()
+                    .contains(&UndertakingKind::MeetPatron),
+                !sanctuary.patrons().is_empty()
+            );
+        }
+    }
+
+    #[test]
+    fn every_patron_can_be_met_somewhere() {
+        for patron in Patron::iter() {
+            assert!(Sanctuary::iter().any(|sanctuary| sanctuary.patrons().contains(&patron)));
+        }
+    }
+
+    #[test]
+    fn picks_sanctuary_most_heroes_call_home() {
+        let heroes = [
+            Character::new(
+                HeroicCulture::HobbitsOfTheShire,
+                Calling::Scholar,
+                "Marigold",
+            ),
+            Character::new(HeroicCulture::HobbitsOfTheShire, Calling::Warden, "Hamfast"),
+            Character::new(HeroicCulture::MenOfBree, Calling::Captain, "Tom"),
+        ];
+
+        assert_eq!(Sanctuary::suitable_for(&heroes), Sanctuary::TheShire);
+        assert_eq!(Sanctuary::suitable_for(&[]), Sanctuary::Bree);
+    }
+}
@@ -0,0 +1,450 @@
+/*!
+# Settlements
+
+Hamlets, villages and towns of Eriador, the folk who live there and the inn where travellers
+gather to share news.
+*/
+
+use std::ops::RangeInclusive;
+
+use rand::{Rng, seq::IndexedRandom};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{cultures::HeroicCulture, map::Region, npcs::Npc, tables::rumours::RumourRoll};
+
+/// How big a settlement is
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, strum::Display, Eq, PartialEq, Serialize, ToSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum SettlementSize {
+    /// A handful of homesteads
+    Hamlet,
+    #[default]
+    Village,
+    /// A walled town on a busy road
+    Town,
+}
+
+impl SettlementSize {
+    #[must_use]
+    pub fn population(self) -> RangeInclusive<u32> {
+        match self {
+            SettlementSize::Hamlet => 20..=80,
+            SettlementSize::Village => 100..=400,
+            SettlementSize::Town => 500..=2000,
+        }
+    }
+
+    /// Number of folk living there in noticeable numbers
+    fn folk(self) -> usize {
+        match self {
+            SettlementSize::Hamlet => 1,
+            SettlementSize::Village => 2,
+            SettlementSize::Town => usize::MAX,
+        }
+    }
+
+    fn notable_locations(self) -> usize {
+        match self {
+            SettlementSize::Hamlet => 1,
+            SettlementSize::Village => 3,
+            SettlementSize::Town => 5,
+        }
+    }
+
+    fn rumours(self) -> usize {
+        match self {
+            SettlementSize::Hamlet => 1,
+            SettlementSize::Village => 2,
+            SettlementSize::Town => 3,
+        }
+    }
+}
+
+/// Peoples of Eriador who are not among the Heroic Cultures
+#[derive(Clone, Copy, Debug, Deserialize, strum::Display, Eq, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum OtherFolk {
+    /// Hillmen of the south-west, long at odds with the Dúnedain
+    Dunlendings,
+    /// Hardy folk of the northern fells, whose forefathers served the Witch-king of Angmar
+    HillMen,
+    /// Traders and wanderers come up the Greenway from Gondor and beyond
+    Southerners,
+}
+
+/// A people living in a settlement
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum Folk {
+    Heroic(HeroicCulture),
+    Other(OtherFolk),
+}
+
+/// How many of a settlement's inhabitants belong to a folk
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FolkShare {
+    folk: Folk,
+    /// Share of the population, in percent
+    share: u32,
+}
+
+/// Percentages of the population for folk of the given weights, rounded so that they add up to
+/// 100 by handing the points lost to rounding down to the largest remainders
+fn shares(folk: &[(Folk, u32)]) -> Vec<FolkShare> {
+    let total = folk.iter().map(|(_, weight)| weight).sum::<u32>().max(1);
+    let mut shares = folk
+        .iter()
+        .map(|(folk, weight)| FolkShare {
+            folk: *folk,
+            share: weight * 100 / total,
+        })
+        .collect::<Vec<_>>();
+
+    let mut by_remainder = (0..folk.len()).collect::<Vec<_>>();
+    by_remainder.sort_by_key(|&index| std::cmp::Reverse(folk[index].1 * 100 % total));
+    let missing = 100 - shares.iter().map(|share| share.share).sum::<u32>();
+    for index in by_remainder
+        .into_iter()
+        .take(usize::try_from(missing).unwrap_or_default())
+    {
+        shares[index].share += 1;
+    }
+    shares
+}
+
+/// Folk living in a region, with how common each one is. The most common folk comes first.
+#[must_use]
+pub fn folk(region: Region) -> &'static [(Folk, u32)] {
+    use Folk::{Heroic, Other};
+
+    match region {
+        Region::TheShire => &[
+            (Heroic(HeroicCulture::HobbitsOfTheShire), 90),
+            (Heroic(HeroicCulture::MenOfBree), 5),
+            (Heroic(HeroicCulture::DwarvesOfDurinsFolk), 5),
+        ],
+        Region::BreeLand => &[
+            (Heroic(HeroicCulture::MenOfBree), 60),
+            (Heroic(HeroicCulture::HobbitsOfTheShire), 30),
+            (Heroic(HeroicCulture::RangersOfTheNorth), 5),
+            (Heroic(HeroicCulture::DwarvesOfDurinsFolk), 5),
+        ],
+        Region::Lindon => &[
+            (Heroic(HeroicCulture::ElvesOfLindon), 80),
+            (Heroic(HeroicCulture::DwarvesOfDurinsFolk), 10),
+            (Heroic(HeroicCulture::HobbitsOfTheShire), 10),
+        ],
+        Region::BlueMountains => &[
+            (Heroic(HeroicCulture::DwarvesOfDurinsFolk), 85),
+            (Heroic(HeroicCulture::HobbitsOfTheShire), 10),
+            (Heroic(HeroicCulture::ElvesOfLindon), 5),
+        ],
+        Region::Eregion | Region::Gwathlo | Region::Minhiriath => &[
+            (Other(OtherFolk::Dunlendings), 50),
+            (Other(OtherFolk::Southerners), 30),
+            (Heroic(HeroicCulture::MenOfBree), 20),
+        ],
+        Region::Bruinen => &[
+            (Heroic(HeroicCulture::ElvesOfLindon), 50),
+            (Heroic(HeroicCulture::RangersOfTheNorth), 30),
+            (Heroic(HeroicCulture::MenOfBree), 10),
+            (Heroic(HeroicCulture::DwarvesOfDurinsFolk), 10),
+        ],
+        Region::Angmar => &[
+            (Other(OtherFolk::HillMen), 80),
+            (Other(OtherFolk::Southerners), 10),
+            (Heroic(HeroicCulture::RangersOfTheNorth), 10),
+        ],
+        Region::Ettenmoors => &[
+            (Other(OtherFolk::HillMen), 60),
+            (Heroic(HeroicCulture::RangersOfTheNorth), 25),
+            (Other(OtherFolk::Southerners), 15),
+        ],
+        Region::Trollshaws => &[
+            (Other(OtherFolk::HillMen), 40),
+            (Heroic(HeroicCulture::MenOfBree), 30),
+            (Heroic(HeroicCulture::RangersOfTheNorth), 20),
+            (Heroic(HeroicCulture::DwarvesOfDurinsFolk), 10),
+        ],
+        Region::BarrowDowns
+        | Region::Evendim
+        | Region::LoneLands
+        | Region::NorthDowns
+        | Region::WeatherHills => &[
+            (Heroic(HeroicCulture::MenOfBree), 50),
+            (Heroic(HeroicCulture::RangersOfTheNorth), 20),
+            (Heroic(HeroicCulture::HobbitsOfTheShire), 15),
+            (Heroic(HeroicCulture::DwarvesOfDurinsFolk), 10),
+            (Other(OtherFolk::Southerners), 5),
+        ],
+    }
+}
+
+/// The inn where travellers find a bed, a meal and the latest news
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Inn {
+    name: String,
+    keeper: Npc,
+}
+
+impl Inn {
+    /// Open an inn kept by one of the given folk
+    fn random<R: Rng + ?Sized>(rng: &mut R, folk: &[(Folk, u32)]) -> Self {
+        let culture = folk
+            .iter()
+            .filter_map(|(folk, weight)| match folk {
+                Folk::Heroic(culture) => Some((*culture, *weight)),
+                Folk::Other(_) => None,
+            })
+            .collect::<Vec<_>>()
+            .choose_weighted(rng, |(_, weight)| *weight)
+            .map_or(HeroicCulture::MenOfBree, |(culture, _)| *culture);
+
+        Self {
+            name: inn_name(rng),
+            keeper: Npc::with_occupation(rng, culture, "innkeeper"),
+        }
+    }
+}
+
+/// Name an inn after the painted sign over its door
+pub fn inn_name<R: Rng + ?Sized>(rng: &mut R) -> String {
+    let noun = INN_NOUNS.choose(rng).unwrap();
+    if rng.random_bool(0.7) {
+        format!("The {} {noun}", INN_ADJECTIVES.choose(rng).unwrap())
+    } else {
+        format!("The {noun} and {}", INN_NOUNS.choose(rng).unwrap())
+    }
+}
+
+/// A place the company can visit
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Settlement {
+    name: String,
+    size: SettlementSize,
+    region: Region,
+    population: u32,
+    /// Folk living there, the most common first
+    folk: Vec<FolkShare>,
+    notable_locations: Vec<&'static str>,
+    inn: Inn,
+    /// What is being talked about in the common room
+    rumours: Vec<RumourRoll>,
+}
+
+impl Settlement {
+    /// Generate a settlement of the given size in a region of Eriador, as found during `year`
+    pub fn random<R: Rng + ?Sized>(
+        rng: &mut R,
+        size: SettlementSize,
+        region: Region,
+        year: u16,
+    ) -> Self {
+        let folk = &folk(region)[..size.folk().min(folk(region).len())];
+
+        Self {
+            name: format!(
+                "{}{}",
+                NAME_PREFIXES.choose(rng).unwrap(),
+                NAME_SUFFIXES.choose(rng).unwrap()
+            ),
+            size,
+            region,
+            population: rng.random_range(size.population()),
+            folk: shares(folk),
+            notable_locations: NOTABLE_LOCATIONS
+                .choose_multiple(rng, size.notable_locations())
+                .copied()
+                .collect(),
+            inn: Inn::random(rng, folk),
+            rumours: RumourRoll::roll_many(rng, region, year, size.rumours()),
+        }
+    }
+}
+
+const NAME_PREFIXES: &[&str] = &[
+    "Ash", "Bram", "Brock", "Comb", "Crick", "Dun", "Fern", "Fox", "Hay", "Ox", "Stad", "Thorn",
+    "Tuck", "Whit", "Wood",
+];
+
+const NAME_SUFFIXES: &[&str] = &[
+    "bury", "by", "dle", "ford", "hall", "ham", "hill", "hollow", "stead", "ton", "well", "wick",
+];
+
+const INN_ADJECTIVES: &[&str] = &[
+    "Blue",
+    "Crooked",
+    "Drowsy",
+    "Floating",
+    "Golden",
+    "Green",
+    "Laughing",
+    "Leaping",
+    "Merry",
+    "Old",
+    "Prancing",
+    "Red",
+    "Silver",
+    "Sleeping",
+    "Wandering",
+];
+
+const INN_NOUNS: &[&str] = &[
+    "Badger", "Barrel", "Boar", "Bush", "Crown", "Dragon", "Goose", "Horn", "Kettle", "Lantern",
+    "Log", "Mole", "Moon", "Oak", "Perch", "Plough", "Pony", "Stag", "Star", "Wheel",
+];
+
+const NOTABLE_LOCATIONS: &[&str] = &[
+    "a market square where pedlars gather on feast days",
+    "a watch-house by the gate",
+    "an old stone bridge built in the days of the kings",
+    "a smithy whose fire is never allowed to go out",
+    "a mill on a fast stream",
+    "the ruins of a watch-tower of Arnor",
+    "a granary guarded by a surly dog",
+    "a ferry across the river",
+    "the house of the village elder",
+    "a bowling green",
+    "stables where ponies can be hired",
+    "a tannery that is best approached upwind",
+];
+
+#[cfg(test)]
+mod test {
+    use strum::IntoEnumIterator;
+
+    use crate::{rand::rng_from_seed, tables::rumours::CAMPAIGN_START};
+
+    use super::*;
+
+    #[test]
+    fn every_region_has_folk() {
+        for region in Region::iter() {
+            assert!(!folk(region).is_empty());
+        }
+    }
+
+    #[test]
+    fn shares_add_up_to_a_hundred() {
+        let mut rng = rng_from_seed(2965);
+        for region in Region::iter() {
+            assert_eq!(
+                folk(region).iter().map(|(_, weight)| weight).sum::<u32>(),
+                100
+            );
+            for size in [
+                SettlementSize::Hamlet,
+                SettlementSize::Village,
+                SettlementSize::Town,
+            ] {
+                let settlement = Settlement::random(&mut rng, size, region, CAMPAIGN_START);
+                assert_eq!(
+                    settlement.folk.iter().map(|share| share.share).sum::<u32>(),
+                    100
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rounds_shares_by_largest_remainder() {
+        use Folk::{Heroic, Other};
+
+        let percentages = |folk: &[(Folk, u32)]| {
+            shares(folk)
+                .iter()
+                .map(|share| share.share)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            percentages(&[
+                (Heroic(HeroicCulture::MenOfBree), 2),
+                (Heroic(HeroicCulture::RangersOfTheNorth), 2),
+                (Other(OtherFolk::HillMen), 2),
+            ]),
+            [34, 33, 33]
+        );
+        // 70.6% and 29.4%: the larger remainder gets the lost point
+        assert_eq!(
+            percentages(&[
+                (Other(OtherFolk::HillMen), 60),
+                (Heroic(HeroicCulture::RangersOfTheNorth), 25),
+            ]),
+            [71, 29]
+        );
+    }
+
+    #[test]
+    fn elves_dwell_in_the_vale_of_bruinen() {
+        assert_eq!(
+            folk(Region::Bruinen)[0].0,
+            Folk::Heroic(HeroicCulture::ElvesOfLindon)
+        );
+        assert_eq!(folk(Region::Angmar)[0].0, Folk::Other(OtherFolk::HillMen));
+    }
+
+    #[test]
+    fn hamlets_have_a_single_folk() {
+        let settlement = Settlement::random(
+            &mut rng_from_seed(2965),
+            SettlementSize::Hamlet,
+            Region::TheShire,
+            CAMPAIGN_START,
+        );
+
+        assert_eq!(settlement.folk.len(), 1);
+        assert_eq!(settlement.folk[0].share, 100);
+        assert!(
+            SettlementSize::Hamlet
+                .population()
+                .contains(&settlement.population)
+        );
+    }
+
+    #[test]
+    fn towns_have_every_folk_of_the_region() {
+        let settlement = Settlement::random(
+            &mut rng_from_seed(1),
+            SettlementSize::Town,
+            Region::BreeLand,
+            CAMPAIGN_START,
+        );
+
+        assert_eq!(settlement.folk.len(), folk(Region::BreeLand).len());
+        assert_eq!(settlement.notable_locations.len(), 5);
+        assert_eq!(settlement.rumours.len(), 3);
+    }
+
+    #[test]
+    fn innkeeper_belongs_to_a_heroic_culture() {
+        let mut rng = rng_from_seed(7);
+        for _ in 0..20 {
+            let settlement =
+                Settlement::random(&mut rng, SettlementSize::Town, Region::Gwathlo, 3018);
+            let keeper = serde_json::to_value(&settlement.inn.keeper).unwrap();
+
+            assert_eq!(keeper["occupation"], "innkeeper");
+            assert_eq!(keeper["culture"], "men-of-bree");
+        }
+    }
+
+    #[test]
+    fn inns_are_named_after_their_sign() {
+        let mut rng = rng_from_seed(3);
+        for _ in 0..20 {
+            let name = inn_name(&mut rng);
+
+            assert!(name.starts_with("The "));
+            assert_eq!(
+                name.split(' ').count(),
+                if name.contains(" and ") { 4 } else { 3 }
+            );
+        }
+    }
+}
@@ -0,0 +1,314 @@
+/*!
+# Solo play
+
+Tools for Strider Mode, where a single Player-hero journeys alone and the Loremaster's voice is
+replaced by oracles. The Telling Table answers yes-or-no questions with a roll of the Feat die,
+and the Lore tables give words to interpret when the answer needs more than a yes or a no.
+
+A lone hero fills every journey role, and has no companions to share a Fellowship pool with.
+*/
+
+use anyhow::bail;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use strum::EnumIter;
+use utoipa::ToSchema;
+
+use crate::{
+    calendar::Date,
+    characters::Character,
+    companies::{
+        Company,
+        fellowship::{ChosenUndertaking, FellowshipPhase, Undertaking, UndertakingKind},
+    },
+    dice::FeatDie,
+    journeys::{Journey, Role, Roles, RouteSegment, Season, default_target_number},
+    patrons::Patron,
+    sanctuaries::Sanctuary,
+};
+
+pub mod lore;
+
+/// How likely the answer to a question is to be yes
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Deserialize,
+    strum::Display,
+    EnumIter,
+    Eq,
+    PartialEq,
+    Serialize,
+    ToSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "title_case")]
+pub enum Likelihood {
+    Certain,
+    Likely,
+    #[default]
+    Middling,
+    Doubtful,
+    Unthinkable,
+}
+
+impl Likelihood {
+    /// Lowest Feat die number answering yes
+    #[must_use]
+    pub fn target_number(self) -> u8 {
+        match self {
+            Likelihood::Certain => 2,
+            Likelihood::Likely => 4,
+            Likelihood::Middling => 6,
+            Likelihood::Doubtful => 8,
+            Likelihood::Unthinkable => 10,
+        }
+    }
+}
+
+/// Answer of the Telling Table
+#[derive(Clone, Copy, Debug, Deserialize, strum::Display, Eq, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum Answer {
+    /// The Eye of Sauron: no, and things take a turn for the worse
+    #[strum(serialize = "No, and...")]
+    NoAnd,
+    #[strum(serialize = "No")]
+    No,
+    #[strum(serialize = "Yes")]
+    Yes,
+    /// The Gandalf rune: yes, and something unexpectedly good comes of it
+    #[strum(serialize = "Yes, and...")]
+    YesAnd,
+}
+
+impl Answer {
+    /// Answer given by a result of the Feat die for a question of the given likelihood
+    #[must_use]
+    pub fn from_feat_die(feat_die: FeatDie, likelihood: Likelihood) -> Self {
+        match feat_die {
+            FeatDie::Eye => Answer::NoAnd,
+            FeatDie::Gandalf => Answer::YesAnd,
+            FeatDie::Number(number) if number >= likelihood.target_number() => Answer::Yes,
+            FeatDie::Number(_) => Answer::No,
+        }
+    }
+}
+
+/// A question put to the Telling Table, and its answer
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TellingRoll {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    question: Option<String>,
+    likelihood: Likelihood,
+    /// Lowest Feat die number answering yes
+    target_number: u8,
+    feat_die: FeatDie,
+    answer: Answer,
+}
+
+impl TellingRoll {
+    /// Roll the Feat die to answer a question
+    pub fn roll<R: Rng + ?Sized>(
+        rng: &mut R,
+        question: Option<String>,
+        likelihood: Likelihood,
+    ) -> Self {
+        let feat_die = rng.random();
+        Self {
+            question,
+            likelihood,
+            target_number: likelihood.target_number(),
+            feat_die,
+            answer: Answer::from_feat_die(feat_die, likelihood),
+        }
+    }
+}
+
+/// A lone hero's skill ratings for the journey roles they fill
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, ToSchema)]
+pub struct JourneySkills {
+    #[serde(default)]
+    travel: u8,
+    #[serde(default)]
+    hunting: u8,
+    #[serde(default)]
+    awareness: u8,
+    #[serde(default)]
+    explore: u8,
+}
+
+impl JourneySkills {
+    /// Rating in the skill rolled for a role
+    #[must_use]
+    pub fn rating(self, role: Role) -> u8 {
+        match role {
+            Role::Guide => self.travel,
+            Role::Hunter => self.hunting,
+            Role::LookOut => self.awareness,
+            Role::Scout => self.explore,
+        }
+    }
+}
+
+/// A journey made by a lone hero, who fills every role
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct SoloJourney {
+    route: Vec<RouteSegment>,
+    #[serde(default)]
+    season: Season,
+    /// Date the hero sets out, which decides the season when given
+    start: Option<Date>,
+    hero: String,
+    #[serde(default)]
+    skills: JourneySkills,
+    /// Target Number for the hero's rolls
+    #[serde(default = "default_target_number")]
+    target_number: u32,
+}
+
+impl From<SoloJourney> for Journey {
+    fn from(journey: SoloJourney) -> Self {
+        let skills = journey.skills;
+        Journey::new(
+            journey.route,
+            journey.season,
+            journey.start,
+            Roles::alone(
+                &journey.hero,
+                |role| skills.rating(role),
+                journey.target_number,
+            ),
+        )
+    }
+}
+
+/// Everything needed to resolve a lone hero's Fellowship phase
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct SoloFellowshipPhase {
+    hero: Character,
+    patron: Option<Patron>,
+    safe_haven: Option<Sanctuary>,
+    /// The hero's current date in the campaign
+    date: Option<Date>,
+    #[serde(default)]
+    yule: bool,
+    undertaking: Option<Undertaking>,
+}
+
+impl TryFrom<SoloFellowshipPhase> for FellowshipPhase {
+    type Error = anyhow::Error;
+
+    /// Set up the phase for a company of one
+    ///
+    /// # Errors
+    ///
+    /// If the hero chose to strengthen a fellowship they do not have.
+    fn try_from(phase: SoloFellowshipPhase) -> anyhow::Result<Self> {
+        if let Some(undertaking) = &phase.undertaking
+            && UndertakingKind::from(undertaking) == UndertakingKind::StrengthenFellowship
+        {
+            bail!("A lone hero has no Fellowship pool to strengthen");
+        }
+
+        let undertakings = phase
+            .undertaking
+            .map(|undertaking| ChosenUndertaking::new(phase.hero.name(), undertaking))
+            .into_iter()
+            .collect();
+        Ok(FellowshipPhase::new(
+            Company::alone(phase.hero, phase.patron, phase.safe_haven, phase.date),
+            phase.yule,
+            undertakings,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use strum::IntoEnumIterator;
+
+    use crate::{callings::Calling, cultures::HeroicCulture, rand::rng_from_seed};
+
+    use super::*;
+
+    #[test]
+    fn runes_always_answer() {
+        for likelihood in Likelihood::iter() {
+            assert_eq!(
+                Answer::from_feat_die(FeatDie::Gandalf, likelihood),
+                Answer::YesAnd
+            );
+            assert_eq!(
+                Answer::from_feat_die(FeatDie::Eye, likelihood),
+                Answer::NoAnd
+            );
+        }
+    }
+
+    #[test]
+    fn likelier_questions_answer_yes_more_often() {
+        let yes = |likelihood| {
+            (1..=10)
+                .filter(|&number| {
+                    Answer::from_feat_die(FeatDie::Number(number), likelihood) == Answer::Yes
+                })
+                .count()
+        };
+
+        let counts = Likelihood::iter().map(yes).collect::<Vec<_>>();
+        assert!(counts.is_sorted_by(|a, b| a > b));
+        assert_eq!(yes(Likelihood::Middling), 5);
+    }
+
+    #[test]
+    fn telling_roll_answers_from_its_feat_die() {
+        let mut rng = rng_from_seed(2965);
+        for _ in 0..50 {
+            let roll = TellingRoll::roll(&mut rng, None, Likelihood::Likely);
+            assert_eq!(
+                roll.answer,
+                Answer::from_feat_die(roll.feat_die, Likelihood::Likely)
+            );
+        }
+    }
+
+    #[test]
+    fn lone_hero_fills_every_role() {
+        let journey = serde_json::from_str::<SoloJourney>(
+            r#"{
+                "route": [{"region": "wild-land", "hexes": 4}],
+                "hero": "Aragorn",
+                "skills": {"travel": 3, "explore": 2}
+            }"#,
+        )
+        .unwrap();
+        let journey = Journey::from(journey);
+        let log = serde_json::to_value(journey.resolve(&mut rng_from_seed(1))).unwrap();
+
+        assert_eq!(
+            log["fatigue"]
+                .as_object()
+                .unwrap()
+                .keys()
+                .collect::<Vec<_>>(),
+            ["Aragorn"]
+        );
+    }
+
+    #[test]
+    fn lone_hero_cannot_strengthen_fellowship() {
+        let phase = SoloFellowshipPhase {
+            hero: Character::new(HeroicCulture::RangersOfTheNorth, Calling::Warden, "Ivorwen"),
+            patron: None,
+            safe_haven: Some(Sanctuary::Rivendell),
+            date: None,
+            yule: false,
+            undertaking: Some(Undertaking::StrengthenFellowship),
+        };
+
+        assert!(FellowshipPhase::try_from(phase).is_err());
+    }
+}
@@ -0,0 +1,224 @@
+/*!
+# Lore tables
+
+Word prompts for questions the Telling Table cannot answer with a yes or a no. An action, an
+aspect and a focus are drawn together and read as a short phrase, such as "Betray ancient
+alliance", for the player to interpret in the light of the story so far.
+
+Each table has a row for every face of the Feat die, from the Eye to the Gandalf rune, and an
+entry in the row for every result of the Success die.
+*/
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use strum::EnumIter;
+use utoipa::ToSchema;
+
+use crate::dice::{FeatDie, SuccessDie};
+
+/// Entries of a Lore table, by Feat die row and Success die column
+type LoreTable = [[&'static str; 6]; 12];
+
+/// Words prompting what is being done
+const ACTIONS: LoreTable = [
+    ["Abandon", "Attack", "Betray", "Bind", "Block", "Break"],
+    ["Build", "Call", "Capture", "Carry", "Chase", "Command"],
+    ["Conceal", "Corrupt", "Create", "Deceive", "Defend", "Delay"],
+    ["Destroy", "Discover", "Endure", "Escape", "Explore", "Fail"],
+    ["Find", "Follow", "Forget", "Gather", "Guard", "Guide"],
+    ["Heal", "Hide", "Hunt", "Imprison", "Inspire", "Journey"],
+    ["Keep", "Lead", "Learn", "Lose", "Mourn", "Oppose"],
+    [
+        "Possess", "Protect", "Pursue", "Question", "Rebuild", "Refuse",
+    ],
+    ["Remember", "Rescue", "Reveal", "Ruin", "Seek", "Share"],
+    [
+        "Steal",
+        "Strengthen",
+        "Summon",
+        "Surrender",
+        "Take",
+        "Tempt",
+    ],
+    ["Threaten", "Trade", "Trust", "Uncover", "Unite", "Wait"],
+    ["Wander", "Warn", "Watch", "Weaken", "Welcome", "Wound"],
+];
+
+/// Words prompting what something is like
+const ASPECTS: LoreTable = [
+    ["Ancient", "Bitter", "Black", "Bold", "Broken", "Burning"],
+    ["Cold", "Corrupted", "Cruel", "Cursed", "Dark", "Dead"],
+    ["Deep", "Desolate", "Distant", "Dreadful", "Dying", "Elven"],
+    ["Empty", "Enchanted", "Evil", "Faded", "Fair", "Fallen"],
+    ["False", "Fell", "Forgotten", "Forsaken", "Free", "Friendly"],
+    ["Gentle", "Glorious", "Golden", "Grey", "Grim", "Hidden"],
+    ["Hopeful", "Hungry", "Lonely", "Lost", "Loyal", "Mighty"],
+    ["Mysterious", "Noble", "Old", "Proud", "Quiet", "Ruined"],
+    ["Sad", "Secret", "Shadowy", "Silent", "Simple", "Strange"],
+    [
+        "Strong",
+        "Swift",
+        "Terrible",
+        "Treacherous",
+        "Twisted",
+        "Unexpected",
+    ],
+    ["Unseen", "Valiant", "Vast", "Warm", "Watchful", "Weary"],
+    ["Wild", "Wise", "Withered", "Wondrous", "Wounded", "Young"],
+];
+
+/// Words prompting who or what is concerned
+const FOCUSES: LoreTable = [
+    ["Alliance", "Ally", "Ancestor", "Battle", "Beast", "Blood"],
+    ["Border", "Bridge", "Burden", "Captive", "Cave", "Child"],
+    ["Crown", "Curse", "Danger", "Darkness", "Death", "Debt"],
+    ["Door", "Dream", "Dwarf", "Enemy", "Exile", "Fire"],
+    ["Foe", "Forest", "Fortress", "Friend", "Gift", "Grave"],
+    ["Guardian", "Heir", "Home", "Hope", "Hunger", "Journey"],
+    ["King", "Knowledge", "Land", "Language", "Legacy", "Light"],
+    ["Map", "Memory", "Message", "Mountain", "Night", "Oath"],
+    ["Orc", "Path", "Power", "Prisoner", "Prophecy", "Refuge"],
+    ["Relic", "River", "Road", "Ruin", "Secret", "Servant"],
+    ["Shadow", "Song", "Spirit", "Stone", "Storm", "Stranger"],
+    ["Sword", "Tomb", "Tower", "Treasure", "Wall", "Winter"],
+];
+
+/// One of the Lore tables
+#[derive(
+    Clone, Copy, Debug, Deserialize, strum::Display, EnumIter, Eq, PartialEq, Serialize, ToSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum LoreColumn {
+    Action,
+    Aspect,
+    Focus,
+}
+
+impl LoreColumn {
+    fn table(self) -> &'static LoreTable {
+        match self {
+            LoreColumn::Action => &ACTIONS,
+            LoreColumn::Aspect => &ASPECTS,
+            LoreColumn::Focus => &FOCUSES,
+        }
+    }
+
+    /// Word found at the row of the Feat die and the column of the Success die
+    #[must_use]
+    pub fn entry(self, feat_die: FeatDie, success_die: SuccessDie) -> &'static str {
+        let row = match feat_die {
+            FeatDie::Eye => 0,
+            FeatDie::Number(number) => usize::from(number.clamp(1, 10)),
+            FeatDie::Gandalf => 11,
+        };
+        let column = usize::from(success_die.value(false).clamp(1, 6) - 1);
+        self.table()[row][column]
+    }
+}
+
+/// A word drawn from one of the Lore tables, with the dice that picked it
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LoreWord {
+    feat_die: FeatDie,
+    success_die: SuccessDie,
+    word: &'static str,
+}
+
+impl LoreWord {
+    /// Roll the Feat die and a Success die on a Lore table
+    pub fn roll<R: Rng + ?Sized>(rng: &mut R, column: LoreColumn) -> Self {
+        let feat_die = rng.random();
+        let success_die = rng.random();
+        Self {
+            feat_die,
+            success_die,
+            word: column.entry(feat_die, success_die),
+        }
+    }
+}
+
+/// One word drawn from each Lore table, or from a single one
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LoreRoll {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    action: Option<LoreWord>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    aspect: Option<LoreWord>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    focus: Option<LoreWord>,
+}
+
+impl LoreRoll {
+    /// Roll on the given table only, or on all three
+    pub fn roll<R: Rng + ?Sized>(rng: &mut R, column: Option<LoreColumn>) -> Self {
+        let mut roll = |wanted| {
+            column
+                .is_none_or(|column| column == wanted)
+                .then(|| LoreWord::roll(rng, wanted))
+        };
+        Self {
+            action: roll(LoreColumn::Action),
+            aspect: roll(LoreColumn::Aspect),
+            focus: roll(LoreColumn::Focus),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use strum::IntoEnumIterator;
+
+    use crate::rand::rng_from_seed;
+
+    use super::*;
+
+    fn feat_dice() -> impl Iterator<Item = FeatDie> {
+        [FeatDie::Eye]
+            .into_iter()
+            .chain((1..=10).map(FeatDie::Number))
+            .chain([FeatDie::Gandalf])
+    }
+
+    fn success_dice() -> impl Iterator<Item = SuccessDie> {
+        (1..=6u8).map(|face| serde_json::from_value(face.into()).unwrap())
+    }
+
+    #[test]
+    fn every_die_combination_maps_to_an_entry() {
+        for column in LoreColumn::iter() {
+            let mut words = HashSet::new();
+            for feat_die in feat_dice() {
+                for success_die in success_dice() {
+                    let word = column.entry(feat_die, success_die);
+                    assert!(!word.is_empty());
+                    words.insert(word);
+                }
+            }
+
+            // Every combination gives a different word
+            assert_eq!(words.len(), 72);
+        }
+    }
+
+    #[test]
+    fn words_match_their_dice() {
+        let mut rng = rng_from_seed(2965);
+        for column in LoreColumn::iter() {
+            let word = LoreWord::roll(&mut rng, column);
+            assert_eq!(word.word, column.entry(word.feat_die, word.success_die));
+        }
+    }
+
+    #[test]
+    fn rolls_on_a_single_column() {
+        let mut rng = rng_from_seed(1);
+        let roll = LoreRoll::roll(&mut rng, Some(LoreColumn::Aspect));
+        assert!(roll.action.is_none() && roll.aspect.is_some() && roll.focus.is_none());
+
+        let roll = LoreRoll::roll(&mut rng, None);
+        assert!(roll.action.is_some() && roll.aspect.is_some() && roll.focus.is_some());
+    }
+}
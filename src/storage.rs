@@ -0,0 +1,310 @@
+/*!
+# Storage
+
+Persists generated content between requests so a Loremaster can come back to it later.
+*/
+
+use rand::Rng;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use utoipa::ToSchema;
+
+use crate::rand::rng_from_os_rng;
+
+/// Minimal key-value interface the application relies on.
+///
+/// Implemented by the Spin key-value store when deployed, and by an in-memory store in tests.
+pub trait KeyValueStore {
+    /// Value stored at `key`, if any
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store could not be read.
+    fn get(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>>;
+
+    /// Store `value` at `key`, replacing any previous value
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store could not be written to.
+    fn set(&self, key: &str, value: &[u8]) -> anyhow::Result<()>;
+
+    /// Remove `key` from the store. Removing a missing key is not an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store could not be written to.
+    fn delete(&self, key: &str) -> anyhow::Result<()>;
+
+    /// All keys currently in the store
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store could not be read.
+    fn keys(&self) -> anyhow::Result<Vec<String>>;
+}
+
+/// The default Spin key-value store for this component.
+///
+/// The store is opened on each access, so nothing is touched until a route actually needs it.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SpinStore;
+
+impl SpinStore {
+    fn open() -> anyhow::Result<spin_sdk::key_value::Store> {
+        Ok(spin_sdk::key_value::Store::open_default()?)
+    }
+}
+
+impl KeyValueStore for SpinStore {
+    fn get(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        Ok(Self::open()?.get(key)?)
+    }
+
+    fn set(&self, key: &str, value: &[u8]) -> anyhow::Result<()> {
+        Ok(Self::open()?.set(key, value)?)
+    }
+
+    fn delete(&self, key: &str) -> anyhow::Result<()> {
+        Ok(Self::open()?.delete(key)?)
+    }
+
+    fn keys(&self) -> anyhow::Result<Vec<String>> {
+        Ok(Self::open()?.get_keys()?)
+    }
+}
+
+/// A value saved in the store, along with the id it can be retrieved by.
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct Stored<T> {
+    /// Identifier used to retrieve, update or delete the value
+    pub id: String,
+    /// The stored value
+    #[serde(flatten)]
+    pub value: T,
+}
+
+/// A group of JSON documents of the same kind, kept under a shared key prefix.
+#[derive(Debug)]
+pub struct Collection<'a, S: ?Sized> {
+    store: &'a S,
+    prefix: &'static str,
+}
+
+impl<'a, S: KeyValueStore + ?Sized> Collection<'a, S> {
+    /// Documents stored in `store` with keys of the form `{prefix}/{id}`
+    pub fn new(store: &'a S, prefix: &'static str) -> Self {
+        Self { store, prefix }
+    }
+
+    fn key(&self, id: &str) -> String {
+        format!("{}/{id}", self.prefix)
+    }
+
+    /// Document with the given id, if it exists
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store could not be read or the document could not be deserialized.
+    pub fn get<T: DeserializeOwned>(&self, id: &str) -> anyhow::Result<Option<T>> {
+        self.store
+            .get(&self.key(id))?
+            .map(|value| serde_json::from_slice(&value))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    /// Save a new document under a freshly generated id
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store could not be written to.
+    pub fn insert<T: Serialize>(&self, value: T) -> anyhow::Result<Stored<T>> {
+        let id = format!("{:016x}", rng_from_os_rng().random::<u64>());
+        self.store
+            .set(&self.key(&id), &serde_json::to_vec(&value)?)?;
+        Ok(Stored { id, value })
+    }
+
+    /// Save a document under the given id, replacing any previous one
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store could not be written to.
+    pub fn set<T: Serialize>(&self, id: &str, value: T) -> anyhow::Result<Stored<T>> {
+        self.store
+            .set(&self.key(id), &serde_json::to_vec(&value)?)?;
+        Ok(Stored {
+            id: id.to_owned(),
+            value,
+        })
+    }
+
+    /// Replace an existing document. Returns `None` if there was nothing to replace.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store could not be read or written to.
+    pub fn replace<T: Serialize>(&self, id: &str, value: T) -> anyhow::Result<Option<Stored<T>>> {
+        let key = self.key(id);
+        if self.store.get(&key)?.is_none() {
+            return Ok(None);
+        }
+        self.store.set(&key, &serde_json::to_vec(&value)?)?;
+        Ok(Some(Stored {
+            id: id.to_owned(),
+            value,
+        }))
+    }
+
+    /// Remove a document. Returns whether it existed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store could not be read or written to.
+    pub fn remove(&self, id: &str) -> anyhow::Result<bool> {
+        let key = self.key(id);
+        if self.store.get(&key)?.is_none() {
+            return Ok(false);
+        }
+        self.store.delete(&key)?;
+        Ok(true)
+    }
+
+    /// All documents in the collection, ordered by id
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store could not be read or a document could not be deserialized.
+    pub fn list<T: DeserializeOwned>(&self) -> anyhow::Result<Vec<Stored<T>>> {
+        let prefix = format!("{}/", self.prefix);
+        let mut ids = self
+            .store
+            .keys()?
+            .into_iter()
+            .filter_map(|key| key.strip_prefix(&prefix).map(ToOwned::to_owned))
+            .collect::<Vec<_>>();
+        ids.sort();
+
+        ids.into_iter()
+            .filter_map(|id| {
+                self.get(&id)
+                    .map(|value| value.map(|value| Stored { id, value }))
+                    .transpose()
+            })
+            .collect()
+    }
+}
+
+/// In-memory store for exercising storage-backed code outside of Spin.
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub struct MemoryStore(std::cell::RefCell<std::collections::BTreeMap<String, Vec<u8>>>);
+
+#[cfg(test)]
+impl KeyValueStore for MemoryStore {
+    fn get(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        Ok(self.0.borrow().get(key).cloned())
+    }
+
+    fn set(&self, key: &str, value: &[u8]) -> anyhow::Result<()> {
+        self.0.borrow_mut().insert(key.to_owned(), value.to_vec());
+        Ok(())
+    }
+
+    fn delete(&self, key: &str) -> anyhow::Result<()> {
+        self.0.borrow_mut().remove(key);
+        Ok(())
+    }
+
+    fn keys(&self) -> anyhow::Result<Vec<String>> {
+        Ok(self.0.borrow().keys().cloned().collect())
+    }
+}
+
+#[cfg(test)]
+impl<S: KeyValueStore + ?Sized> KeyValueStore for std::rc::Rc<S> {
+    fn get(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        self.as_ref().get(key)
+    }
+
+    fn set(&self, key: &str, value: &[u8]) -> anyhow::Result<()> {
+        self.as_ref().set(key, value)
+    }
+
+    fn delete(&self, key: &str) -> anyhow::Result<()> {
+        self.as_ref().delete(key)
+    }
+
+    fn keys(&self) -> anyhow::Result<Vec<String>> {
+        self.as_ref().keys()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn inserted_documents_can_be_retrieved() {
+        let store = MemoryStore::default();
+        let collection = Collection::new(&store, "things");
+        let stored = collection.insert("value").unwrap();
+
+        assert_eq!(
+            Some("value".to_owned()),
+            collection.get::<String>(&stored.id).unwrap()
+        );
+    }
+
+    #[test]
+    fn replace_only_updates_existing_documents() {
+        let store = MemoryStore::default();
+        let collection = Collection::new(&store, "things");
+        let stored = collection.insert(1).unwrap();
+
+        assert!(collection.replace(&stored.id, 2).unwrap().is_some());
+        assert_eq!(Some(2), collection.get::<i32>(&stored.id).unwrap());
+        assert!(collection.replace("missing", 3).unwrap().is_none());
+        assert_eq!(None, collection.get::<i32>("missing").unwrap());
+    }
+
+    #[test]
+    fn set_saves_under_the_given_id() {
+        let store = MemoryStore::default();
+        let collection = Collection::new(&store, "things");
+
+        collection.set("first", 1).unwrap();
+        collection.set("first", 2).unwrap();
+        assert_eq!(Some(2), collection.get::<i32>("first").unwrap());
+    }
+
+    #[test]
+    fn remove_reports_whether_document_existed() {
+        let store = MemoryStore::default();
+        let collection = Collection::new(&store, "things");
+        let stored = collection.insert(1).unwrap();
+
+        assert!(collection.remove(&stored.id).unwrap());
+        assert!(!collection.remove(&stored.id).unwrap());
+    }
+
+    #[test]
+    fn list_only_includes_own_prefix() {
+        let store = MemoryStore::default();
+        let things = Collection::new(&store, "things");
+        let others = Collection::new(&store, "others");
+        things.insert(1).unwrap();
+        things.insert(2).unwrap();
+        others.insert(3).unwrap();
+
+        let mut values = things
+            .list::<i32>()
+            .unwrap()
+            .into_iter()
+            .map(|stored| stored.value)
+            .collect::<Vec<_>>();
+        values.sort_unstable();
+
+        assert_eq!(vec![1, 2], values);
+    }
+}
@@ -0,0 +1,590 @@
+/*!
+# Tables
+
+Random tables the Loremaster rolls on during play.
+
+Most tables are plain data: a list of entries, picked by weight or by rolling dice against the
+range each entry covers. An entry can embed rolls on other tables with `{table:<id>}`, and
+names generated for a Heroic Culture with `{name:<culture>}`.
+*/
+
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use anyhow::{Context, anyhow, bail};
+use rand::{Rng, seq::IndexedRandom};
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use utoipa::ToSchema;
+
+use crate::{cultures::HeroicCulture, dice::notation::Expression};
+
+pub mod custom;
+pub mod journey_events;
+pub mod revelation_episodes;
+pub mod rumours;
+
+/// How deep rolls on tables can nest, to stop tables referencing each other forever
+const MAX_DEPTH: usize = 10;
+
+/// How many references a single roll can follow, to stop tables fanning out into each other
+const MAX_REFERENCES: usize = 1000;
+
+/// Longest text a single roll can expand to
+const MAX_TEXT_LENGTH: usize = 10_000;
+
+/// A random table
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct Table {
+    /// Kebab-case id other tables use to reference this one
+    #[serde(default)]
+    id: String,
+    name: String,
+    /// Dice rolled to pick an entry by range. Entries are picked by weight when left out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>, example = "2d6")]
+    dice: Option<Expression>,
+    entries: Vec<Entry>,
+}
+
+impl Table {
+    /// A table where every entry is as likely as the others
+    #[must_use]
+    pub fn list(id: &str, name: &str, entries: &[&str]) -> Self {
+        Self {
+            id: id.to_owned(),
+            name: name.to_owned(),
+            dice: None,
+            entries: entries
+                .iter()
+                .map(|text| Entry::weighted(text, 1))
+                .collect(),
+        }
+    }
+
+    /// A table where entries are picked according to their weights
+    #[must_use]
+    pub fn weighted(id: &str, name: &str, entries: &[(&str, u32)]) -> Self {
+        Self {
+            id: id.to_owned(),
+            name: name.to_owned(),
+            dice: None,
+            entries: entries
+                .iter()
+                .map(|(text, weight)| Entry::weighted(text, *weight))
+                .collect(),
+        }
+    }
+
+    /// Pick an entry, along with the dice total for tables rolled with dice
+    fn pick<R: Rng + ?Sized>(&self, rng: &mut R) -> anyhow::Result<(Option<i64>, &Entry)> {
+        if let Some(dice) = &self.dice {
+            let total = dice.roll(rng).total();
+            let entry = self
+                .entries
+                .iter()
+                .find(|entry| entry.range.is_some_and(|range| range.contains(total)))
+                .with_context(|| format!("No entry of table {} for a roll of {total}", self.id))?;
+            Ok((Some(total), entry))
+        } else {
+            let entry = self
+                .entries
+                .choose_weighted(rng, |entry| entry.weight)
+                .map_err(|_| anyhow!("Table {} has no entry to pick", self.id))?;
+            Ok((None, entry))
+        }
+    }
+}
+
+/// An entry of a random table
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct Entry {
+    /// Dice totals picking this entry, like `3-5` or `7`, on tables rolled with dice
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>, example = "3-5")]
+    range: Option<EntryRange>,
+    /// How likely the entry is compared to the others, on tables without dice
+    #[serde(default = "default_weight")]
+    weight: u32,
+    /// Text of the entry, which can embed `{table:<id>}` and `{name:<culture>}`
+    text: String,
+}
+
+impl Entry {
+    fn weighted(text: &str, weight: u32) -> Self {
+        Self {
+            range: None,
+            weight,
+            text: text.to_owned(),
+        }
+    }
+
+    /// Ids of the tables the entry rolls on, once for every reference
+    fn references(&self) -> Vec<&str> {
+        let mut ids = vec![];
+        // Malformed references are reported when the table is validated
+        expand(&self.text, |reference| {
+            if let Reference::Table(id) = reference {
+                ids.push(id);
+            }
+            Ok(String::new())
+        })
+        .ok();
+        ids
+    }
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+/// Dice totals an entry covers, from `low` to `high` inclusive
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct EntryRange {
+    low: u32,
+    high: u32,
+}
+
+impl EntryRange {
+    fn contains(self, total: i64) -> bool {
+        (i64::from(self.low)..=i64::from(self.high)).contains(&total)
+    }
+}
+
+impl fmt::Display for EntryRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.low == self.high {
+            write!(f, "{}", self.low)
+        } else {
+            write!(f, "{}-{}", self.low, self.high)
+        }
+    }
+}
+
+impl FromStr for EntryRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Expected a range like 3-5 or 7, found {s:?}");
+        let (low, high) = s.split_once('-').unwrap_or((s, s));
+        let low = low.trim().parse().map_err(|_| invalid())?;
+        let high = high.trim().parse().map_err(|_| invalid())?;
+        if low > high {
+            return Err(invalid());
+        }
+        Ok(Self { low, high })
+    }
+}
+
+impl TryFrom<String> for EntryRange {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<EntryRange> for String {
+    fn from(range: EntryRange) -> Self {
+        range.to_string()
+    }
+}
+
+/// Something embedded in the text of an entry, to be replaced when rolling
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Reference<'a> {
+    /// `{table:<id>}`: roll on another table
+    Table(&'a str),
+    /// `{name:<culture>}`: generate a name for a Heroic Culture
+    Name(HeroicCulture),
+}
+
+/// Replace every reference embedded in `text` with what `resolve` returns for it
+pub fn expand<'a>(
+    text: &'a str,
+    mut resolve: impl FnMut(Reference<'a>) -> anyhow::Result<String>,
+) -> anyhow::Result<String> {
+    let mut expanded = String::new();
+    let mut rest = text;
+    while let Some((before, after)) = rest.split_once('{') {
+        let (reference, after) = after
+            .split_once('}')
+            .with_context(|| format!("Unclosed reference in {text:?}"))?;
+        let reference = match reference.split_once(':') {
+            Some(("table", id)) => Reference::Table(id),
+            Some(("name", culture)) => Reference::Name(
+                culture
+                    .parse()
+                    .map_err(|_| anyhow!("Unknown culture {culture:?} in {text:?}"))?,
+            ),
+            _ => bail!("Unknown reference {{{reference}}} in {text:?}"),
+        };
+        expanded.push_str(before);
+        expanded.push_str(&resolve(reference)?);
+        rest = after;
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+/// Result of rolling on a table
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TableRoll {
+    table: String,
+    /// Total of the dice, for tables rolled with dice
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total: Option<i64>,
+    /// Text of the entry, with every reference rolled
+    text: String,
+}
+
+impl TableRoll {
+    #[must_use]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+/// Summary of a table, as listed with the other tables
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TableSummary {
+    id: String,
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
+    dice: Option<Expression>,
+    entries: usize,
+}
+
+/// Tables that can be rolled on and reference each other, by id
+#[derive(Debug, Default)]
+pub struct Tables(BTreeMap<String, Table>);
+
+impl Tables {
+    /// Every table that comes with the application
+    #[must_use]
+    pub fn builtin() -> Self {
+        HeroicCulture::iter()
+            .flat_map(HeroicCulture::name_tables)
+            .collect()
+    }
+
+    #[must_use]
+    pub fn get(&self, id: &str) -> Option<&Table> {
+        self.0.get(id)
+    }
+
+    /// Summaries of the tables, sorted by id
+    pub fn summaries(&self) -> impl Iterator<Item = TableSummary> {
+        self.0.values().map(|table| TableSummary {
+            id: table.id.clone(),
+            name: table.name.clone(),
+            dice: table.dice.clone(),
+            entries: table.entries.len(),
+        })
+    }
+
+    /// Roll on the table with the given id, following references to other tables
+    pub fn roll<R: Rng + ?Sized>(&self, rng: &mut R, id: &str) -> anyhow::Result<TableRoll> {
+        self.roll_nested(rng, id, 0, &mut 0)
+    }
+
+    /// Roll on a table nested `depth` deep, counting the references followed so far
+    fn roll_nested<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        id: &str,
+        depth: usize,
+        references: &mut usize,
+    ) -> anyhow::Result<TableRoll> {
+        if depth > MAX_DEPTH {
+            bail!("Tables nest more than {MAX_DEPTH} deep, check {id} for a loop");
+        }
+        let table = self
+            .get(id)
+            .with_context(|| format!("Unknown table {id}"))?;
+        let (total, entry) = table.pick(rng)?;
+        let text = expand(&entry.text, |reference| match reference {
+            Reference::Table(id) => {
+                *references += 1;
+                if *references > MAX_REFERENCES {
+                    bail!("Rolling on {id} follows more than {MAX_REFERENCES} references");
+                }
+                Ok(self.roll_nested(rng, id, depth + 1, references)?.text)
+            }
+            Reference::Name(culture) => Ok(culture.random_name(rng)),
+        })?;
+        if text.len() > MAX_TEXT_LENGTH {
+            bail!("Rolling on {id} gives more than {MAX_TEXT_LENGTH} characters of text");
+        }
+
+        Ok(TableRoll {
+            table: table.id.clone(),
+            total,
+            text,
+        })
+    }
+
+    /// Check that no roll reaching `table` can loop or fan out past the budget once it is added,
+    /// replacing the table with the same id
+    ///
+    /// # Errors
+    ///
+    /// If tables reference each other in a loop, or a roll on one of them can follow more than
+    /// [`MAX_REFERENCES`] references.
+    pub fn check_references(&self, table: &Table) -> anyhow::Result<()> {
+        let lookup = |id: &str| {
+            if id == table.id {
+                Some(table)
+            } else {
+                self.get(id)
+            }
+        };
+
+        // The new table, and every table rolling on it along the way
+        let mut reaching = vec![table.id.as_str()];
+        loop {
+            let before = reaching.len();
+            for id in self.0.keys() {
+                if !reaching.contains(&id.as_str())
+                    && lookup(id).is_some_and(|other| {
+                        other
+                            .entries
+                            .iter()
+                            .flat_map(Entry::references)
+                            .any(|reference| reaching.contains(&reference))
+                    })
+                {
+                    reaching.push(id);
+                }
+            }
+            if reaching.len() == before {
+                break;
+            }
+        }
+
+        let mut counts = BTreeMap::new();
+        for id in reaching {
+            let count = most_references(id, &lookup, &mut counts, &mut vec![])?;
+            if count > MAX_REFERENCES {
+                bail!(
+                    "A roll on {id} can follow {count} references to other tables, \
+                     more than {MAX_REFERENCES}"
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Most references a roll on the table `id` can follow, remembering each table's in `counts`
+fn most_references<'a>(
+    id: &'a str,
+    lookup: &impl Fn(&str) -> Option<&'a Table>,
+    counts: &mut BTreeMap<&'a str, usize>,
+    path: &mut Vec<&'a str>,
+) -> anyhow::Result<usize> {
+    if let Some(&count) = counts.get(id) {
+        return Ok(count);
+    }
+    if path.contains(&id) {
+        bail!(
+            "Tables reference each other in a loop: {} -> {id}",
+            path.join(" -> ")
+        );
+    }
+    let Some(table) = lookup(id) else {
+        return Ok(0);
+    };
+
+    path.push(id);
+    let mut most = 0;
+    for entry in &table.entries {
+        let mut count = 0usize;
+        for id in entry.references() {
+            count = count
+                .saturating_add(1)
+                .saturating_add(most_references(id, lookup, counts, path)?);
+        }
+        most = most.max(count);
+    }
+    path.pop();
+
+    counts.insert(id, most);
+    Ok(most)
+}
+
+impl Extend<Table> for Tables {
+    fn extend<T: IntoIterator<Item = Table>>(&mut self, tables: T) {
+        self.0
+            .extend(tables.into_iter().map(|table| (table.id.clone(), table)));
+    }
+}
+
+impl FromIterator<Table> for Tables {
+    fn from_iter<T: IntoIterator<Item = Table>>(tables: T) -> Self {
+        Self(
+            tables
+                .into_iter()
+                .map(|table| (table.id.clone(), table))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rand::rng_from_seed;
+
+    use super::*;
+
+    fn encounters() -> Table {
+        serde_json::from_str(
+            r#"{
+                "id": "road-encounters",
+                "name": "Encounters on the road",
+                "dice": "2d6",
+                "entries": [
+                    { "range": "2-6", "text": "{table:travellers}" },
+                    { "range": "7", "text": "Nothing but rain" },
+                    { "range": "8-12", "text": "Wolves led by {name:men-of-bree}'s lost dog" }
+                ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn travellers() -> Table {
+        Table::weighted(
+            "travellers",
+            "Travellers",
+            &[("Dwarves going east", 3), ("A lone Ranger", 1)],
+        )
+    }
+
+    #[test]
+    fn rolls_nested_tables() {
+        let tables = [encounters(), travellers()].into_iter().collect::<Tables>();
+        let mut rng = rng_from_seed(2965);
+        for _ in 0..50 {
+            let roll = tables.roll(&mut rng, "road-encounters").unwrap();
+            let total = roll.total.unwrap();
+
+            assert!((2..=12).contains(&total));
+            assert!(!roll.text.contains('{'));
+            match total {
+                2..=6 => assert!(["Dwarves going east", "A lone Ranger"].contains(&roll.text())),
+                7 => assert_eq!(roll.text(), "Nothing but rain"),
+                _ => assert!(roll.text().starts_with("Wolves led by ")),
+            }
+        }
+    }
+
+    #[test]
+    fn weighted_entries_come_up_more_often() {
+        let tables = [travellers()].into_iter().collect::<Tables>();
+        let mut rng = rng_from_seed(1);
+        let dwarves = (0..1000)
+            .filter(|_| tables.roll(&mut rng, "travellers").unwrap().text == "Dwarves going east")
+            .count();
+
+        assert!(dwarves > 650);
+        assert!(dwarves < 850);
+    }
+
+    #[test]
+    fn reports_unknown_references() {
+        let tables = [Table::list("broken", "Broken", &["{table:missing}"])]
+            .into_iter()
+            .collect::<Tables>();
+
+        assert!(tables.roll(&mut rng_from_seed(1), "broken").is_err());
+        assert!(tables.roll(&mut rng_from_seed(1), "missing").is_err());
+        assert!(expand("{name:orcs}", |_| Ok(String::new())).is_err());
+        assert!(expand("{dice:2d6}", |_| Ok(String::new())).is_err());
+    }
+
+    #[test]
+    fn stops_tables_referencing_each_other_forever() {
+        let tables = [
+            Table::list("chicken", "Chicken", &["{table:egg}"]),
+            Table::list("egg", "Egg", &["{table:chicken}"]),
+        ]
+        .into_iter()
+        .collect::<Tables>();
+
+        assert!(tables.roll(&mut rng_from_seed(1), "chicken").is_err());
+    }
+
+    /// Tables rolling the table below them ten times, `levels` deep
+    fn fanning_tables(levels: usize) -> Tables {
+        let mut tables = vec![Table::list("level-0", "Level 0", &["Rain"])];
+        for level in 1..=levels {
+            let entry = format!("{{table:level-{}}}", level - 1).repeat(10);
+            tables.push(Table::list(&format!("level-{level}"), "Fan", &[&entry]));
+        }
+        tables.into_iter().collect()
+    }
+
+    #[test]
+    fn stops_tables_fanning_out_past_the_budget() {
+        let tables = fanning_tables(3);
+        assert!(tables.roll(&mut rng_from_seed(1), "level-2").is_ok());
+
+        let err = tables.roll(&mut rng_from_seed(1), "level-3").unwrap_err();
+        assert!(err.to_string().contains("more than 1000 references"));
+    }
+
+    #[test]
+    fn stops_text_growing_past_the_budget() {
+        let tables = [
+            Table::list("long", "Long", &[&"Rain ".repeat(1000)]),
+            Table::list("longer", "Longer", &[&"{table:long}".repeat(3)]),
+        ]
+        .into_iter()
+        .collect::<Tables>();
+
+        assert!(tables.roll(&mut rng_from_seed(1), "long").is_ok());
+        assert!(tables.roll(&mut rng_from_seed(1), "longer").is_err());
+    }
+
+    #[test]
+    fn checks_references_of_new_tables() {
+        let tables = fanning_tables(2);
+
+        assert!(
+            tables
+                .check_references(&Table::list("level-3", "Fan", &["{table:level-2}"]))
+                .is_ok()
+        );
+        assert!(
+            tables
+                .check_references(&Table::list("level-0", "Loop", &["{table:level-2}"]))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn builtin_tables_can_all_be_rolled() {
+        let tables = Tables::builtin();
+        let mut rng = rng_from_seed(7);
+
+        for summary in tables.summaries() {
+            assert!(tables.roll(&mut rng, &summary.id).is_ok());
+        }
+        assert!(tables.get("men-of-bree-names").is_some());
+    }
+
+    #[test]
+    fn ranges_parse_from_strings() {
+        assert_eq!(
+            "3-5".parse::<EntryRange>(),
+            Ok(EntryRange { low: 3, high: 5 })
+        );
+        assert_eq!(
+            "7".parse::<EntryRange>(),
+            Ok(EntryRange { low: 7, high: 7 })
+        );
+        assert!("5-3".parse::<EntryRange>().is_err());
+    }
+}
@@ -0,0 +1,395 @@
+/*!
+# Custom tables
+
+Tables uploaded by Loremasters, either as JSON or as plain text with one entry per line:
+
+```text
+# Lines starting with # are comments
+@name Encounters on the road
+@dice 2d6
+2-6: {table:travellers}
+7: Nothing but rain
+8-12: Wolves
+```
+
+Without `@dice`, entries are picked by weight: a line can start with its weight, like
+`3: Dwarves going east`, and lines without one have a weight of 1.
+*/
+
+use std::collections::BTreeSet;
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::dice::notation::Expression;
+
+use super::{Entry, EntryRange, Table, Tables, expand};
+
+/// Something wrong with an uploaded table
+#[derive(Debug, Eq, PartialEq, Serialize, ToSchema)]
+pub struct TableError {
+    /// Line of a plain text upload the error is on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    line: Option<usize>,
+    /// Position of the entry the error is about, starting at 1, for JSON uploads
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entry: Option<usize>,
+    message: String,
+}
+
+impl TableError {
+    fn line(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line: Some(line),
+            entry: None,
+            message: message.into(),
+        }
+    }
+}
+
+/// Read a table uploaded as JSON, saving it under `id` alongside the other `tables`
+///
+/// # Errors
+///
+/// Returns every problem found with the table.
+pub fn parse_json(id: &str, json: &[u8], tables: &Tables) -> Result<Table, Vec<TableError>> {
+    let mut table = serde_json::from_slice::<Table>(json)
+        .map_err(|err| vec![TableError::line(err.line(), err.to_string())])?;
+    id.clone_into(&mut table.id);
+
+    let errors = validate(&table, tables)
+        .into_iter()
+        .map(|(entry, message)| TableError {
+            line: None,
+            entry: entry.map(|index| index + 1),
+            message,
+        })
+        .collect::<Vec<_>>();
+    if errors.is_empty() {
+        Ok(table)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Read a table uploaded as plain text with one entry per line, saving it under `id` alongside
+/// the other `tables`
+///
+/// # Errors
+///
+/// Returns every problem found with the table, along with the line it is on.
+pub fn parse_lines(id: &str, text: &str, tables: &Tables) -> Result<Table, Vec<TableError>> {
+    let mut name = None;
+    let mut dice = None;
+    let mut lines = vec![];
+    let mut errors = vec![];
+
+    for (number, line) in text.lines().enumerate() {
+        let (number, line) = (number + 1, line.trim());
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(directive) = line.strip_prefix('@') {
+            let (directive, value) = directive.split_once(' ').unwrap_or((directive, ""));
+            match directive {
+                "name" => name = Some(value.trim().to_owned()),
+                "dice" => match value.trim().parse::<Expression>() {
+                    Ok(value) => dice = Some(value),
+                    Err(err) => errors.push(TableError::line(number, err)),
+                },
+                _ => errors.push(TableError::line(
+                    number,
+                    format!("Unknown directive @{directive}, expected @name or @dice"),
+                )),
+            }
+            continue;
+        }
+        lines.push((number, line));
+    }
+
+    let mut entries = vec![];
+    let mut entry_lines = vec![];
+    for (number, line) in lines {
+        let (prefix, text) = match line.split_once(':') {
+            Some((prefix, text))
+                if !prefix.trim().is_empty()
+                    && prefix
+                        .trim()
+                        .chars()
+                        .all(|c| c.is_ascii_digit() || c == '-') =>
+            {
+                (Some(prefix.trim()), text.trim())
+            }
+            _ => (None, line),
+        };
+        let mut entry = Entry::weighted(text, 1);
+        match (prefix, &dice) {
+            (Some(prefix), Some(_)) => match prefix.parse::<EntryRange>() {
+                Ok(range) => entry.range = Some(range),
+                Err(err) => errors.push(TableError::line(number, err)),
+            },
+            (Some(prefix), None) => match prefix.parse() {
+                Ok(weight) => entry.weight = weight,
+                Err(_) => errors.push(TableError::line(
+                    number,
+                    format!("Expected a weight, found {prefix:?}. Ranges need @dice."),
+                )),
+            },
+            (None, _) => {}
+        }
+        entries.push(entry);
+        entry_lines.push(number);
+    }
+
+    let table = Table {
+        id: id.to_owned(),
+        name: name.unwrap_or_else(|| id.to_owned()),
+        dice,
+        entries,
+    };
+    errors.extend(
+        validate(&table, tables)
+            .into_iter()
+            .map(|(entry, message)| TableError {
+                line: entry.map(|index| entry_lines[index]),
+                entry: None,
+                message,
+            }),
+    );
+    errors.sort_by_key(|error| error.line);
+
+    if errors.is_empty() {
+        Ok(table)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Problems with a table, along with the index of the entry they are about
+fn validate(table: &Table, tables: &Tables) -> Vec<(Option<usize>, String)> {
+    let mut errors = vec![];
+    if table.id.is_empty()
+        || !table
+            .id
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+    {
+        errors.push((
+            None,
+            format!(
+                "Table id {:?} should only use lowercase letters, digits and dashes",
+                table.id
+            ),
+        ));
+    }
+    if table.name.trim().is_empty() {
+        errors.push((None, "Table needs a name".to_owned()));
+    }
+    if table.entries.is_empty() {
+        errors.push((None, "Table needs at least one entry".to_owned()));
+    }
+
+    // Totals the dice can roll, which entries should cover exactly
+    let bounds = table.dice.as_ref().and_then(|dice| match dice.bounds() {
+        Some(bounds) if bounds.0 >= 0 => Some(bounds),
+        Some(_) => {
+            errors.push((
+                None,
+                format!("Tables cannot use {dice}, which can roll below 0"),
+            ));
+            None
+        }
+        None => {
+            errors.push((None, format!("Tables cannot use {dice}, which explodes")));
+            None
+        }
+    });
+
+    let mut covered = BTreeSet::new();
+    for (index, entry) in table.entries.iter().enumerate() {
+        if entry.text.trim().is_empty() {
+            errors.push((Some(index), "Entry has no text".to_owned()));
+        }
+        if let Err(err) = expand(&entry.text, |_| Ok(String::new())) {
+            errors.push((Some(index), err.to_string()));
+        }
+        match (&table.dice, entry.range) {
+            (Some(dice), Some(range)) => {
+                let Some((lowest, highest)) = bounds else {
+                    continue;
+                };
+                let (low, high) = (i64::from(range.low), i64::from(range.high));
+                if low < lowest || high > highest {
+                    errors.push((
+                        Some(index),
+                        format!(
+                            "Range {range} is outside what {dice} can roll ({lowest}-{highest})"
+                        ),
+                    ));
+                } else if (low..=high).filter(|total| !covered.insert(*total)).count() > 0 {
+                    errors.push((Some(index), format!("Range {range} overlaps another entry")));
+                }
+            }
+            (Some(_), None) => errors.push((
+                Some(index),
+                "Entries of tables rolled with dice need a range".to_owned(),
+            )),
+            (None, Some(_)) => errors.push((
+                Some(index),
+                "Ranges only apply to tables rolled with dice".to_owned(),
+            )),
+            (None, None) => {
+                if entry.weight == 0 {
+                    errors.push((Some(index), "Weight should be at least 1".to_owned()));
+                }
+            }
+        }
+    }
+
+    if let Some((lowest, highest)) = bounds
+        && !table.entries.is_empty()
+    {
+        let missing = (lowest..=highest)
+            .filter(|total| !covered.contains(total))
+            .map(|total| total.to_string())
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            errors.push((
+                None,
+                format!("No entry for a roll of {}", missing.join(", ")),
+            ));
+        }
+    }
+
+    if let Err(err) = tables.check_references(table) {
+        errors.push((None, err.to_string()));
+    }
+    errors
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{rand::rng_from_seed, tables::Tables};
+
+    use super::*;
+
+    fn lines(id: &str, text: &str) -> Result<Table, Vec<TableError>> {
+        parse_lines(id, text, &Tables::default())
+    }
+
+    fn json(id: &str, json: &[u8]) -> Result<Table, Vec<TableError>> {
+        parse_json(id, json, &Tables::default())
+    }
+
+    #[test]
+    fn parses_dice_tables_from_lines() {
+        let table = lines(
+            "road-encounters",
+            "# Rolled at every camp\n\
+             @name Encounters on the road\n\
+             @dice 2d6\n\
+             \n\
+             2-6: Travellers: Dwarves going east\n\
+             7: Nothing but rain\n\
+             8-12: Wolves led by {name:men-of-bree}'s lost dog\n",
+        )
+        .unwrap();
+
+        assert_eq!(table.name, "Encounters on the road");
+        assert_eq!(table.entries.len(), 3);
+        assert_eq!(table.entries[0].text, "Travellers: Dwarves going east");
+        assert_eq!(
+            table.entries[2].range,
+            Some(EntryRange { low: 8, high: 12 })
+        );
+    }
+
+    #[test]
+    fn parses_weighted_tables_from_lines() {
+        let table = lines("travellers", "3: Dwarves going east\nA lone Ranger").unwrap();
+        let tables = [table].into_iter().collect::<Tables>();
+
+        assert_eq!(tables.get("travellers").unwrap().name, "travellers");
+        assert_eq!(tables.get("travellers").unwrap().entries[0].weight, 3);
+        assert!(tables.roll(&mut rng_from_seed(1), "travellers").is_ok());
+    }
+
+    #[test]
+    fn reports_errors_per_line() {
+        let errors = lines(
+            "broken",
+            "@dice 1d6\n\
+             1-3: Rain\n\
+             3-4: Hail\n\
+             Fog\n\
+             6: Snow from {name:orcs}\n\
+             @colour red\n",
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            errors.iter().map(|error| error.line).collect::<Vec<_>>(),
+            vec![None, Some(3), Some(4), Some(5), Some(6)]
+        );
+        assert_eq!(errors[0].message, "No entry for a roll of 5");
+    }
+
+    #[test]
+    fn reports_errors_per_entry_for_json() {
+        let errors = json(
+            "weather",
+            br#"{ "name": "Weather", "entries": [{ "text": "Rain" }, { "text": "", "weight": 0 }] }"#,
+        )
+        .unwrap_err();
+
+        assert!(errors.iter().all(|error| error.entry == Some(2)));
+        assert_eq!(errors.len(), 2);
+    }
+
+    #[test]
+    fn reports_malformed_json() {
+        let errors = json("weather", b"{\n\"name\": ").unwrap_err();
+
+        assert_eq!(errors[0].line, Some(2));
+    }
+
+    #[test]
+    fn rejects_dice_entries_cannot_cover() {
+        assert!(lines("fate", "@dice 4dF\n0: Nothing").is_err());
+        assert!(lines("boom", "@dice 1d6!\n1-6: Boom").is_err());
+        assert!(lines("stats", "@dice 4d6kh3\n3-18: A hero").is_ok());
+    }
+
+    #[test]
+    fn rejects_ids_that_cannot_be_referenced() {
+        assert!(lines("Bad Id", "Rain").is_err());
+    }
+
+    #[test]
+    fn rejects_tables_referencing_each_other_in_a_loop() {
+        let tables = [Table::list("egg", "Egg", &["{table:chicken}"])]
+            .into_iter()
+            .collect::<Tables>();
+
+        let errors = parse_lines("chicken", "{table:egg}", &tables).unwrap_err();
+        assert!(errors[0].message.contains("loop"));
+        assert!(lines("ouroboros", "{table:ouroboros}").is_err());
+        assert!(parse_lines("chicken", "{table:egg-free}", &tables).is_ok());
+    }
+
+    #[test]
+    fn rejects_tables_fanning_out_past_the_budget() {
+        // Every level rolls the one below ten times: 10 + 100 + 1000 references
+        let tables = [
+            Table::list("leaf", "Leaf", &["Rain"]),
+            Table::list("level-1", "Level 1", &[&"{table:leaf}".repeat(10)]),
+            Table::list("level-2", "Level 2", &[&"{table:level-1}".repeat(10)]),
+        ]
+        .into_iter()
+        .collect::<Tables>();
+
+        assert!(parse_lines("level-3", &"{table:level-2}".repeat(9), &tables).is_ok());
+        let errors = parse_lines("level-3", &"{table:level-2}".repeat(10), &tables).unwrap_err();
+        assert!(errors[0].message.contains("1110 references"));
+    }
+}
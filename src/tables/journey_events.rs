@@ -0,0 +1,471 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    dice::{Favour, FeatDie, SuccessDie},
+    journeys::Role,
+};
+
+/// The entries of the Journey Event table
+#[derive(Clone, Copy, Debug, Deserialize, strum::Display, Eq, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "title_case")]
+pub enum JourneyEvent {
+    TerribleMisfortune,
+    Despair,
+    IllChoices,
+    Mishap,
+    ShortCut,
+    ChanceMeeting,
+    JoyfulSight,
+}
+
+impl JourneyEvent {
+    /// Event for a result of the Feat die
+    #[must_use]
+    pub fn from_feat_die(feat_die: FeatDie) -> Self {
+        match feat_die {
+            FeatDie::Eye => JourneyEvent::TerribleMisfortune,
+            FeatDie::Number(1) => JourneyEvent::Despair,
+            FeatDie::Number(2 | 3) => JourneyEvent::IllChoices,
+            FeatDie::Number(8 | 9) => JourneyEvent::ShortCut,
+            FeatDie::Number(10) => JourneyEvent::ChanceMeeting,
+            FeatDie::Number(_) => JourneyEvent::Mishap,
+            FeatDie::Gandalf => JourneyEvent::JoyfulSight,
+        }
+    }
+
+    /// Fatigue every hero gains from the event
+    #[must_use]
+    pub fn fatigue(self) -> u32 {
+        match self {
+            JourneyEvent::TerribleMisfortune => 3,
+            JourneyEvent::Despair | JourneyEvent::IllChoices | JourneyEvent::Mishap => 2,
+            JourneyEvent::ShortCut | JourneyEvent::ChanceMeeting => 1,
+            JourneyEvent::JoyfulSight => 0,
+        }
+    }
+
+    /// What happens when the targeted hero passes their test
+    #[must_use]
+    pub fn on_success(self) -> &'static str {
+        match self {
+            JourneyEvent::ShortCut => "The journey takes 1 day less.",
+            JourneyEvent::ChanceMeeting => {
+                "The company meets someone who is well-disposed towards them."
+            }
+            JourneyEvent::JoyfulSight => "Every hero regains 1 Hope.",
+            JourneyEvent::TerribleMisfortune
+            | JourneyEvent::Despair
+            | JourneyEvent::IllChoices
+            | JourneyEvent::Mishap => "The company carries on without further trouble.",
+        }
+    }
+
+    /// What happens when the targeted hero fails their test
+    #[must_use]
+    pub fn on_failure(self) -> &'static str {
+        match self {
+            JourneyEvent::TerribleMisfortune => "The targeted hero is Wounded.",
+            JourneyEvent::Despair => "Every hero gains 2 Shadow points (Dread).",
+            JourneyEvent::IllChoices => "The targeted hero gains 1 Shadow point (Dread).",
+            JourneyEvent::Mishap => {
+                "The journey takes 1 more day, and the targeted hero gains 1 extra Fatigue."
+            }
+            JourneyEvent::ChanceMeeting => "The company meets someone who is wary of them.",
+            JourneyEvent::ShortCut | JourneyEvent::JoyfulSight => {
+                "The company carries on without further trouble."
+            }
+        }
+    }
+
+    /// Detail subtable of the event, one entry per Success die result
+    #[must_use]
+    pub fn details(self) -> &'static [EventDetail; 6] {
+        match self {
+            JourneyEvent::TerribleMisfortune => &TERRIBLE_MISFORTUNE,
+            JourneyEvent::Despair => &DESPAIR,
+            JourneyEvent::IllChoices => &ILL_CHOICES,
+            JourneyEvent::Mishap => &MISHAP,
+            JourneyEvent::ShortCut => &SHORT_CUT,
+            JourneyEvent::ChanceMeeting => &CHANCE_MEETING,
+            JourneyEvent::JoyfulSight => &JOYFUL_SIGHT,
+        }
+    }
+
+    /// Detail drawn for a given Success die result
+    #[must_use]
+    pub fn detail(self, success_die: SuccessDie) -> &'static EventDetail {
+        let index = usize::from(success_die.value(false).clamp(1, 6) - 1);
+        &self.details()[index]
+    }
+}
+
+/// What exactly befalls the company, and which role must deal with it
+#[derive(Debug, Eq, PartialEq, Serialize, ToSchema)]
+pub struct EventDetail {
+    title: &'static str,
+    description: &'static str,
+    /// Role whose holder must roll to deal with the event
+    target: Role,
+}
+
+impl EventDetail {
+    #[must_use]
+    pub fn title(&self) -> &'static str {
+        self.title
+    }
+
+    #[must_use]
+    pub fn target(&self) -> Role {
+        self.target
+    }
+}
+
+/// Result of rolling on the Journey Event table and its detail subtable
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JourneyEventRoll {
+    feat_die: FeatDie,
+    event: JourneyEvent,
+    /// Fatigue every hero gains from the event
+    fatigue: u32,
+    success_die: SuccessDie,
+    detail: &'static EventDetail,
+    /// Skill the targeted hero rolls
+    skill: &'static str,
+    on_success: &'static str,
+    on_failure: &'static str,
+}
+
+impl JourneyEventRoll {
+    /// Roll the Feat die for the event and a Success die for its detail
+    pub fn roll<R: Rng + ?Sized>(rng: &mut R, favour: Favour) -> Self {
+        let feat_die = FeatDie::roll_with(rng, favour);
+        let success_die = rng.random::<SuccessDie>();
+        let event = JourneyEvent::from_feat_die(feat_die);
+        let detail = event.detail(success_die);
+        Self {
+            feat_die,
+            event,
+            fatigue: event.fatigue(),
+            success_die,
+            detail,
+            skill: detail.target.skill(),
+            on_success: event.on_success(),
+            on_failure: event.on_failure(),
+        }
+    }
+
+    #[must_use]
+    pub fn feat_die(&self) -> FeatDie {
+        self.feat_die
+    }
+
+    #[must_use]
+    pub fn event(&self) -> JourneyEvent {
+        self.event
+    }
+
+    #[must_use]
+    pub fn detail(&self) -> &'static EventDetail {
+        self.detail
+    }
+}
+
+const TERRIBLE_MISFORTUNE: [EventDetail; 6] = [
+    EventDetail {
+        title: "Crumbling Ledge",
+        description: "The path along a cliff gives way beneath the company's feet.",
+        target: Role::Scout,
+    },
+    EventDetail {
+        title: "Fog on the Fells",
+        description: "A cold mist rises and leads the company astray towards a precipice.",
+        target: Role::Scout,
+    },
+    EventDetail {
+        title: "Ambush",
+        description: "Wild beasts or evil folk fall upon the company from hiding.",
+        target: Role::LookOut,
+    },
+    EventDetail {
+        title: "Stalked",
+        description: "Something follows the company by night, striking when the watch is weak.",
+        target: Role::LookOut,
+    },
+    EventDetail {
+        title: "Tainted Fare",
+        description: "Food gathered along the way turns out to be spoiled or poisonous.",
+        target: Role::Hunter,
+    },
+    EventDetail {
+        title: "Rockslide",
+        description: "Stones thunder down the slope as the company passes below.",
+        target: Role::Guide,
+    },
+];
+
+const DESPAIR: [EventDetail; 6] = [
+    EventDetail {
+        title: "Grim Remains",
+        description: "The company crosses a field where a battle of the ancient wars was fought.",
+        target: Role::LookOut,
+    },
+    EventDetail {
+        title: "Evil Ruin",
+        description: "Broken walls recall dark deeds done in the days of Angmar.",
+        target: Role::LookOut,
+    },
+    EventDetail {
+        title: "Endless Rain",
+        description: "Days of grey rain soak the company and their spirits.",
+        target: Role::Guide,
+    },
+    EventDetail {
+        title: "Haunted Hollow",
+        description: "The company camps in a hollow where restless spirits whisper at night.",
+        target: Role::Scout,
+    },
+    EventDetail {
+        title: "Blighted Land",
+        description: "The land is stripped of game and green things, as if by a curse.",
+        target: Role::Hunter,
+    },
+    EventDetail {
+        title: "Shadow in the East",
+        description: "A darkness gathers on the horizon, and no-one can say what it portends.",
+        target: Role::Guide,
+    },
+];
+
+const ILL_CHOICES: [EventDetail; 6] = [
+    EventDetail {
+        title: "Wrong Turning",
+        description: "The company follows a promising trail that leads nowhere.",
+        target: Role::Guide,
+    },
+    EventDetail {
+        title: "Unwise Camp",
+        description: "The chosen campsite is damp, exposed or worse.",
+        target: Role::Scout,
+    },
+    EventDetail {
+        title: "Poaching",
+        description: "The company hunts on land jealously kept by others.",
+        target: Role::Hunter,
+    },
+    EventDetail {
+        title: "Quarrel",
+        description: "Tempers flare during a long watch and harsh words are spoken.",
+        target: Role::LookOut,
+    },
+    EventDetail {
+        title: "Reckless Crossing",
+        description: "The company fords a river where it runs too fast and deep.",
+        target: Role::Guide,
+    },
+    EventDetail {
+        title: "Trespass",
+        description: "The company wanders where it is not welcome, and is seen.",
+        target: Role::Scout,
+    },
+];
+
+const MISHAP: [EventDetail; 6] = [
+    EventDetail {
+        title: "Swollen River",
+        description: "Heavy rains have made a river impassable where the company meant to ford it.",
+        target: Role::Guide,
+    },
+    EventDetail {
+        title: "Blocked Path",
+        description: "Fallen trees or a landslide bar the way ahead.",
+        target: Role::Scout,
+    },
+    EventDetail {
+        title: "Scarce Game",
+        description: "The company's provisions run low and there is little to hunt.",
+        target: Role::Hunter,
+    },
+    EventDetail {
+        title: "Lost Gear",
+        description: "Something important is left behind at the last camp.",
+        target: Role::LookOut,
+    },
+    EventDetail {
+        title: "Lame Pony",
+        description: "One of the company's beasts of burden goes lame.",
+        target: Role::Scout,
+    },
+    EventDetail {
+        title: "Foul Weather",
+        description: "A storm forces the company to seek shelter and wait.",
+        target: Role::Guide,
+    },
+];
+
+const SHORT_CUT: [EventDetail; 6] = [
+    EventDetail {
+        title: "Hidden Path",
+        description: "A narrow track, known only to a few, promises to save time.",
+        target: Role::Guide,
+    },
+    EventDetail {
+        title: "Ancient Road",
+        description: "The overgrown remains of an old road of Arnor run the company's way.",
+        target: Role::Scout,
+    },
+    EventDetail {
+        title: "Shallow Ford",
+        description: "The company finds a ford where the river runs low.",
+        target: Role::Scout,
+    },
+    EventDetail {
+        title: "Game Trail",
+        description: "Deer have worn a trail through the thickest of the woods.",
+        target: Role::Hunter,
+    },
+    EventDetail {
+        title: "High Vantage",
+        description: "From a hilltop, a quicker way through the land below can be seen.",
+        target: Role::LookOut,
+    },
+    EventDetail {
+        title: "Friendly Directions",
+        description: "A passing traveller tells of a better way.",
+        target: Role::Guide,
+    },
+];
+
+const CHANCE_MEETING: [EventDetail; 6] = [
+    EventDetail {
+        title: "Fellow Travellers",
+        description: "A group of wayfarers is heading the same way.",
+        target: Role::LookOut,
+    },
+    EventDetail {
+        title: "Rangers on Patrol",
+        description: "Grim-faced Rangers of the North step out of the trees.",
+        target: Role::LookOut,
+    },
+    EventDetail {
+        title: "Hunters in the Wild",
+        description: "The company comes across hunters tracking the same game.",
+        target: Role::Hunter,
+    },
+    EventDetail {
+        title: "Hermit",
+        description: "A solitary dweller of the Wild watches the company from their doorstep.",
+        target: Role::Scout,
+    },
+    EventDetail {
+        title: "Merchants on the Road",
+        description: "Dwarven merchants travel with laden ponies towards the Blue Mountains.",
+        target: Role::Guide,
+    },
+    EventDetail {
+        title: "Elves Journeying West",
+        description: "A company of Elves passes by in the twilight, singing softly.",
+        target: Role::LookOut,
+    },
+];
+
+const JOYFUL_SIGHT: [EventDetail; 6] = [
+    EventDetail {
+        title: "Herd of Deer",
+        description: "A proud stag leads his herd across a sunlit glade.",
+        target: Role::LookOut,
+    },
+    EventDetail {
+        title: "Hidden Dell",
+        description: "A peaceful dell offers clear water and a sheltered camp.",
+        target: Role::Scout,
+    },
+    EventDetail {
+        title: "Plentiful Fare",
+        description: "Berries, mushrooms and game abound.",
+        target: Role::Hunter,
+    },
+    EventDetail {
+        title: "Ancient Monument",
+        description: "A standing stone raised by the Dúnedain still bears its proud carvings.",
+        target: Role::Guide,
+    },
+    EventDetail {
+        title: "Starlit Night",
+        description: "The stars shine brightly, and for a while the Shadow seems far away.",
+        target: Role::LookOut,
+    },
+    EventDetail {
+        title: "Birdsong",
+        description: "The morning air is filled with the songs of birds.",
+        target: Role::Scout,
+    },
+];
+
+#[cfg(test)]
+mod test {
+    use strum::IntoEnumIterator;
+
+    use crate::{rand::rng_from_seed, regions::RegionType};
+
+    use super::*;
+
+    #[test]
+    fn every_feat_die_face_has_an_event() {
+        assert_eq!(
+            JourneyEvent::from_feat_die(FeatDie::Eye),
+            JourneyEvent::TerribleMisfortune
+        );
+        assert_eq!(
+            JourneyEvent::from_feat_die(FeatDie::Number(1)),
+            JourneyEvent::Despair
+        );
+        assert_eq!(
+            JourneyEvent::from_feat_die(FeatDie::Number(5)),
+            JourneyEvent::Mishap
+        );
+        assert_eq!(
+            JourneyEvent::from_feat_die(FeatDie::Number(9)),
+            JourneyEvent::ShortCut
+        );
+        assert_eq!(
+            JourneyEvent::from_feat_die(FeatDie::Gandalf),
+            JourneyEvent::JoyfulSight
+        );
+    }
+
+    #[test]
+    fn every_role_can_be_targeted() {
+        let targets = [
+            JourneyEvent::TerribleMisfortune,
+            JourneyEvent::Despair,
+            JourneyEvent::IllChoices,
+            JourneyEvent::Mishap,
+            JourneyEvent::ShortCut,
+            JourneyEvent::ChanceMeeting,
+            JourneyEvent::JoyfulSight,
+        ]
+        .into_iter()
+        .flat_map(|event| event.details().iter().map(EventDetail::target))
+        .collect::<Vec<_>>();
+
+        for role in [Role::Guide, Role::Hunter, Role::LookOut, Role::Scout] {
+            assert!(targets.contains(&role));
+        }
+    }
+
+    #[test]
+    fn roll_matches_its_tables() {
+        let mut rng = rng_from_seed(2965);
+        for region in RegionType::iter() {
+            let roll = JourneyEventRoll::roll(&mut rng, region.event_favour());
+
+            assert_eq!(roll.event, JourneyEvent::from_feat_die(roll.feat_die));
+            assert_eq!(roll.detail, roll.event.detail(roll.success_die));
+            assert_eq!(roll.skill, roll.detail.target.skill());
+            assert_eq!(roll.fatigue, roll.event.fatigue());
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use rand::Rng;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::dice::FeatDie;
+
+/// A turn of events revealing that the Enemy has become aware of the company
+#[derive(Debug, Eq, PartialEq, Serialize, ToSchema)]
+pub struct RevelationEpisode {
+    title: &'static str,
+    description: &'static str,
+    /// Consequences the Loremaster should apply
+    effects: &'static [&'static str],
+}
+
+/// Result of drawing a Revelation Episode
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RevelationEpisodeRoll {
+    feat_die: FeatDie,
+    episode: &'static RevelationEpisode,
+}
+
+impl RevelationEpisodeRoll {
+    /// Roll the Feat die to draw a Revelation Episode
+    pub fn roll<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let feat_die = rng.random::<FeatDie>();
+        Self {
+            feat_die,
+            episode: episode(feat_die),
+        }
+    }
+}
+
+/// Episode drawn for a given Feat die result
+#[must_use]
+pub fn episode(feat_die: FeatDie) -> &'static RevelationEpisode {
+    let index = match feat_die {
+        FeatDie::Eye => 0,
+        FeatDie::Number(number) => usize::from(number.clamp(1, 10)),
+        FeatDie::Gandalf => 11,
+    };
+    &REVELATION_EPISODES[index]
+}
+
+/// One episode per Feat die face, from the Eye of Sauron to the Gandalf rune
+const REVELATION_EPISODES: [RevelationEpisode; 12] = [
+    RevelationEpisode {
+        title: "The Hunt Begins",
+        description: "A servant of the Enemy has picked up the trail of the company and gathers \
+                      others to hunt them down.",
+        effects: &[
+            "The company is ambushed during the next Journey Event or at the end of the journey",
+            "Every hero gains 1 Shadow point (Dread)",
+        ],
+    },
+    RevelationEpisode {
+        title: "Ill Tidings",
+        description: "News arrives of a calamity befalling a friend, a patron or the folk of the \
+                      company's Safe Haven, and the company is to blame.",
+        effects: &["Every hero gains 1 Shadow point (Dread)"],
+    },
+    RevelationEpisode {
+        title: "Betrayal",
+        description: "Someone the company trusted has sold word of their errand to agents of the \
+                      Shadow.",
+        effects: &["The Resistance of the next Council the company takes part in is raised by 1"],
+    },
+    RevelationEpisode {
+        title: "Spies in the Sky",
+        description: "Flocks of crebain wheel overhead, and the company cannot shake the feeling \
+                      of being watched.",
+        effects: &["All Stealth rolls are ill-favoured until the end of the journey"],
+    },
+    RevelationEpisode {
+        title: "Foul Weather",
+        description: "A storm of unnatural fury breaks over the company, as if the land itself \
+                      had turned against them.",
+        effects: &[
+            "Every hero gains 1 Fatigue",
+            "The journey takes 1 extra day",
+        ],
+    },
+    RevelationEpisode {
+        title: "Old Foes Return",
+        description: "An adversary the company defeated or spared in the past returns, bearing a \
+                      grudge and new allies.",
+        effects: &["The Loremaster introduces a returning adversary in the next encounter"],
+    },
+    RevelationEpisode {
+        title: "The Lost Trail",
+        description: "Paths the company relied upon have been watched, broken or deliberately \
+                      misdirected.",
+        effects: &["The next Marching test is ill-favoured"],
+    },
+    RevelationEpisode {
+        title: "A Sinister Visitor",
+        description: "A stranger arrives where the company rests, asking too many questions \
+                      about travellers on the road.",
+        effects: &[
+            "The company must leave their current refuge or face a confrontation with the stranger",
+        ],
+    },
+    RevelationEpisode {
+        title: "Troubled Dreams",
+        description: "Visions of a lidless eye and of shadows gathering in the East trouble the \
+                      company's sleep.",
+        effects: &["Heroes do not recover Endurance from their next prolonged rest"],
+    },
+    RevelationEpisode {
+        title: "A Price on Their Heads",
+        description: "Ruffians and outlaws have been promised a reward for news of the company's \
+                      whereabouts.",
+        effects: &["Every encounter with strangers starts with them being suspicious"],
+    },
+    RevelationEpisode {
+        title: "Dark Rumours",
+        description: "Whispers spread ahead of the company, painting them as troublemakers or \
+                      worse.",
+        effects: &["The company suffers a -1 penalty to Standing in the next settlement visited"],
+    },
+    RevelationEpisode {
+        title: "A Narrow Escape",
+        description: "The Eye sweeps over the company and passes on, its attention drawn \
+                      elsewhere for now.",
+        effects: &["No further effect, but the Loremaster should foreshadow what is to come"],
+    },
+];
+
+#[cfg(test)]
+mod test {
+    use crate::rand::rng_from_seed;
+
+    use super::*;
+
+    #[test]
+    fn every_feat_die_face_has_an_episode() {
+        let faces = [FeatDie::Eye, FeatDie::Gandalf]
+            .into_iter()
+            .chain((1..=10).map(FeatDie::Number));
+
+        for face in faces {
+            let episode = episode(face);
+            assert!(!episode.title.is_empty());
+            assert!(!episode.effects.is_empty());
+        }
+    }
+
+    #[test]
+    fn same_seed_draws_same_episode() {
+        let first = RevelationEpisodeRoll::roll(&mut rng_from_seed(7));
+        let second = RevelationEpisodeRoll::roll(&mut rng_from_seed(7));
+
+        assert_eq!(first.feat_die, second.feat_die);
+        assert_eq!(first.episode, second.episode);
+    }
+}
@@ -0,0 +1,347 @@
+use std::ops::RangeInclusive;
+
+use anyhow::bail;
+use rand::{
+    Rng,
+    seq::{IndexedRandom, IteratorRandom},
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    cultures::HeroicCulture,
+    map::Region,
+    tables::{Reference, expand},
+};
+
+/// Year of the Third Age the default Eriador campaign starts in
+pub const CAMPAIGN_START: u16 = 2965;
+
+/// Rumours that can be heard at any time
+const ANY_YEAR: RangeInclusive<u16> = 0..=u16::MAX;
+
+/// Whether an entry is idle talk or something the company can act upon
+#[derive(Clone, Copy, Debug, Deserialize, strum::Display, Eq, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum RumourKind {
+    /// News and gossip overheard in the common room
+    Rumour,
+    /// A request for help or an opportunity that can start an adventure
+    Hook,
+}
+
+/// An entry of the rumour table
+///
+/// The text can name NPCs with `{name:<culture>}`, which is replaced by a name generated for
+/// that Heroic Culture.
+#[derive(Debug)]
+pub struct Rumour {
+    kind: RumourKind,
+    text: &'static str,
+    /// Regions the rumour is heard in, or everywhere if empty
+    regions: &'static [Region],
+    /// Years of the Third Age the rumour is heard in
+    years: RangeInclusive<u16>,
+}
+
+impl Rumour {
+    fn is_heard(&self, region: Region, year: u16) -> bool {
+        (self.regions.is_empty() || self.regions.contains(&region)) && self.years.contains(&year)
+    }
+}
+
+/// An NPC named in a rumour
+#[derive(Debug, Serialize, ToSchema)]
+pub struct NamedNpc {
+    name: String,
+    culture: HeroicCulture,
+}
+
+/// Result of rolling on the rumour table
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RumourRoll {
+    kind: RumourKind,
+    text: String,
+    /// NPCs mentioned in the text, in order of appearance
+    npcs: Vec<NamedNpc>,
+}
+
+impl RumourRoll {
+    /// Roll a rumour heard in `region` during `year`, optionally only of the given kind
+    pub fn roll<R: Rng + ?Sized>(
+        rng: &mut R,
+        region: Region,
+        year: u16,
+        kind: Option<RumourKind>,
+    ) -> Self {
+        let rumour = rumours(region, year)
+            .filter(|rumour| kind.is_none_or(|kind| rumour.kind == kind))
+            .choose(rng)
+            .expect("rumours of every kind are heard everywhere");
+        Self::tell(rng, rumour)
+    }
+
+    /// Roll `count` different rumours heard in `region` during `year`
+    pub fn roll_many<R: Rng + ?Sized>(
+        rng: &mut R,
+        region: Region,
+        year: u16,
+        count: usize,
+    ) -> Vec<Self> {
+        rumours(region, year)
+            .collect::<Vec<_>>()
+            .choose_multiple(rng, count)
+            .map(|rumour| Self::tell(rng, rumour))
+            .collect()
+    }
+
+    /// Fill in the names of the NPCs the rumour mentions
+    fn tell<R: Rng + ?Sized>(rng: &mut R, rumour: &Rumour) -> Self {
+        let mut npcs = vec![];
+        let text = expand(rumour.text, |reference| match reference {
+            Reference::Name(culture) => {
+                let name = culture.random_name(rng);
+                npcs.push(NamedNpc {
+                    name: name.clone(),
+                    culture,
+                });
+                Ok(name)
+            }
+            Reference::Table(id) => bail!("Rumours cannot roll on table {id}"),
+        })
+        .expect("rumours only name NPCs of Heroic Cultures");
+
+        Self {
+            kind: rumour.kind,
+            text,
+            npcs,
+        }
+    }
+}
+
+/// Rumours heard in `region` during `year`
+pub fn rumours(region: Region, year: u16) -> impl Iterator<Item = &'static Rumour> {
+    RUMOURS
+        .iter()
+        .filter(move |rumour| rumour.is_heard(region, year))
+}
+
+const RUMOURS: &[Rumour] = &[
+    Rumour {
+        kind: RumourKind::Rumour,
+        text: "Strange folk have been seen on the roads, asking questions about travellers.",
+        regions: &[],
+        years: ANY_YEAR,
+    },
+    Rumour {
+        kind: RumourKind::Rumour,
+        text: "Wolves were heard howling close to the fences last winter, bolder than ever.",
+        regions: &[],
+        years: ANY_YEAR,
+    },
+    Rumour {
+        kind: RumourKind::Rumour,
+        text: "{name:men-of-bree} the pedlar swears an old map of forgotten treasure was found \
+               in a cellar, but will not say whose.",
+        regions: &[],
+        years: ANY_YEAR,
+    },
+    Rumour {
+        kind: RumourKind::Hook,
+        text: "{name:men-of-bree} has not come back from gathering firewood, and the family \
+               offers a reward to anyone who finds them.",
+        regions: &[],
+        years: ANY_YEAR,
+    },
+    Rumour {
+        kind: RumourKind::Hook,
+        text: "A wounded traveller named {name:rangers-of-the-north} asks for a message to be \
+               carried north, and will say no more.",
+        regions: &[],
+        years: ANY_YEAR,
+    },
+    Rumour {
+        kind: RumourKind::Rumour,
+        text: "Lights have been seen moving among the mounds on the Barrow-downs at night.",
+        regions: &[Region::BarrowDowns, Region::BreeLand, Region::TheShire],
+        years: ANY_YEAR,
+    },
+    Rumour {
+        kind: RumourKind::Hook,
+        text: "Farmer {name:hobbits-of-the-shire} wants someone to find out who, or what, has \
+               been stealing from the mushroom fields.",
+        regions: &[Region::TheShire],
+        years: ANY_YEAR,
+    },
+    Rumour {
+        kind: RumourKind::Rumour,
+        text: "Tall Elves have been seen walking in the Green Hills under the stars, singing as \
+               they go westwards.",
+        regions: &[Region::TheShire, Region::Lindon],
+        years: ANY_YEAR,
+    },
+    Rumour {
+        kind: RumourKind::Hook,
+        text: "{name:men-of-bree}, gatekeeper of Bree, is looking for bold folk to escort a \
+               wagon of goods down the Greenway.",
+        regions: &[Region::BreeLand],
+        years: ANY_YEAR,
+    },
+    Rumour {
+        kind: RumourKind::Rumour,
+        text: "Trolls have come down from the Ettenmoors, and the Last Bridge is no longer safe \
+               after dark.",
+        regions: &[
+            Region::Bruinen,
+            Region::Ettenmoors,
+            Region::LoneLands,
+            Region::Trollshaws,
+        ],
+        years: ANY_YEAR,
+    },
+    Rumour {
+        kind: RumourKind::Hook,
+        text: "The ruins on Weathertop are said to hide a stair going down, and \
+               {name:rangers-of-the-north} knows where to find it.",
+        regions: &[Region::LoneLands, Region::WeatherHills],
+        years: ANY_YEAR,
+    },
+    Rumour {
+        kind: RumourKind::Rumour,
+        text: "Hillmen from Dunland have been trading furs for steel, and asking the way north.",
+        regions: &[Region::Eregion, Region::Gwathlo, Region::Minhiriath],
+        years: ANY_YEAR,
+    },
+    Rumour {
+        kind: RumourKind::Hook,
+        text: "{name:elves-of-lindon} seeks companions to visit the ruined halls of Eregion, \
+               where something long forgotten stirs.",
+        regions: &[Region::Eregion, Region::Lindon, Region::Bruinen],
+        years: ANY_YEAR,
+    },
+    Rumour {
+        kind: RumourKind::Rumour,
+        text: "Cold things walk in the ruins of Fornost, and no shepherd will graze their flock \
+               on the North Downs any more.",
+        regions: &[Region::Angmar, Region::Evendim, Region::NorthDowns],
+        years: ANY_YEAR,
+    },
+    Rumour {
+        kind: RumourKind::Rumour,
+        text: "Dwarves from the Blue Mountains pass east in numbers, bound for the Lonely \
+               Mountain where the King under the Mountain has returned.",
+        regions: &[Region::BlueMountains, Region::BreeLand, Region::TheShire],
+        years: 2965..=2988,
+    },
+    Rumour {
+        kind: RumourKind::Rumour,
+        text: "Balin son of Fundin has gone to retake Moria, and many Dwarves of the Blue \
+               Mountains went with him.",
+        regions: &[Region::BlueMountains, Region::BreeLand, Region::Eregion],
+        years: 2989..=2994,
+    },
+    Rumour {
+        kind: RumourKind::Hook,
+        text: "No word has come from Moria for years. {name:dwarves-of-durins-folk} seeks \
+               travellers willing to go and learn what became of Balin's folk.",
+        regions: &[Region::BlueMountains, Region::Eregion],
+        years: 2995..=3018,
+    },
+    Rumour {
+        kind: RumourKind::Rumour,
+        text: "Mad Baggins of Bag End vanished in front of everyone at his own birthday party, \
+               and his nephew has his hole and his gold.",
+        regions: &[Region::BreeLand, Region::TheShire],
+        years: 3001..=3018,
+    },
+    Rumour {
+        kind: RumourKind::Rumour,
+        text: "Squint-eyed Southerners have been buying up pipe-weed and lodging at the inns \
+               along the Greenway.",
+        regions: &[Region::BreeLand, Region::Gwathlo, Region::Minhiriath],
+        years: 3000..=3018,
+    },
+    Rumour {
+        kind: RumourKind::Rumour,
+        text: "The Rangers are hunting a wretched creature that creeps along the rivers and \
+               steals fish and babes alike.",
+        regions: &[Region::Bruinen, Region::LoneLands, Region::Trollshaws],
+        years: 3009..=3017,
+    },
+    Rumour {
+        kind: RumourKind::Hook,
+        text: "Black Riders have been asking for a hobbit named Baggins, and \
+               {name:hobbits-of-the-shire} is frightened enough to ask for help.",
+        regions: &[Region::BreeLand, Region::TheShire],
+        years: 3018..=3018,
+    },
+];
+
+#[cfg(test)]
+mod test {
+    use strum::IntoEnumIterator;
+
+    use crate::rand::rng_from_seed;
+
+    use super::*;
+
+    #[test]
+    fn every_region_has_rumours_and_hooks() {
+        for region in Region::iter() {
+            for year in [CAMPAIGN_START, 2990, 3018] {
+                let heard = rumours(region, year).collect::<Vec<_>>();
+
+                assert!(heard.iter().any(|rumour| rumour.kind == RumourKind::Rumour));
+                assert!(heard.iter().any(|rumour| rumour.kind == RumourKind::Hook));
+            }
+        }
+    }
+
+    #[test]
+    fn rumours_depend_on_year() {
+        let talks_of_balin =
+            |year| rumours(Region::BlueMountains, year).any(|rumour| rumour.text.contains("Balin"));
+
+        assert!(!talks_of_balin(CAMPAIGN_START));
+        assert!(talks_of_balin(2990));
+    }
+
+    #[test]
+    fn names_npcs_in_text() {
+        let mut rng = rng_from_seed(2965);
+        for rumour in RUMOURS {
+            let roll = RumourRoll::tell(&mut rng, rumour);
+
+            assert!(!roll.text.contains('{'));
+            assert_eq!(roll.npcs.len(), rumour.text.matches("{name:").count());
+            for npc in &roll.npcs {
+                assert!(roll.text.contains(&npc.name));
+            }
+        }
+    }
+
+    #[test]
+    fn rolls_only_requested_kind() {
+        let mut rng = rng_from_seed(7);
+        for _ in 0..20 {
+            let roll = RumourRoll::roll(
+                &mut rng,
+                Region::TheShire,
+                CAMPAIGN_START,
+                Some(RumourKind::Hook),
+            );
+
+            assert_eq!(roll.kind, RumourKind::Hook);
+        }
+    }
+
+    #[test]
+    fn rolls_different_rumours() {
+        let rolls = RumourRoll::roll_many(&mut rng_from_seed(1), Region::BreeLand, 3018, 3);
+
+        assert_eq!(rolls.len(), 3);
+        assert_ne!(rolls[0].text, rolls[1].text);
+        assert_ne!(rolls[1].text, rolls[2].text);
+    }
+}
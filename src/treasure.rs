@@ -0,0 +1,494 @@
+/*!
+# Treasure
+
+Magical treasure found in hoards and ruins: Marvellous Artefacts and Wondrous Items whose
+Blessings aid the skills of their bearer, and Famous Weapons and Armour with Qualities and Banes
+against the servants of the Enemy. Every item has a name, a history and a folk who made it, and
+the more potent ones draw the attention of the Eye when carried.
+*/
+
+use rand::{
+    Rng,
+    distr::{Distribution, StandardUniform},
+    seq::{IndexedRandom, IteratorRandom},
+};
+use serde::{Deserialize, Serialize};
+use strum::{EnumIter, IntoEnumIterator};
+use utoipa::ToSchema;
+
+use crate::{combat::adversaries::AdversaryType, cultures::HeroicCulture};
+
+pub mod hoards;
+
+/// Kind of magical treasure
+#[derive(
+    Clone, Copy, Debug, Deserialize, strum::Display, EnumIter, Eq, PartialEq, Serialize, ToSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "title_case")]
+pub enum MagicalItemKind {
+    /// A finely made object with a single Blessing
+    MarvellousArtefact,
+    /// An object of great power with two Blessings
+    WondrousItem,
+    /// A weapon of legend, with Qualities and a Bane against a kind of foe
+    FamousWeapon,
+    /// Armour of legend, with Qualities protecting its wearer
+    FamousArmour,
+}
+
+impl MagicalItemKind {
+    /// Eye Awareness added at the start of an Adventuring phase for every item carried.
+    /// Marvellous Artefacts are too humble to draw the Enemy's gaze.
+    #[must_use]
+    pub fn eye_awareness(self) -> u32 {
+        match self {
+            MagicalItemKind::MarvellousArtefact => 0,
+            MagicalItemKind::WondrousItem
+            | MagicalItemKind::FamousWeapon
+            | MagicalItemKind::FamousArmour => 1,
+        }
+    }
+}
+
+impl Distribution<MagicalItemKind> for StandardUniform {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> MagicalItemKind {
+        // The more potent the treasure, the rarer it is
+        match rng.random_range(0..12) {
+            0..=5 => MagicalItemKind::MarvellousArtefact,
+            6..=8 => MagicalItemKind::WondrousItem,
+            9 | 10 => MagicalItemKind::FamousWeapon,
+            _ => MagicalItemKind::FamousArmour,
+        }
+    }
+}
+
+/// Folk whose craft the item is
+#[derive(
+    Clone, Copy, Debug, Deserialize, strum::Display, EnumIter, Eq, PartialEq, Serialize, ToSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "title_case")]
+pub enum Origin {
+    /// Forged by the Dwarves of Khazad-dûm and the Blue Mountains
+    Dwarven,
+    /// Wrought by the Noldor of Eregion or the Elves of Lindon
+    Elven,
+    /// Made by the Men of Westernesse for the kings of Arnor
+    Numenorean,
+    /// Crafted by the Northmen of Dale and the Vales of Anduin
+    Northern,
+}
+
+impl Origin {
+    /// Culture whose names are given to the makers and bearers of the item
+    #[must_use]
+    pub fn culture(self) -> HeroicCulture {
+        match self {
+            Origin::Dwarven => HeroicCulture::DwarvesOfDurinsFolk,
+            Origin::Elven => HeroicCulture::ElvesOfLindon,
+            Origin::Numenorean => HeroicCulture::RangersOfTheNorth,
+            Origin::Northern => HeroicCulture::Bardings,
+        }
+    }
+
+    /// Weapons the folk is famous for forging
+    fn weapons(self) -> &'static [&'static str] {
+        match self {
+            Origin::Dwarven => &["axe", "great axe", "mattock", "short sword"],
+            Origin::Elven => &["sword", "long sword", "bow", "spear", "dagger"],
+            Origin::Numenorean => &["sword", "long sword", "dagger", "great spear"],
+            Origin::Northern => &["sword", "axe", "spear", "great bow"],
+        }
+    }
+
+    /// Foes the folk forged weapons against
+    fn foes(self) -> &'static [AdversaryType] {
+        match self {
+            Origin::Dwarven => &[AdversaryType::Orcs, AdversaryType::Trolls],
+            Origin::Elven => &[
+                AdversaryType::Orcs,
+                AdversaryType::Undead,
+                AdversaryType::Wolves,
+            ],
+            Origin::Numenorean => &[
+                AdversaryType::Undead,
+                AdversaryType::Orcs,
+                AdversaryType::EvilMen,
+            ],
+            Origin::Northern => &[
+                AdversaryType::Wolves,
+                AdversaryType::Orcs,
+                AdversaryType::EvilMen,
+            ],
+        }
+    }
+
+    /// Beginnings and endings of names in the folk's tongue
+    fn name_parts(self) -> (&'static [&'static str], &'static [&'static str]) {
+        match self {
+            Origin::Dwarven => (
+                &["Bar", "Khel", "Gund", "Naug", "Azan", "Tharkun", "Zigil"],
+                &["abad", "grim", "bizar", "zirak", "hul", "dum"],
+            ),
+            Origin::Elven => (
+                &[
+                    "Aeg", "Glam", "Gwaeth", "Hadh", "Lin", "Ring", "Thal", "Mith",
+                ],
+                &["rist", "dring", "ost", "hir", "agor", "loth", "ion"],
+            ),
+            Origin::Numenorean => (
+                &["Anar", "Elen", "Isil", "Tar", "Val", "Aran", "Ond"],
+                &["andil", "acar", "dur", "quen", "orn", "ion"],
+            ),
+            Origin::Northern => (
+                &["Wolf", "Storm", "Grim", "Gold", "Raven", "Iron", "Ash"],
+                &["brand", "biter", "fang", "helm", "song", "ward"],
+            ),
+        }
+    }
+
+    /// When and where the folk made the item
+    fn eras(self) -> &'static [&'static str] {
+        match self {
+            Origin::Dwarven => &[
+                "in Khazad-dûm before the Balrog woke",
+                "in the smithies of Nogrod in the Blue Mountains",
+                "in the halls of Erebor before the coming of Smaug",
+            ],
+            Origin::Elven => &[
+                "by the Noldor of Ost-in-Edhil in the Second Age",
+                "in Gondolin, and carried east after its fall",
+                "by the smiths of Lindon under the eyes of Círdan",
+            ],
+            Origin::Numenorean => &[
+                "for the kings of Arnor at Annúminas",
+                "in the smithies of Fornost during the war with Angmar",
+                "in Westernesse, and brought over the Sea by Elendil's folk",
+            ],
+            Origin::Northern => &[
+                "in Dale in the days of King Girion",
+                "by the Northmen of the Vales of Anduin",
+                "by the Éothéod before they rode south",
+            ],
+        }
+    }
+}
+
+impl Distribution<Origin> for StandardUniform {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Origin {
+        Origin::iter().choose(rng).unwrap()
+    }
+}
+
+/// A skill a Blessing grants a bonus Success die to
+#[derive(
+    Clone, Copy, Debug, Deserialize, strum::Display, EnumIter, Eq, PartialEq, Serialize, ToSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "UPPERCASE")]
+pub enum Skill {
+    Awe,
+    Athletics,
+    Awareness,
+    Hunting,
+    Song,
+    Craft,
+    Enhearten,
+    Travel,
+    Insight,
+    Healing,
+    Courtesy,
+    Battle,
+    Persuade,
+    Stealth,
+    Scan,
+    Explore,
+    Riddle,
+    Lore,
+}
+
+impl Skill {
+    /// Objects that can carry a Blessing of the skill
+    fn forms(self) -> &'static [&'static str] {
+        match self {
+            Skill::Awe => &["circlet", "brooch", "banner"],
+            Skill::Athletics => &["belt", "pair of boots"],
+            Skill::Awareness => &["lantern", "hood", "ring"],
+            Skill::Hunting => &["hunting horn", "quiver"],
+            Skill::Song => &["harp", "flute", "horn"],
+            Skill::Craft => &["hammer", "set of tools"],
+            Skill::Enhearten => &["banner", "horn", "pendant"],
+            Skill::Travel => &["staff", "cloak", "pair of boots"],
+            Skill::Insight => &["ring", "mirror"],
+            Skill::Healing => &["phial", "herb-pouch"],
+            Skill::Courtesy => &["cloak-pin", "ring"],
+            Skill::Battle => &["war-horn", "banner"],
+            Skill::Persuade => &["pendant", "ring"],
+            Skill::Stealth => &["cloak", "hood"],
+            Skill::Scan => &["crystal", "helm"],
+            Skill::Explore => &["map", "lamp"],
+            Skill::Riddle => &["book", "key"],
+            Skill::Lore => &["book", "scroll"],
+        }
+    }
+}
+
+/// A special property of a Famous Weapon or Armour
+#[derive(
+    Clone, Copy, Debug, Deserialize, strum::Display, EnumIter, Eq, PartialEq, Serialize, ToSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "title_case")]
+pub enum Quality {
+    /// Armour: lighter than it looks, reducing its Load
+    CunningMake,
+    /// Armour: fits so well it adds to Protection rolls
+    CloseFitting,
+    /// Shields: cannot be smashed by a Piercing Blow
+    Reinforced,
+    /// Weapons: adds to the Injury rating
+    Fell,
+    /// Weapons: adds to the Damage rating
+    Grievous,
+    /// Weapons: scores Piercing Blows more easily
+    Keen,
+    /// Glows with a pale light when servants of the Enemy are near
+    Luminescence,
+}
+
+/// Qualities a weapon can have
+const WEAPON_QUALITIES: &[Quality] = &[
+    Quality::Fell,
+    Quality::Grievous,
+    Quality::Keen,
+    Quality::Luminescence,
+];
+
+/// Qualities a piece of armour can have. Only shields can be Reinforced.
+const ARMOUR_QUALITIES: &[Quality] = &[
+    Quality::CunningMake,
+    Quality::CloseFitting,
+    Quality::Luminescence,
+];
+
+/// Pieces of armour that can be famous
+const ARMOUR: &[&str] = &["mail-shirt", "coat of mail", "helm", "shield"];
+
+/// What became of an item before it was found
+const FATES: &[&str] = &[
+    "It was lost when its bearer fell in battle against the Witch-king.",
+    "It was buried with its last owner in a barrow, and forgotten.",
+    "It lay for long years in a troll-hoard, under dust and bones.",
+    "It was carried into the Wild by a wanderer who never came back.",
+    "It was stolen by Orcs and passed from hand to hand among them.",
+    "It was hidden away when its folk fled, waiting for a worthy bearer.",
+];
+
+/// A piece of magical treasure
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct MagicalItem {
+    name: String,
+    kind: MagicalItemKind,
+    origin: Origin,
+    /// What the item looks like
+    form: String,
+    history: String,
+    /// Skills the item grants its bearer a bonus Success die to
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    blessings: Vec<Skill>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    qualities: Vec<Quality>,
+    /// Foes a Famous Weapon is especially deadly against
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    banes: Vec<AdversaryType>,
+}
+
+impl MagicalItem {
+    /// Generate an item of the given kind, made by the given folk
+    pub fn random<R: Rng + ?Sized>(rng: &mut R, kind: MagicalItemKind, origin: Origin) -> Self {
+        let maker = origin.culture().random_name(rng);
+        let (blessings, qualities, banes, form) = match kind {
+            MagicalItemKind::MarvellousArtefact | MagicalItemKind::WondrousItem => {
+                let count = if kind == MagicalItemKind::WondrousItem {
+                    2
+                } else {
+                    1
+                };
+                let blessings = Skill::iter().choose_multiple(rng, count);
+                let form = *blessings[0].forms().choose(rng).unwrap();
+                (blessings, vec![], vec![], form)
+            }
+            MagicalItemKind::FamousWeapon => {
+                let count = rng.random_range(1..=2);
+                let qualities = WEAPON_QUALITIES.choose_multiple(rng, count).copied();
+                let banes = vec![*origin.foes().choose(rng).unwrap()];
+                let form = *origin.weapons().choose(rng).unwrap();
+                (vec![], qualities.collect(), banes, form)
+            }
+            MagicalItemKind::FamousArmour => {
+                let form = *ARMOUR.choose(rng).unwrap();
+                let count = rng.random_range(1..=2);
+                let mut qualities = ARMOUR_QUALITIES
+                    .choose_multiple(rng, count)
+                    .copied()
+                    .collect::<Vec<_>>();
+                if form == "shield" {
+                    qualities.push(Quality::Reinforced);
+                }
+                (vec![], qualities, vec![], form)
+            }
+        };
+
+        Self {
+            name: name(rng, kind, origin, form, &maker, banes.first().copied()),
+            history: format!(
+                "Made by {maker} {}. {}",
+                origin.eras().choose(rng).unwrap(),
+                FATES.choose(rng).unwrap()
+            ),
+            kind,
+            origin,
+            form: form.to_owned(),
+            blessings,
+            qualities,
+            banes,
+        }
+    }
+
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Eye Awareness the item adds when carried at the start of an Adventuring phase
+    #[must_use]
+    pub fn eye_awareness(&self) -> u32 {
+        self.kind.eye_awareness()
+    }
+}
+
+impl Distribution<MagicalItem> for StandardUniform {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> MagicalItem {
+        let kind = rng.random();
+        let origin = rng.random();
+        MagicalItem::random(rng, kind, origin)
+    }
+}
+
+/// Name an item. Famous Weapons and Armour are given a name of their own in the tongue of their
+/// makers, while lesser treasure is known by the name of its maker.
+fn name<R: Rng + ?Sized>(
+    rng: &mut R,
+    kind: MagicalItemKind,
+    origin: Origin,
+    form: &str,
+    maker: &str,
+    bane: Option<AdversaryType>,
+) -> String {
+    let (beginnings, endings) = origin.name_parts();
+    let proper = format!(
+        "{}{}",
+        beginnings.choose(rng).unwrap(),
+        endings.choose(rng).unwrap()
+    );
+
+    match kind {
+        MagicalItemKind::MarvellousArtefact | MagicalItemKind::WondrousItem => {
+            let maker = maker.split(' ').next().unwrap_or(maker);
+            let mut form = form
+                .trim_start_matches("pair of ")
+                .trim_start_matches("set of ")
+                .to_owned();
+            if let Some(first) = form.get(..1) {
+                form.replace_range(..1, &first.to_uppercase());
+            }
+            format!("{maker}'s {form}")
+        }
+        MagicalItemKind::FamousWeapon => {
+            let epithet = match bane {
+                Some(AdversaryType::Orcs) => "Goblin-cleaver",
+                Some(AdversaryType::Trolls) => "Troll-bane",
+                Some(AdversaryType::Wolves) => "Wolf-slayer",
+                Some(AdversaryType::Undead) => "Wight-bane",
+                Some(AdversaryType::EvilMen) => "Oath-keeper",
+                None => "Foe-hammer",
+            };
+            format!("{proper}, the {epithet}")
+        }
+        MagicalItemKind::FamousArmour => {
+            let epithet = ["Unyielding", "Star-bright", "Unbroken", "Shadow-proof"]
+                .choose(rng)
+                .unwrap();
+            format!("{proper}, the {epithet}")
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rand::rng_from_seed;
+
+    use super::*;
+
+    #[test]
+    fn items_get_enchantments_of_their_kind() {
+        let mut rng = rng_from_seed(2965);
+        for kind in MagicalItemKind::iter() {
+            for origin in Origin::iter() {
+                let item = MagicalItem::random(&mut rng, kind, origin);
+
+                assert!(!item.name.is_empty());
+                assert!(item.history.starts_with("Made by"));
+                match kind {
+                    MagicalItemKind::MarvellousArtefact => assert_eq!(item.blessings.len(), 1),
+                    MagicalItemKind::WondrousItem => assert_eq!(item.blessings.len(), 2),
+                    MagicalItemKind::FamousWeapon => {
+                        assert!(item.qualities.iter().all(|q| WEAPON_QUALITIES.contains(q)));
+                        assert!(origin.foes().contains(&item.banes[0]));
+                        assert!(origin.weapons().contains(&item.form.as_str()));
+                    }
+                    MagicalItemKind::FamousArmour => {
+                        assert!(!item.qualities.is_empty());
+                        assert!(item.banes.is_empty());
+                        assert_eq!(
+                            item.qualities.contains(&Quality::Reinforced),
+                            item.form == "shield"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn lesser_treasure_is_named_after_its_maker() {
+        let item = MagicalItem::random(
+            &mut rng_from_seed(1),
+            MagicalItemKind::MarvellousArtefact,
+            Origin::Northern,
+        );
+
+        assert!(item.name.contains("'s "));
+        assert!(
+            item.name
+                .chars()
+                .nth(item.name.find("'s ").unwrap() + 3)
+                .unwrap()
+                .is_uppercase()
+        );
+    }
+
+    #[test]
+    fn only_potent_items_draw_the_eye() {
+        let mut rng = rng_from_seed(3);
+        for _ in 0..50 {
+            let item = rng.random::<MagicalItem>();
+
+            assert_eq!(
+                item.eye_awareness(),
+                u32::from(item.kind != MagicalItemKind::MarvellousArtefact)
+            );
+        }
+    }
+}
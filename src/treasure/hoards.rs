@@ -0,0 +1,243 @@
+/*!
+# Hoards
+
+Treasure left behind in troll-holes, barrows and dragon-lairs. A hoard has a rating from 1 to 6,
+and the Feat die is rolled on the Hoard table once for every point of it: numbers yield Treasure
+points worth the die times the rating, a 10 or the Gandalf rune also turn up a magical item, and
+every Eye of Sauron lays a curse of Greed on whoever claims a share.
+*/
+
+use std::{fmt::Write, ops::RangeInclusive};
+
+use rand::{Rng, seq::IndexedRandom};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::dice::FeatDie;
+
+use super::{MagicalItem, MagicalItemKind};
+
+/// Ratings a hoard can have, from a few coins in a barrow to a dragon's bed of gold
+pub const HOARD_RATINGS: RangeInclusive<u8> = 1..=6;
+
+/// Famous items that can turn up when the Gandalf rune is rolled on the Hoard table
+const GREAT_TREASURE: &[MagicalItemKind] = &[
+    MagicalItemKind::WondrousItem,
+    MagicalItemKind::FamousWeapon,
+    MagicalItemKind::FamousArmour,
+];
+
+/// The entries of the Hoard table
+#[derive(Clone, Copy, Debug, Deserialize, strum::Display, Eq, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "title_case")]
+pub enum HoardFind {
+    /// A curse lies on the gold: heroes claiming a share gain 1 Shadow point (Greed)
+    Curse,
+    /// Tarnished coins and trinkets
+    Coins,
+    /// Gems, jewellery and fine plate
+    PreciousThings,
+    /// Precious things, and a Marvellous Artefact among them
+    MarvellousArtefact,
+    /// Precious things, and an item of legend among them
+    GreatTreasure,
+}
+
+impl HoardFind {
+    /// Find for a result of the Feat die
+    #[must_use]
+    pub fn from_feat_die(feat_die: FeatDie) -> Self {
+        match feat_die {
+            FeatDie::Eye => HoardFind::Curse,
+            FeatDie::Number(1..=5) => HoardFind::Coins,
+            FeatDie::Number(10) => HoardFind::MarvellousArtefact,
+            FeatDie::Number(_) => HoardFind::PreciousThings,
+            FeatDie::Gandalf => HoardFind::GreatTreasure,
+        }
+    }
+}
+
+/// One roll on the Hoard table
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HoardRoll {
+    feat_die: FeatDie,
+    find: HoardFind,
+    /// Treasure points found: the Feat die times the hoard rating
+    treasure: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    item: Option<MagicalItem>,
+}
+
+impl HoardRoll {
+    /// Roll the Feat die on the Hoard table for a hoard of the given rating
+    pub fn roll<R: Rng + ?Sized>(rng: &mut R, rating: u8) -> Self {
+        let feat_die = rng.random::<FeatDie>();
+        let find = HoardFind::from_feat_die(feat_die);
+        let item = match find {
+            HoardFind::Curse | HoardFind::Coins | HoardFind::PreciousThings => None,
+            HoardFind::MarvellousArtefact => Some(MagicalItemKind::MarvellousArtefact),
+            HoardFind::GreatTreasure => GREAT_TREASURE.choose(rng).copied(),
+        }
+        .map(|kind| {
+            let origin = rng.random();
+            MagicalItem::random(rng, kind, origin)
+        });
+
+        Self {
+            feat_die,
+            find,
+            treasure: u32::from(feat_die.value()) * u32::from(rating),
+            item,
+        }
+    }
+}
+
+/// A hoard and everything found in it
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Hoard {
+    rating: u8,
+    rolls: Vec<HoardRoll>,
+    /// Treasure points in the whole hoard
+    treasure: u32,
+    /// Shadow points (Greed) gained by every hero claiming a share
+    shadow: u8,
+    /// The hoard described in Markdown, ready to be read out to the players
+    description: String,
+}
+
+impl Hoard {
+    /// Roll on the Hoard table once for every point of rating
+    pub fn random<R: Rng + ?Sized>(rng: &mut R, rating: u8) -> Self {
+        let rolls = (0..rating)
+            .map(|_| HoardRoll::roll(rng, rating))
+            .collect::<Vec<_>>();
+        let mut hoard = Self {
+            rating,
+            treasure: rolls.iter().map(|roll| roll.treasure).sum(),
+            shadow: u8::try_from(
+                rolls
+                    .iter()
+                    .filter(|roll| roll.find == HoardFind::Curse)
+                    .count(),
+            )
+            .unwrap_or(u8::MAX),
+            rolls,
+            description: String::new(),
+        };
+        hoard.description = hoard.describe();
+        hoard
+    }
+
+    /// Magical items found in the hoard
+    pub fn magical_items(&self) -> impl Iterator<Item = &MagicalItem> {
+        self.rolls.iter().filter_map(|roll| roll.item.as_ref())
+    }
+
+    /// Describe the hoard in Markdown
+    fn describe(&self) -> String {
+        let mut description = format!(
+            "# Hoard (rating {})\n\n**{} Treasure points**\n",
+            self.rating, self.treasure
+        );
+
+        let items = self.magical_items().collect::<Vec<_>>();
+        if !items.is_empty() {
+            description.push_str("\n## Magical treasure\n\n");
+        }
+        for item in items {
+            let _ = write!(
+                description,
+                "- **{}** ({}, {}): {}",
+                item.name, item.kind, item.form, item.history
+            );
+            let enchantments = item
+                .blessings
+                .iter()
+                .map(|skill| format!("Blessing of {skill}"))
+                .chain(item.qualities.iter().map(ToString::to_string))
+                .chain(
+                    item.banes
+                        .iter()
+                        .map(|bane| format!("Bane of {}", bane.to_string().replace('-', " "))),
+                )
+                .collect::<Vec<_>>();
+            if !enchantments.is_empty() {
+                let _ = write!(description, " *{}.*", enchantments.join(", "));
+            }
+            description.push('\n');
+        }
+
+        if self.shadow > 0 {
+            let _ = write!(
+                description,
+                "\nA curse lies upon the hoard: every hero who claims a share gains {} Shadow \
+                 {} (Greed).\n",
+                self.shadow,
+                if self.shadow == 1 { "point" } else { "points" }
+            );
+        }
+        description
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rand::rng_from_seed;
+
+    use super::*;
+
+    #[test]
+    fn rolls_once_per_rating_point() {
+        let mut rng = rng_from_seed(2965);
+        for rating in HOARD_RATINGS {
+            let hoard = Hoard::random(&mut rng, rating);
+
+            assert_eq!(hoard.rolls.len(), usize::from(rating));
+            assert_eq!(
+                hoard.treasure,
+                hoard.rolls.iter().map(|roll| roll.treasure).sum::<u32>()
+            );
+            assert!(
+                hoard
+                    .description
+                    .starts_with(&format!("# Hoard (rating {rating})"))
+            );
+        }
+    }
+
+    #[test]
+    fn finds_follow_the_feat_die() {
+        let mut rng = rng_from_seed(12);
+        for _ in 0..200 {
+            let roll = HoardRoll::roll(&mut rng, 3);
+
+            assert_eq!(roll.find, HoardFind::from_feat_die(roll.feat_die));
+            assert_eq!(roll.treasure, u32::from(roll.feat_die.value()) * 3);
+            match roll.find {
+                HoardFind::MarvellousArtefact => {
+                    assert_eq!(roll.item.unwrap().kind, MagicalItemKind::MarvellousArtefact);
+                }
+                HoardFind::GreatTreasure => {
+                    assert!(GREAT_TREASURE.contains(&roll.item.unwrap().kind));
+                }
+                HoardFind::Curse | HoardFind::Coins | HoardFind::PreciousThings => {
+                    assert!(roll.item.is_none());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn description_lists_items_and_curses() {
+        let mut rng = rng_from_seed(7);
+        for _ in 0..50 {
+            let hoard = Hoard::random(&mut rng, 6);
+
+            for item in hoard.magical_items() {
+                assert!(hoard.description.contains(&format!("**{}**", item.name)));
+            }
+            assert_eq!(hoard.description.contains("curse"), hoard.shadow > 0);
+        }
+    }
+}